@@ -9,11 +9,27 @@ pub const MAX_CORE_OPTIONS: usize = 90;
 pub const MAX_CORE_CONTROLLER_INFO_TYPES: usize = 10;
 pub const MAX_CORE_SUBSYSTEM_INFO: usize = 40;
 pub const MAX_CORE_SUBSYSTEM_ROM_INFO: usize = 40;
+#[doc = "quantidade máxima de núcleos carregados ao mesmo tempo no processo"]
+pub const MAX_CORE_SLOTS: usize = 4;
 pub const CORE_OPTION_EXTENSION_FILE: &str = "opt";
 pub const DEFAULT_MAX_PORT: usize = 2;
 pub const INVALID_CONTROLLER_PORT: i16 = -1;
 pub const SAVE_IMAGE_EXTENSION_FILE: &str = "png";
 pub const SAVE_EXTENSION_FILE: &str = "save";
+pub const SAVE_RAM_EXTENSION_FILE: &str = "srm";
+pub const CHEAT_EXTENSION_FILE: &str = "cht";
+#[doc = "candidatos da busca de cheats enviados ao listener, a contagem sempre é a total"]
+pub const MAX_REPORTED_CHEAT_CANDIDATES: usize = 100;
+pub const CONTROLLER_TYPES_EXTENSION_FILE: &str = "ctrl";
+#[doc = "intervalo em segundos entre cada gravação automática da SRAM"]
+pub const SAVE_RAM_AUTOSAVE_INTERVAL: u64 = 10;
+#[doc = "quantidade de frames entre cada snapshot do rewind"]
+pub const REWIND_SNAPSHOT_INTERVAL: usize = 2;
+pub const REWIND_BUFFER_SIZE_MB: usize = 64;
+pub const MAX_RUN_AHEAD_FRAMES: usize = 6;
+#[doc = "use 0 para avançar sem limite de velocidade"]
+pub const DEFAULT_FAST_FORWARD_RATIO: f64 = 4.0;
+pub const DEFAULT_SLOW_MOTION_RATIO: f64 = 0.5;
 
 //URLS
 pub const CORE_INFOS_URL: &str = "https://buildbot.libretro.com/assets/frontend/info.zip";
@@ -2,6 +2,8 @@ extern crate libretro_sys;
 
 pub mod constants;
 pub mod error_handle;
+pub mod logger;
 pub mod retro_paths;
+pub mod retro_settings;
 pub mod test_workdir;
 pub mod types;
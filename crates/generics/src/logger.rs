@@ -0,0 +1,253 @@
+// Log compartilhado por todos os crates do workspace.
+//
+// Cada mensagem tem um nível e um alvo. O nível mínimo de cada alvo pode ser trocado a
+// qualquer momento, e todas as mensagens aceitas vão para um único sink, que por padrão
+// escreve no stdout.
+
+use crate::error_handle::ErrorHandle;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogTarget {
+    #[doc = "mensagens enviadas pelo próprio núcleo pela log interface"]
+    Core,
+    #[doc = "chamadas do environment e das callbacks do núcleo"]
+    Env,
+    Video,
+    Audio,
+    Input,
+    Ipc,
+    #[doc = "diagnósticos do frontend que não pertencem a nenhum dos outros alvos"]
+    Frontend,
+}
+
+impl LogTarget {
+    pub const ALL: [LogTarget; 7] = [
+        LogTarget::Core,
+        LogTarget::Env,
+        LogTarget::Video,
+        LogTarget::Audio,
+        LogTarget::Input,
+        LogTarget::Ipc,
+        LogTarget::Frontend,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub target: LogTarget,
+    pub message: String,
+}
+
+pub trait LogSink: Send + Sync {
+    fn write(&self, record: &LogRecord);
+}
+
+impl<F> LogSink for F
+where
+    F: Fn(&LogRecord) + Send + Sync,
+{
+    fn write(&self, record: &LogRecord) {
+        self(record)
+    }
+}
+
+pub struct StdoutSink;
+
+impl LogSink for StdoutSink {
+    fn write(&self, record: &LogRecord) {
+        println!(
+            "[{:?}] {:?}: {}",
+            record.target, record.level, record.message
+        );
+    }
+}
+
+#[doc = "acrescenta as mensagens no fim do arquivo, uma por linha"]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, ErrorHandle> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl LogSink for FileSink {
+    fn write(&self, record: &LogRecord) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(
+                file,
+                "[{:?}] {:?}: {}",
+                record.target, record.level, record.message
+            );
+        }
+    }
+}
+
+#[doc = "envia as mensagens para outra thread, como a que escreve os eventos do IPC"]
+pub struct ChannelSink {
+    sender: Mutex<Sender<LogRecord>>,
+}
+
+impl ChannelSink {
+    pub fn new(sender: Sender<LogRecord>) -> Self {
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+}
+
+impl LogSink for ChannelSink {
+    fn write(&self, record: &LogRecord) {
+        if let Ok(sender) = self.sender.lock() {
+            let _ = sender.send(record.clone());
+        }
+    }
+}
+
+// u8::MAX desliga o alvo
+const LEVEL_OFF: u8 = u8::MAX;
+
+static SINK: RwLock<Option<Arc<dyn LogSink>>> = RwLock::new(None);
+
+// logs do núcleo costumam ser muito verbosos, por isso só avisos e erros aparecem por padrão
+static LEVELS: [AtomicU8; 7] = [
+    AtomicU8::new(LogLevel::Warn as u8),
+    AtomicU8::new(LogLevel::Warn as u8),
+    AtomicU8::new(LogLevel::Info as u8),
+    AtomicU8::new(LogLevel::Info as u8),
+    AtomicU8::new(LogLevel::Info as u8),
+    AtomicU8::new(LogLevel::Info as u8),
+    AtomicU8::new(LogLevel::Info as u8),
+];
+
+#[doc = "troca o destino de todas as mensagens, substituindo o sink anterior"]
+pub fn set_log_sink(sink: impl LogSink + 'static) {
+    let mut current = SINK.write().unwrap_or_else(|e| e.into_inner());
+    *current = Some(Arc::new(sink));
+}
+
+#[doc = "volta a escrever no stdout"]
+pub fn reset_log_sink() {
+    let mut current = SINK.write().unwrap_or_else(|e| e.into_inner());
+    *current = None;
+}
+
+#[doc = "define o nível mínimo aceito para o alvo, None desliga o alvo"]
+pub fn set_log_level(target: LogTarget, level: Option<LogLevel>) {
+    let value = level.map(|level| level as u8).unwrap_or(LEVEL_OFF);
+    LEVELS[target.index()].store(value, Ordering::Relaxed);
+}
+
+pub fn log_level(target: LogTarget) -> Option<LogLevel> {
+    match LEVELS[target.index()].load(Ordering::Relaxed) {
+        0 => Some(LogLevel::Debug),
+        1 => Some(LogLevel::Info),
+        2 => Some(LogLevel::Warn),
+        3 => Some(LogLevel::Error),
+        _ => None,
+    }
+}
+
+pub fn log_enabled(target: LogTarget, level: LogLevel) -> bool {
+    log_level(target).is_some_and(|min| level >= min)
+}
+
+pub fn log(target: LogTarget, level: LogLevel, message: impl Into<String>) {
+    if !log_enabled(target, level) {
+        return;
+    }
+
+    let record = LogRecord {
+        level,
+        target,
+        message: message.into(),
+    };
+
+    // o sink é clonado para que ele possa chamar o log de novo sem travar o lock
+    let sink = SINK.read().ok().and_then(|sink| sink.clone());
+
+    match sink {
+        Some(sink) => sink.write(&record),
+        None => StdoutSink.write(&record),
+    }
+}
+
+pub fn debug(target: LogTarget, message: impl Into<String>) {
+    log(target, LogLevel::Debug, message);
+}
+
+pub fn info(target: LogTarget, message: impl Into<String>) {
+    log(target, LogLevel::Info, message);
+}
+
+pub fn warn(target: LogTarget, message: impl Into<String>) {
+    log(target, LogLevel::Warn, message);
+}
+
+pub fn error(target: LogTarget, message: impl Into<String>) {
+    log(target, LogLevel::Error, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    // o sink e os níveis são globais, então tudo fica em um único teste
+    #[test]
+    fn test_filter_and_route_records() {
+        let (sender, receiver) = mpsc::channel();
+        set_log_sink(ChannelSink::new(sender));
+
+        set_log_level(LogTarget::Audio, Some(LogLevel::Warn));
+        info(LogTarget::Audio, "descartada");
+        error(LogTarget::Audio, "aceita");
+
+        set_log_level(LogTarget::Audio, None);
+        error(LogTarget::Audio, "alvo desligado");
+
+        let records: Vec<LogRecord> = receiver.try_iter().collect();
+        assert_eq!(
+            records,
+            vec![LogRecord {
+                level: LogLevel::Error,
+                target: LogTarget::Audio,
+                message: "aceita".to_owned(),
+            }]
+        );
+
+        assert!(!log_enabled(LogTarget::Audio, LogLevel::Error));
+        set_log_level(LogTarget::Audio, Some(LogLevel::Info));
+        assert_eq!(log_level(LogTarget::Audio), Some(LogLevel::Info));
+
+        reset_log_sink();
+    }
+}
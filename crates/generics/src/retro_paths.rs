@@ -17,6 +17,7 @@ pub struct RetroPaths {
     pub infos: Arc<String>,
     pub databases: Arc<String>,
     pub arts: Arc<String>,
+    pub cheats: Arc<String>,
 }
 
 impl PartialEq for RetroPaths {
@@ -37,6 +38,7 @@ impl RetroPaths {
         infos: String,
         databases: String,
         arts: String,
+        cheats: String,
     ) -> Result<Self, ErrorHandle> {
         if Path::new(&system).exists().not() && fs::create_dir_all(&system).is_err() {
             return Err(ErrorHandle {
@@ -92,6 +94,12 @@ impl RetroPaths {
             });
         }
 
+        if Path::new(&cheats).exists().not() && fs::create_dir_all(&cheats).is_err() {
+            return Err(ErrorHandle {
+                message: "Não foi possível criar a pasta cheats".to_owned(),
+            });
+        }
+
         Ok(Self {
             base_dir: Arc::new(base_dir),
             system: Arc::new(system),
@@ -103,6 +111,7 @@ impl RetroPaths {
             infos: Arc::new(infos),
             databases: Arc::new(databases),
             arts: Arc::new(arts),
+            cheats: Arc::new(cheats),
         })
     }
 
@@ -116,6 +125,7 @@ impl RetroPaths {
         let infos = format!("{}/infos", base);
         let databases = format!("{}/databases", base);
         let arts = format!("{}/arts", base);
+        let cheats = format!("{}/cheats", base);
 
         Self::new(
             base.to_string(),
@@ -128,6 +138,7 @@ impl RetroPaths {
             infos,
             databases,
             arts,
+            cheats,
         )
     }
 }
@@ -0,0 +1,158 @@
+use libretro_sys::binding_libretro::retro_language;
+use std::ffi::CString;
+
+/// Preferências do usuário repassadas ao núcleo pelo environment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetroSettings {
+    #[doc = "Tag BCP-47 do idioma do usuário, como `pt-BR` ou `en-US`"]
+    pub language: String,
+    #[doc = "Nome exibido pelos núcleos que suportam netplay ou perfis, vazio para não informar"]
+    pub username: String,
+    #[doc = "Comprime os dados dos save states, arquivos antigos continuam sendo lidos"]
+    pub compress_save_states: bool,
+    #[doc = "Carrega o auto save state criado ao fechar o jogo da última vez"]
+    pub load_auto_state: bool,
+}
+
+impl Default for RetroSettings {
+    fn default() -> Self {
+        Self {
+            language: "en-US".to_owned(),
+            username: String::new(),
+            compress_save_states: false,
+            load_auto_state: false,
+        }
+    }
+}
+
+impl RetroSettings {
+    pub fn new(language: impl Into<String>, username: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+            username: username.into(),
+            compress_save_states: false,
+            load_auto_state: false,
+        }
+    }
+
+    pub fn retro_language(&self) -> retro_language {
+        language_from_tag(&self.language)
+    }
+
+    #[doc = "retorna None se o nome estiver vazio ou tiver um byte nulo no meio"]
+    pub fn username_c_string(&self) -> Option<CString> {
+        if self.username.is_empty() {
+            return None;
+        }
+
+        CString::new(self.username.as_str()).ok()
+    }
+}
+
+// converte uma tag BCP-47 para o idioma do libretro, idiomas sem equivalente ficam em inglês
+pub fn language_from_tag(tag: &str) -> retro_language {
+    let tag = tag.trim().replace('_', "-").to_lowercase();
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    let rest: Vec<&str> = subtags.collect();
+    let has = |subtag: &str| rest.contains(&subtag);
+
+    match primary {
+        "en" if has("gb") || has("uk") => retro_language::RETRO_LANGUAGE_BRITISH_ENGLISH,
+        "ja" => retro_language::RETRO_LANGUAGE_JAPANESE,
+        "fr" => retro_language::RETRO_LANGUAGE_FRENCH,
+        "es" => retro_language::RETRO_LANGUAGE_SPANISH,
+        "de" => retro_language::RETRO_LANGUAGE_GERMAN,
+        "it" => retro_language::RETRO_LANGUAGE_ITALIAN,
+        "nl" => retro_language::RETRO_LANGUAGE_DUTCH,
+        "pt" if has("br") => retro_language::RETRO_LANGUAGE_PORTUGUESE_BRAZIL,
+        "pt" => retro_language::RETRO_LANGUAGE_PORTUGUESE_PORTUGAL,
+        "ru" => retro_language::RETRO_LANGUAGE_RUSSIAN,
+        "ko" => retro_language::RETRO_LANGUAGE_KOREAN,
+        "zh" if has("hant") || has("tw") || has("hk") || has("mo") => {
+            retro_language::RETRO_LANGUAGE_CHINESE_TRADITIONAL
+        }
+        "zh" => retro_language::RETRO_LANGUAGE_CHINESE_SIMPLIFIED,
+        "eo" => retro_language::RETRO_LANGUAGE_ESPERANTO,
+        "pl" => retro_language::RETRO_LANGUAGE_POLISH,
+        "vi" => retro_language::RETRO_LANGUAGE_VIETNAMESE,
+        "ar" => retro_language::RETRO_LANGUAGE_ARABIC,
+        "el" => retro_language::RETRO_LANGUAGE_GREEK,
+        "tr" => retro_language::RETRO_LANGUAGE_TURKISH,
+        "sk" => retro_language::RETRO_LANGUAGE_SLOVAK,
+        "fa" => retro_language::RETRO_LANGUAGE_PERSIAN,
+        "he" | "iw" => retro_language::RETRO_LANGUAGE_HEBREW,
+        "ast" => retro_language::RETRO_LANGUAGE_ASTURIAN,
+        "fi" => retro_language::RETRO_LANGUAGE_FINNISH,
+        "id" | "in" => retro_language::RETRO_LANGUAGE_INDONESIAN,
+        "sv" => retro_language::RETRO_LANGUAGE_SWEDISH,
+        "uk" => retro_language::RETRO_LANGUAGE_UKRAINIAN,
+        "cs" => retro_language::RETRO_LANGUAGE_CZECH,
+        "ca" if has("valencia") => retro_language::RETRO_LANGUAGE_CATALAN_VALENCIA,
+        "ca" => retro_language::RETRO_LANGUAGE_CATALAN,
+        "hu" => retro_language::RETRO_LANGUAGE_HUNGARIAN,
+        "be" => retro_language::RETRO_LANGUAGE_BELARUSIAN,
+        _ => retro_language::RETRO_LANGUAGE_ENGLISH,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_tag() {
+        assert_eq!(
+            language_from_tag("pt-BR"),
+            retro_language::RETRO_LANGUAGE_PORTUGUESE_BRAZIL
+        );
+        assert_eq!(
+            language_from_tag("pt"),
+            retro_language::RETRO_LANGUAGE_PORTUGUESE_PORTUGAL
+        );
+        assert_eq!(
+            language_from_tag("en_GB"),
+            retro_language::RETRO_LANGUAGE_BRITISH_ENGLISH
+        );
+        assert_eq!(
+            language_from_tag("zh-Hant-TW"),
+            retro_language::RETRO_LANGUAGE_CHINESE_TRADITIONAL
+        );
+        assert_eq!(
+            language_from_tag("zh-CN"),
+            retro_language::RETRO_LANGUAGE_CHINESE_SIMPLIFIED
+        );
+        assert_eq!(
+            language_from_tag("ca-ES-valencia"),
+            retro_language::RETRO_LANGUAGE_CATALAN_VALENCIA
+        );
+        assert_eq!(
+            language_from_tag("ja-JP"),
+            retro_language::RETRO_LANGUAGE_JAPANESE
+        );
+
+        // idiomas desconhecidos ou tags inválidas caem no inglês
+        assert_eq!(
+            language_from_tag("sw-KE"),
+            retro_language::RETRO_LANGUAGE_ENGLISH
+        );
+        assert_eq!(
+            language_from_tag(""),
+            retro_language::RETRO_LANGUAGE_ENGLISH
+        );
+    }
+
+    #[test]
+    fn test_username_c_string() {
+        assert!(RetroSettings::default().username_c_string().is_none());
+        assert!(
+            RetroSettings::new("en", "a\0b")
+                .username_c_string()
+                .is_none()
+        );
+        assert_eq!(
+            RetroSettings::new("en", "tinic").username_c_string(),
+            Some(CString::new("tinic").unwrap())
+        );
+    }
+}
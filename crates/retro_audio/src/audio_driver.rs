@@ -1,5 +1,6 @@
 use crate::{
     audio_resample::AudioResample,
+    audio_speed::AudioSpeed,
     audios::{AudioMetadata, BufferCons, BufferProd},
 };
 use cpal::{
@@ -8,6 +9,7 @@ use cpal::{
 };
 use generics::{
     error_handle::ErrorHandle,
+    logger::{self, LogTarget},
     types::{ArcTMutex, TMutex},
 };
 use retro_core::av_info::AvInfo;
@@ -24,6 +26,7 @@ pub struct AudioDriver {
     pub resampler: AudioResample,
     // so existe se não for necessário fazer o resample!
     front_prod_buffer: ArcTMutex<Option<BufferProd>>,
+    speed: ArcTMutex<AudioSpeed>,
 }
 
 impl AudioDriver {
@@ -32,6 +35,7 @@ impl AudioDriver {
             stream: TMutex::new(None),
             resampler: AudioResample::new(),
             front_prod_buffer: TMutex::new(None),
+            speed: TMutex::new(AudioSpeed::new()),
         })
     }
 
@@ -89,7 +93,31 @@ impl AudioDriver {
         self.front_prod_buffer.store(None);
     }
 
+    pub fn set_speed(&self, ratio: Option<f64>) {
+        self.speed.load_or(AudioSpeed::new()).set_ratio(ratio);
+    }
+
     pub fn add_sample(&self, samples: &[i16], metadata: AudioMetadata) -> Result<(), ErrorHandle> {
+        let mut speed = self
+            .speed
+            .load_or_spawn_err("Não foi possível ler a velocidade do audio")?;
+
+        if speed.is_normal() {
+            drop(speed);
+            return self.push_samples(samples, metadata);
+        }
+
+        let samples = speed.apply(samples);
+        drop(speed);
+
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        self.push_samples(&samples, metadata)
+    }
+
+    fn push_samples(&self, samples: &[i16], metadata: AudioMetadata) -> Result<(), ErrorHandle> {
         if let Some(front_buffer_prod) = &mut *self
             .front_prod_buffer
             .load_or_spawn_err("Front buffer not initialized")?
@@ -106,10 +134,7 @@ impl AudioDriver {
         let config = device.default_output_config().unwrap();
 
         let config = &config.into();
-        let error_callback = |_err| {
-            #[cfg(feature = "debug-logs")]
-            eprintln!("erro no stream {_err}")
-        };
+        let error_callback = |err| logger::warn(LogTarget::Audio, format!("erro no stream {err}"));
         let timeout = Some(Duration::from_millis(2));
         let data_callback = move |front: &mut [i16], _: &cpal::OutputCallbackInfo| {
             if cons.is_empty() {
@@ -0,0 +1,78 @@
+// o núcleo sempre envia amostras estéreo intercaladas
+const CHANNELS: usize = 2;
+
+/// Adapta o áudio do núcleo à velocidade atual do jogo.
+///
+/// Fora de 1x o núcleo gera mais (ou menos) amostras por segundo do que a placa de som
+/// consome, então frames são descartados no fast-forward e repetidos no slow-motion. Isso
+/// mantém o buffer estável, o tom muda junto com a velocidade como nos consoles reais.
+/// Sem limite de velocidade o áudio é descartado por completo.
+#[derive(Debug)]
+pub struct AudioSpeed {
+    ratio: Option<f64>,
+    phase: f64,
+}
+
+impl AudioSpeed {
+    pub fn new() -> Self {
+        Self {
+            ratio: Some(1.0),
+            phase: 0.0,
+        }
+    }
+
+    pub fn set_ratio(&mut self, ratio: Option<f64>) {
+        let ratio = ratio.filter(|ratio| *ratio > 0.0);
+
+        if ratio != self.ratio {
+            self.ratio = ratio;
+            self.phase = 0.0;
+        }
+    }
+
+    pub fn is_normal(&self) -> bool {
+        self.ratio == Some(1.0)
+    }
+
+    pub fn apply(&mut self, samples: &[i16]) -> Vec<i16> {
+        let ratio = match self.ratio {
+            Some(ratio) => ratio,
+            None => return Vec::new(),
+        };
+
+        let step = 1.0 / ratio;
+        let mut out = Vec::with_capacity((samples.len() as f64 * step) as usize + CHANNELS);
+
+        for frame in samples.chunks_exact(CHANNELS) {
+            self.phase += step;
+
+            while self.phase >= 1.0 {
+                out.extend_from_slice(frame);
+                self.phase -= 1.0;
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_speed_ratio() {
+        let samples: Vec<i16> = (0..16).collect();
+        let mut speed = AudioSpeed::new();
+        assert_eq!(speed.apply(&samples), samples);
+
+        speed.set_ratio(Some(4.0));
+        assert_eq!(speed.apply(&samples), vec![6, 7, 14, 15]);
+
+        speed.set_ratio(Some(0.5));
+        assert_eq!(speed.apply(&samples[..4]), vec![0, 1, 0, 1, 2, 3, 2, 3]);
+
+        speed.set_ratio(None);
+        assert!(speed.apply(&samples).is_empty());
+    }
+}
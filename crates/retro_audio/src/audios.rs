@@ -51,6 +51,13 @@ pub struct RetroAudioCb {
     drive: Arc<AudioDriver>,
 }
 
+impl RetroAudioCb {
+    #[doc = "multiplicador da velocidade do jogo, use None quando a velocidade não tiver limite"]
+    pub fn set_speed(&self, ratio: Option<f64>) {
+        self.drive.set_speed(ratio);
+    }
+}
+
 impl RetroAudioEnvCallbacks for RetroAudioCb {
     fn audio_sample_callback(
         &self,
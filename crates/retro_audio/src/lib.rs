@@ -4,6 +4,7 @@ extern crate rubato;
 
 mod audio_driver;
 mod audio_resample;
+mod audio_speed;
 mod audios;
 
-pub use audios::{AudioMetadata, RetroAudio};
+pub use audios::{AudioMetadata, RetroAudio, RetroAudioCb};
@@ -4,6 +4,7 @@ use crate::keyboard::Keyboard;
 use generics::{
     constants::DEFAULT_MAX_PORT,
     error_handle::ErrorHandle,
+    logger::{self, LogTarget},
     types::{ArcTMutex, TMutex},
 };
 use gilrs::Gilrs;
@@ -185,7 +186,7 @@ impl DevicesManager {
     }
 
     pub fn apply_rumble(&self, rubble: DeviceRubble) -> bool {
-        println!("{:?}", rubble);
+        logger::debug(LogTarget::Input, format!("{rubble:?}"));
         true
     }
 }
@@ -7,7 +7,7 @@ use libretro_sys::binding_libretro::{
     retro_system_av_info,
     retro_system_timing, LibretroRaw,
 };
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
 
 #[derive(Default, Debug)]
@@ -59,10 +59,57 @@ impl Default for Video {
     }
 }
 
+#[derive(Debug)]
+pub struct AvOutput {
+    #[doc = "Quando falso, os frames enviados pelo núcleo são descartados."]
+    pub video: AtomicBool,
+    #[doc = "Quando falso, as amostras de áudio enviadas pelo núcleo são descartadas."]
+    pub audio: AtomicBool,
+    #[doc = "Avisa o núcleo que o próximo retro_serialize é apenas para uso interno do frontend."]
+    pub fast_savestates: AtomicBool,
+}
+
+impl Default for AvOutput {
+    fn default() -> Self {
+        Self {
+            video: AtomicBool::new(true),
+            audio: AtomicBool::new(true),
+            fast_savestates: AtomicBool::new(false),
+        }
+    }
+}
+
+impl AvOutput {
+    pub fn set(&self, video: bool, audio: bool) {
+        self.video.store(video, Ordering::SeqCst);
+        self.audio.store(audio, Ordering::SeqCst);
+    }
+
+    #[doc = "Valor esperado por RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE"]
+    pub fn as_env_flags(&self) -> u32 {
+        let mut flags = 0;
+
+        if self.video.load(Ordering::SeqCst) {
+            flags |= 1 << 0;
+        }
+
+        if self.audio.load(Ordering::SeqCst) {
+            flags |= 1 << 1;
+        }
+
+        if self.fast_savestates.load(Ordering::SeqCst) {
+            flags |= 1 << 2;
+        }
+
+        flags
+    }
+}
+
 #[derive(Debug)]
 pub struct AvInfo {
     pub video: Video,
     pub timing: Timing,
+    pub output: AvOutput,
 }
 
 impl AvInfo {
@@ -73,6 +120,7 @@ impl AvInfo {
                 ..Default::default()
             },
             timing: Timing::default(),
+            output: AvOutput::default(),
         }
     }
 
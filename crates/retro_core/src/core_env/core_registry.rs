@@ -0,0 +1,175 @@
+#[cfg(feature = "hw")]
+use crate::core_env::env_video::{
+    context_destroy, context_reset, get_current_frame_buffer, get_proc_address,
+};
+use crate::{
+    core_env::{
+        env_gamepads_io::{input_poll_callback, input_state_callback, rumble_callback},
+        env_video::{audio_sample_batch_callback, audio_sample_callback, video_refresh_callback},
+        environment::core_environment,
+    },
+    retro_core::RetroCore,
+};
+use generics::{constants::MAX_CORE_SLOTS, error_handle::ErrorHandle};
+use libretro_sys::binding_libretro::{
+    retro_audio_sample_batch_t, retro_audio_sample_t, retro_environment_t, retro_input_poll_t,
+    retro_input_state_t, retro_set_rumble_state_t, retro_video_refresh_t,
+};
+#[cfg(feature = "hw")]
+use libretro_sys::binding_libretro::{
+    retro_hw_context_reset_t, retro_hw_get_current_framebuffer_t, retro_hw_get_proc_address_t,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, Weak},
+};
+
+// Os callbacks da libretro não recebem nenhum ponteiro de contexto, então cada slot possui
+// sua própria cópia das funções `extern "C"`, é assim que sabemos de qual núcleo veio a chamada.
+pub(crate) struct SlotCallbacks {
+    pub environment: retro_environment_t,
+    pub audio_sample: retro_audio_sample_t,
+    pub audio_sample_batch: retro_audio_sample_batch_t,
+    pub video_refresh: retro_video_refresh_t,
+    pub input_poll: retro_input_poll_t,
+    pub input_state: retro_input_state_t,
+    pub rumble: retro_set_rumble_state_t,
+    #[cfg(feature = "hw")]
+    pub context_reset: retro_hw_context_reset_t,
+    #[cfg(feature = "hw")]
+    pub context_destroy: retro_hw_context_reset_t,
+    #[cfg(feature = "hw")]
+    pub get_current_framebuffer: retro_hw_get_current_framebuffer_t,
+    #[cfg(feature = "hw")]
+    pub get_proc_address: retro_hw_get_proc_address_t,
+}
+
+const fn create_slot_callbacks<const SLOT: usize>() -> SlotCallbacks {
+    SlotCallbacks {
+        environment: Some(core_environment::<SLOT>),
+        audio_sample: Some(audio_sample_callback::<SLOT>),
+        audio_sample_batch: Some(audio_sample_batch_callback::<SLOT>),
+        video_refresh: Some(video_refresh_callback::<SLOT>),
+        input_poll: Some(input_poll_callback::<SLOT>),
+        input_state: Some(input_state_callback::<SLOT>),
+        rumble: Some(rumble_callback::<SLOT>),
+        #[cfg(feature = "hw")]
+        context_reset: Some(context_reset::<SLOT>),
+        #[cfg(feature = "hw")]
+        context_destroy: Some(context_destroy::<SLOT>),
+        #[cfg(feature = "hw")]
+        get_current_framebuffer: Some(get_current_frame_buffer::<SLOT>),
+        #[cfg(feature = "hw")]
+        get_proc_address: Some(get_proc_address::<SLOT>),
+    }
+}
+
+// o tamanho precisa acompanhar MAX_CORE_SLOTS, o compilador avisa se faltar algum slot
+static SLOT_CALLBACKS: [SlotCallbacks; MAX_CORE_SLOTS] = [
+    create_slot_callbacks::<0>(),
+    create_slot_callbacks::<1>(),
+    create_slot_callbacks::<2>(),
+    create_slot_callbacks::<3>(),
+];
+
+struct CoreSlot {
+    library: PathBuf,
+    core: Weak<RetroCore>,
+}
+
+#[doc = "pelo amor de deus MANTENHA isso dentro desse diretório"]
+static CORE_SLOTS: RwLock<[Option<CoreSlot>; MAX_CORE_SLOTS]> =
+    RwLock::new([const { None }; MAX_CORE_SLOTS]);
+
+pub(crate) fn slot_callbacks(slot: usize) -> &'static SlotCallbacks {
+    &SLOT_CALLBACKS[slot]
+}
+
+/// Reserva um slot para o núcleo em `library`.
+///
+/// Abrir a mesma biblioteca duas vezes devolve o mesmo handle do sistema, ou seja, as duas
+/// instâncias dividiriam o estado global do núcleo. Por isso cada biblioteca só pode estar em
+/// um slot por vez.
+pub(crate) fn reserve_core_slot(library: &Path) -> Result<usize, ErrorHandle> {
+    let library = library
+        .canonicalize()
+        .unwrap_or_else(|_| library.to_path_buf());
+
+    let mut slots = CORE_SLOTS.write()?;
+
+    if slots.iter().flatten().any(|slot| slot.library == library) {
+        return Err(ErrorHandle::new(&format!(
+            "O núcleo {} já está sendo usado por outra instância",
+            library.display()
+        )));
+    }
+
+    let (index, free_slot) = slots
+        .iter_mut()
+        .enumerate()
+        .find(|(_, slot)| slot.is_none())
+        .ok_or_else(|| {
+            ErrorHandle::new(&format!(
+                "Não é possível carregar mais de {MAX_CORE_SLOTS} núcleos ao mesmo tempo"
+            ))
+        })?;
+
+    free_slot.replace(CoreSlot {
+        library,
+        core: Weak::new(),
+    });
+
+    Ok(index)
+}
+
+pub(crate) fn attach_core(slot: usize, core: &Arc<RetroCore>) {
+    if let Ok(mut slots) = CORE_SLOTS.write()
+        && let Some(core_slot) = &mut slots[slot]
+    {
+        core_slot.core = Arc::downgrade(core);
+    }
+}
+
+// depois do retro_deinit o núcleo não deve receber mais nenhum callback, mas a biblioteca
+// continua aberta até o RetroCore ser destruído, então o slot continua reservado
+pub(crate) fn detach_core(slot: usize) {
+    if let Ok(mut slots) = CORE_SLOTS.write()
+        && let Some(core_slot) = &mut slots[slot]
+    {
+        core_slot.core = Weak::new();
+    }
+}
+
+pub(crate) fn release_core_slot(slot: usize) {
+    if let Ok(mut slots) = CORE_SLOTS.write() {
+        slots[slot] = None;
+    }
+}
+
+pub(crate) fn get_core(slot: usize) -> Option<Arc<RetroCore>> {
+    let slots = CORE_SLOTS.read().ok()?;
+
+    slots.get(slot)?.as_ref()?.core.upgrade()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_and_release_slots() {
+        let first = reserve_core_slot(Path::new("/registry_test/first_libretro.so")).unwrap();
+        let second = reserve_core_slot(Path::new("/registry_test/second_libretro.so")).unwrap();
+        assert_ne!(first, second);
+
+        // a mesma biblioteca não pode ocupar dois slots
+        assert!(reserve_core_slot(Path::new("/registry_test/first_libretro.so")).is_err());
+        assert!(get_core(first).is_none());
+
+        release_core_slot(first);
+        let again = reserve_core_slot(Path::new("/registry_test/first_libretro.so")).unwrap();
+
+        release_core_slot(again);
+        release_core_slot(second);
+    }
+}
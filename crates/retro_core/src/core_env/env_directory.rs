@@ -1,6 +1,8 @@
+use crate::vfs::vfs_interface::configure_vfs_interface;
 use crate::{tools::validation::InputValidator, RetroCoreIns};
 use generics::constants::MAX_CORE_SUBSYSTEM_INFO;
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 use libretro_sys::binding_libretro::retro_vfs_interface_info;
 use libretro_sys::{
     binding_libretro::{
@@ -19,8 +21,10 @@ pub unsafe fn env_cb_directory(
 ) -> Result<bool, ErrorHandle> {
     match cmd {
         RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY -> ok",
+            );
 
             let sys_dir = InputValidator::create_safe_c_string(
                 &core_ctx.paths.system,
@@ -34,8 +38,7 @@ pub unsafe fn env_cb_directory(
             Ok(true)
         }
         RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY -> ok");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY -> ok");
 
             let save_dir = InputValidator::create_safe_c_string(
                 &core_ctx.paths.save,
@@ -49,8 +52,10 @@ pub unsafe fn env_cb_directory(
             Ok(true)
         }
         RETRO_ENVIRONMENT_GET_CORE_ASSETS_DIRECTORY => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_CORE_ASSETS_DIRECTORY -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_GET_CORE_ASSETS_DIRECTORY -> ok",
+            );
 
             let assents_dir = InputValidator::create_safe_c_string(
                 &core_ctx.paths.assets,
@@ -64,8 +69,7 @@ pub unsafe fn env_cb_directory(
             Ok(true)
         }
         RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO -> OK");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO -> OK");
 
             InputValidator::validate_non_null_mut_ptr(
                 data,
@@ -80,21 +84,20 @@ pub unsafe fn env_cb_directory(
             Ok(true)
         }
         RETRO_ENVIRONMENT_GET_VFS_INTERFACE => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_VFS_INTERFACE -> OK");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_VFS_INTERFACE -> OK");
 
-            InputValidator::validate_non_null_ptr(
+            if InputValidator::validate_non_null_mut_ptr(
                 data,
                 "data in RETRO_ENVIRONMENT_GET_VFS_INTERFACE",
-            )?;
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
 
-            let vfs_interface = unsafe {
-                &mut *(data as *mut retro_vfs_interface_info)
-            };
-            
-            println!("vfs_interface: {:?}", vfs_interface);
+            let vfs_interface = unsafe { &mut *(data as *mut retro_vfs_interface_info) };
 
-            Ok(true)
+            Ok(configure_vfs_interface(vfs_interface))
         }
         _ => Ok(false),
     }
@@ -0,0 +1,80 @@
+use crate::{RetroCoreIns, tools::validation::InputValidator};
+use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
+use libretro_sys::binding_libretro::{
+    RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION,
+    RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE, RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE,
+    retro_disk_control_callback, retro_disk_control_ext_callback,
+};
+use std::{ffi::c_uint, os::raw::c_void};
+
+pub unsafe fn env_cb_disk_control(
+    core_ctx: &RetroCoreIns,
+    cmd: c_uint,
+    data: *mut c_void,
+) -> Result<bool, ErrorHandle> {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION => {
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION -> ok",
+            );
+
+            if InputValidator::validate_non_null_mut_ptr(
+                data,
+                "data in RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION",
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
+
+            unsafe {
+                *(data as *mut c_uint) = 1;
+            }
+
+            Ok(true)
+        }
+        RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE => {
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE -> ok",
+            );
+
+            if InputValidator::validate_non_null_ptr(
+                data,
+                "data in RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE",
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
+
+            let callback = unsafe { &*(data as *const retro_disk_control_callback) };
+            core_ctx.disk_control.set_interface(callback)?;
+
+            Ok(true)
+        }
+        RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE => {
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE -> ok",
+            );
+
+            if InputValidator::validate_non_null_ptr(
+                data,
+                "data in RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE",
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
+
+            let callback = unsafe { &*(data as *const retro_disk_control_ext_callback) };
+            core_ctx.disk_control.set_ext_interface(callback)?;
+
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
-    core_env::environment::CORE_CONTEXT,
+    core_env::core_registry::{get_core, slot_callbacks},
     generics::constants::MAX_CORE_CONTROLLER_INFO_TYPES,
     libretro_sys::binding_libretro::{
-        retro_controller_info, retro_rumble_effect,
+        retro_controller_info, retro_input_descriptor, retro_rumble_effect,
         retro_rumble_interface, RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
         RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE, RETRO_ENVIRONMENT_SET_CONTROLLER_INFO, RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
     },
@@ -10,18 +10,17 @@ use crate::{
     RetroCoreIns,
 };
 use generics::error_handle::ErrorHandle;
-use std::{ffi::c_uint, os::raw::c_void, ptr::addr_of};
+use generics::logger::{self, LogTarget};
+use std::{ffi::c_uint, os::raw::c_void};
 
-unsafe extern "C" fn rumble_callback(
+pub(crate) unsafe extern "C" fn rumble_callback<const SLOT: usize>(
     port: c_uint,
     effect: retro_rumble_effect,
     strength: u16,
 ) -> bool {
-    let retro_core = unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
-            Some(core_ctx) => core_ctx,
-            None => return false,
-        }
+    let retro_core = match get_core(SLOT) {
+        Some(core_ctx) => core_ctx,
+        None => return false,
     };
 
     let res = retro_core
@@ -32,38 +31,34 @@ unsafe extern "C" fn rumble_callback(
     match res {
         Ok(v) => v,
         Err(e) => {
-            println!("{:?}", e);
+            logger::error(LogTarget::Input, format!("{e:?}"));
             let _ = retro_core.de_init();
             false
         }
     }
 }
 
-pub unsafe extern "C" fn input_poll_callback() {
-    let retro_core = unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
-            Some(core_ctx) => core_ctx,
-            None => return,
-        }
+pub unsafe extern "C" fn input_poll_callback<const SLOT: usize>() {
+    let retro_core = match get_core(SLOT) {
+        Some(core_ctx) => core_ctx,
+        None => return,
     };
 
     if let Err(e) = retro_core.callbacks.controller.input_poll_callback() {
-        println!("{:?}", e);
+        logger::error(LogTarget::Input, format!("{e:?}"));
         let _ = retro_core.de_init();
     }
 }
 
-pub unsafe extern "C" fn input_state_callback(
+pub unsafe extern "C" fn input_state_callback<const SLOT: usize>(
     port: c_uint,
     device: c_uint,
     index: c_uint,
     id: c_uint,
 ) -> i16 {
-    let retro_core = unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
-            Some(core_ctx) => core_ctx,
-            None => return 0,
-        }
+    let retro_core = match get_core(SLOT) {
+        Some(core_ctx) => core_ctx,
+        None => return 0,
     };
 
     let res = retro_core.callbacks.controller.input_state_callback(
@@ -76,7 +71,7 @@ pub unsafe extern "C" fn input_state_callback(
     match res {
         Ok(v) => v,
         Err(e) => {
-            println!("{:?}", e);
+            logger::error(LogTarget::Input, format!("{e:?}"));
             let _ = retro_core.de_init();
             0
         }
@@ -90,13 +85,14 @@ pub unsafe fn env_cb_gamepad_io(
 ) -> Result<bool, ErrorHandle> {
     match cmd {
         RETRO_ENVIRONMENT_GET_INPUT_BITMASKS => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_INPUT_BITMASKS -> ok");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_INPUT_BITMASKS -> ok");
             Ok(true)
         }
         RETRO_ENVIRONMENT_SET_CONTROLLER_INFO => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_CONTROLLER_INFO -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_CONTROLLER_INFO -> ok",
+            );
 
             InputValidator::validate_non_null_mut_ptr(
                 data,
@@ -111,13 +107,31 @@ pub unsafe fn env_cb_gamepad_io(
             Ok(true)
         }
         RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS -> ok");
-            Ok(false)
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS -> ok",
+            );
+
+            if InputValidator::validate_non_null_mut_ptr(
+                data,
+                "data in RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS",
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
+
+            core_ctx
+                .system
+                .get_input_descriptors(data as *const retro_input_descriptor)?;
+
+            Ok(true)
         }
         RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE -> ok",
+            );
 
             InputValidator::validate_non_null_mut_ptr(
                 data,
@@ -125,7 +139,7 @@ pub unsafe fn env_cb_gamepad_io(
             )?;
 
             let rumble_raw = unsafe { &mut *(data as *mut retro_rumble_interface) };
-            rumble_raw.set_rumble_state = Some(rumble_callback);
+            rumble_raw.set_rumble_state = slot_callbacks(core_ctx.slot).rumble;
 
             Ok(true)
         }
@@ -1,21 +1,24 @@
 use crate::{
-    tools::{ffi_tools::get_str_from_ptr, validation::InputValidator},
     RetroCoreIns,
+    tools::{ffi_tools::get_str_from_ptr, validation::InputValidator},
 };
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 use libretro_sys::{
     binding_libretro::{
-        retro_core_option_display, retro_core_options_v2_intl,
-        retro_variable, RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION,
-        RETRO_ENVIRONMENT_GET_VARIABLE,
-        RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE,
+        RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION, RETRO_ENVIRONMENT_GET_VARIABLE,
+        RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE, RETRO_ENVIRONMENT_SET_CORE_OPTIONS,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL,
-        RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL, RETRO_ENVIRONMENT_SET_VARIABLE,
-        RETRO_ENVIRONMENT_SET_VARIABLES,
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK,
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL,
+        RETRO_ENVIRONMENT_SET_VARIABLE, RETRO_ENVIRONMENT_SET_VARIABLES,
+        retro_core_option_definition, retro_core_option_display, retro_core_options_intl,
+        retro_core_options_update_display_callback, retro_core_options_v2,
+        retro_core_options_v2_intl, retro_variable,
     },
     binding_log_interface,
 };
-use std::{ffi::c_uint, os::raw::c_void, sync::atomic::Ordering};
+use std::{ffi::c_uint, os::raw::c_void, ptr::null_mut, sync::atomic::Ordering};
 
 pub unsafe fn env_cb_option(
     core_ctx: &RetroCoreIns,
@@ -24,8 +27,10 @@ pub unsafe fn env_cb_option(
 ) -> Result<bool, ErrorHandle> {
     match cmd {
         RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION -> ok",
+            );
 
             unsafe {
                 *(data as *mut u32) = 2;
@@ -33,14 +38,66 @@ pub unsafe fn env_cb_option(
 
             Ok(true)
         }
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS => {
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_CORE_OPTIONS -> ok");
+
+            InputValidator::validate_non_null_ptr(
+                data,
+                "ptr data in RETRO_ENVIRONMENT_SET_CORE_OPTIONS",
+            )?;
+
+            let _ = core_ctx
+                .options
+                .convert_option_v1(data as *const retro_core_option_definition);
+            let _ = core_ctx.options.try_reload_pref_option();
+
+            Ok(true)
+        }
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL => {
-            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL -> ok",
+            );
 
-            Ok(false)
+            InputValidator::validate_non_null_ptr(
+                data,
+                "ptr data in RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL",
+            )?;
+
+            let options = unsafe { &*(data as *const retro_core_options_intl) };
+
+            let _ = core_ctx.options.convert_option_v1_intl(options);
+            let _ = core_ctx.options.try_reload_pref_option();
+
+            Ok(true)
+        }
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2 => {
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2 -> ok",
+            );
+
+            InputValidator::validate_non_null_ptr(
+                data,
+                "ptr data in RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2",
+            )?;
+
+            // o v2 sem tradução é o mesmo que um v2_intl só com o idioma padrão
+            let mut options = retro_core_options_v2_intl {
+                us: data as *mut retro_core_options_v2,
+                local: null_mut(),
+            };
+
+            let _ = core_ctx.options.convert_option_v2_intl(&mut options);
+            let _ = core_ctx.options.try_reload_pref_option();
+
+            Ok(true)
         }
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL -> ok",
+            );
 
             InputValidator::validate_non_null_ptr(
                 data,
@@ -55,8 +112,10 @@ pub unsafe fn env_cb_option(
             Ok(true)
         }
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY -> ok",
+            );
 
             InputValidator::validate_non_null_ptr(
                 data,
@@ -70,15 +129,27 @@ pub unsafe fn env_cb_option(
             Ok(true)
         }
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK -> need");
-            Ok(false)
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK -> ok",
+            );
+
+            // um ponteiro nulo remove a callback registrada antes
+            let callback =
+                unsafe { (data as *const retro_core_options_update_display_callback).as_ref() }
+                    .and_then(|display| display.callback);
+
+            core_ctx.options.set_display_callback(callback)?;
+
+            Ok(true)
         }
         RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE => {
-            #[cfg(feature = "core_ev_logs")]
-            println!(
-                "RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE {:?} -> ok",
-                core_ctx.options.updated_count.load(Ordering::SeqCst) > 0
+            logger::debug(
+                LogTarget::Env,
+                format!(
+                    "RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE {:?} -> ok",
+                    core_ctx.options.updated_count.load(Ordering::SeqCst) > 0
+                ),
             );
 
             unsafe {
@@ -88,18 +159,40 @@ pub unsafe fn env_cb_option(
             Ok(true)
         }
         RETRO_ENVIRONMENT_SET_VARIABLES => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_VARIABLES -> needed");
-            Ok(false)
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_VARIABLES -> ok");
+
+            InputValidator::validate_non_null_ptr(
+                data,
+                "ptr data in RETRO_ENVIRONMENT_SET_VARIABLES",
+            )?;
+
+            let _ = core_ctx
+                .options
+                .convert_variables(data as *const retro_variable);
+            let _ = core_ctx.options.try_reload_pref_option();
+
+            Ok(true)
         }
         RETRO_ENVIRONMENT_SET_VARIABLE => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_VARIABLE -> needed");
-            Ok(false)
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_VARIABLE -> ok");
+
+            // com data nulo o núcleo só quer saber se o frontend suporta esse comando
+            let variable = match unsafe { (data as *const retro_variable).as_ref() } {
+                Some(variable) => variable,
+                None => return Ok(true),
+            };
+
+            if variable.key.is_null() || variable.value.is_null() {
+                return Ok(false);
+            }
+
+            let key = get_str_from_ptr(variable.key);
+            let value = get_str_from_ptr(variable.value);
+
+            core_ctx.options.set_value_from_core(&key, &value)
         }
         RETRO_ENVIRONMENT_GET_VARIABLE => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_VARIABLE -> ok");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_VARIABLE -> ok");
 
             if InputValidator::validate_non_null_ptr(
                 data,
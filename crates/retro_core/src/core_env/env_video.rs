@@ -1,108 +1,117 @@
-use super::environment::CORE_CONTEXT;
+use super::core_registry::get_core;
+#[cfg(feature = "hw")]
+use super::core_registry::slot_callbacks;
 #[cfg(feature = "hw")]
 use crate::libretro_sys::binding_libretro::{
-    retro_hw_context_type, retro_hw_render_callback,
-    retro_proc_address_t, RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER, RETRO_ENVIRONMENT_SET_HW_RENDER,
+    RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER, RETRO_ENVIRONMENT_SET_HW_RENDER,
+    retro_hw_context_type, retro_hw_render_callback, retro_proc_address_t,
 };
 use crate::{
+    RetroCoreIns,
     libretro_sys::binding_libretro::{
-        retro_game_geometry, retro_pixel_format,
-        RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE, RETRO_ENVIRONMENT_SET_GEOMETRY, RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
+        RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE, RETRO_ENVIRONMENT_GET_FASTFORWARDING,
+        RETRO_ENVIRONMENT_GET_THROTTLE_STATE, RETRO_ENVIRONMENT_SET_GEOMETRY,
+        RETRO_ENVIRONMENT_SET_PIXEL_FORMAT, retro_game_geometry, retro_pixel_format,
+        retro_throttle_state,
     },
     tools::validation::InputValidator,
-    RetroCoreIns,
 };
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 #[cfg(feature = "hw")]
 use std::{ffi::c_char, mem};
 use std::{
     ffi::{c_uint, c_void},
-    ptr::addr_of,
+    sync::atomic::Ordering,
 };
 
-pub unsafe extern "C" fn audio_sample_callback(left: i16, right: i16) {
-    unsafe {
-        if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT)
-            && let Err(e) = core_ctx.callbacks.audio.audio_sample_callback(
-                left,
-                right,
-                core_ctx.av_info.clone(),
-            )
-        {
-            println!("{:?}", e);
-            let _ = core_ctx.de_init();
-        }
+pub unsafe extern "C" fn audio_sample_callback<const SLOT: usize>(left: i16, right: i16) {
+    if let Some(core_ctx) = &get_core(SLOT)
+        && core_ctx.av_info.output.audio.load(Ordering::SeqCst)
+        && let Err(e) =
+            core_ctx
+                .callbacks
+                .audio
+                .audio_sample_callback(left, right, core_ctx.av_info.clone())
+    {
+        logger::error(LogTarget::Audio, format!("{e:?}"));
+        let _ = core_ctx.de_init();
     }
 }
 
-pub unsafe extern "C" fn audio_sample_batch_callback(data: *const i16, frames: usize) -> usize {
-    unsafe {
-        if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT) {
-            let res = core_ctx.callbacks.audio.audio_sample_batch_callback(
-                data,
-                frames,
-                core_ctx.av_info.clone(),
-            );
+pub unsafe extern "C" fn audio_sample_batch_callback<const SLOT: usize>(
+    data: *const i16,
+    frames: usize,
+) -> usize {
+    if let Some(core_ctx) = &get_core(SLOT) {
+        // com o áudio desativado as amostras são consumidas sem chegar ao driver
+        if !core_ctx.av_info.output.audio.load(Ordering::SeqCst) {
+            return frames;
+        }
 
-            match res {
-                Ok(frames) => frames,
-                Err(e) => {
-                    println!("{:?}", e);
-                    let _ = core_ctx.de_init();
-                    0
-                }
+        let res = core_ctx.callbacks.audio.audio_sample_batch_callback(
+            data,
+            frames,
+            core_ctx.av_info.clone(),
+        );
+
+        match res {
+            Ok(frames) => frames,
+            Err(e) => {
+                logger::error(LogTarget::Audio, format!("{e:?}"));
+                let _ = core_ctx.de_init();
+                0
             }
-        } else {
-            0
         }
+    } else {
+        0
     }
 }
 
-pub unsafe extern "C" fn video_refresh_callback(
+pub unsafe extern "C" fn video_refresh_callback<const SLOT: usize>(
     data: *const c_void,
     width: std::os::raw::c_uint,
     height: std::os::raw::c_uint,
     pitch: usize,
 ) {
-    unsafe {
-        if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT)
-            && let Err(e) = core_ctx
-                .callbacks
-                .video
-                .video_refresh_callback(data, width, height, pitch)
-        {
-            println!("{:?}", e);
-            let _ = core_ctx.de_init();
-        }
+    if let Some(core_ctx) = &get_core(SLOT)
+        && core_ctx.av_info.output.video.load(Ordering::SeqCst)
+        && let Err(e) = core_ctx
+            .callbacks
+            .video
+            .video_refresh_callback(data, width, height, pitch)
+    {
+        logger::error(LogTarget::Video, format!("{e:?}"));
+        let _ = core_ctx.de_init();
     }
 }
 
 #[cfg(feature = "hw")]
-unsafe extern "C" fn get_current_frame_buffer() -> usize {
-    println!("get_current_frame_buffer");
-    unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
-            Some(core_ctx) => core_ctx
-                .av_info
-                .video
-                .graphic_api
-                .fbo
-                .read()
-                .unwrap()
-                .unwrap(),
-            None => 0,
-        }
+pub(crate) unsafe extern "C" fn get_current_frame_buffer<const SLOT: usize>() -> usize {
+    logger::debug(LogTarget::Video, "get_current_frame_buffer");
+    match &get_core(SLOT) {
+        Some(core_ctx) => core_ctx
+            .av_info
+            .video
+            .graphic_api
+            .fbo
+            .read()
+            .unwrap()
+            .unwrap(),
+        None => 0,
     }
 }
 
 //TODO: ainda preciso testar  se isso esta funcionando
 #[cfg(feature = "hw")]
-unsafe extern "C" fn get_proc_address(sym: *const c_char) -> retro_proc_address_t {
+pub(crate) unsafe extern "C" fn get_proc_address<const SLOT: usize>(
+    sym: *const c_char,
+) -> retro_proc_address_t {
     use crate::tools::ffi_tools::get_str_from_ptr;
 
-    println!("get_proc_address");
+    logger::debug(LogTarget::Video, "get_proc_address");
     unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
+        match &get_core(SLOT) {
             Some(core_ctx) => {
                 let fc_name = get_str_from_ptr(sym);
 
@@ -119,7 +128,7 @@ unsafe extern "C" fn get_proc_address(sym: *const c_char) -> retro_proc_address_
                         Some(function)
                     }
                     Err(e) => {
-                        println!("{:?}", e);
+                        logger::error(LogTarget::Video, format!("{e:?}"));
                         let _ = core_ctx.de_init();
                         None
                     }
@@ -131,36 +140,32 @@ unsafe extern "C" fn get_proc_address(sym: *const c_char) -> retro_proc_address_
 }
 
 #[cfg(feature = "hw")]
-unsafe extern "C" fn context_reset() {
-    println!("context_reset");
+pub(crate) unsafe extern "C" fn context_reset<const SLOT: usize>() {
+    logger::debug(LogTarget::Video, "context_reset");
 
-    unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
-            Some(core_ctx) => {
-                if let Err(e) = core_ctx.callbacks.video.context_reset() {
-                    println!("context_reset: {:?}", e);
-                    let _ = core_ctx.de_init();
-                }
+    match &get_core(SLOT) {
+        Some(core_ctx) => {
+            if let Err(e) = core_ctx.callbacks.video.context_reset() {
+                logger::error(LogTarget::Video, format!("context_reset: {e:?}"));
+                let _ = core_ctx.de_init();
             }
-            None => println!("context_reset: core_ctx is None"),
         }
+        None => logger::warn(LogTarget::Video, "context_reset: core_ctx is None"),
     }
 }
 
 #[cfg(feature = "hw")]
-unsafe extern "C" fn context_destroy() {
-    println!("context_destroy");
+pub(crate) unsafe extern "C" fn context_destroy<const SLOT: usize>() {
+    logger::debug(LogTarget::Video, "context_destroy");
 
-    unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
-            Some(core_ctx) => {
-                if let Err(e) = core_ctx.callbacks.video.context_destroy() {
-                    println!("context_destroy: {:?}", e);
-                    let _ = core_ctx.de_init();
-                }
+    match &get_core(SLOT) {
+        Some(core_ctx) => {
+            if let Err(e) = core_ctx.callbacks.video.context_destroy() {
+                logger::error(LogTarget::Video, format!("context_destroy: {e:?}"));
+                let _ = core_ctx.de_init();
             }
-            None => println!("context_destroy: core_ctx is None"),
         }
+        None => logger::warn(LogTarget::Video, "context_destroy: core_ctx is None"),
     }
 }
 
@@ -171,8 +176,7 @@ pub unsafe fn env_cb_av(
 ) -> Result<bool, ErrorHandle> {
     match cmd {
         RETRO_ENVIRONMENT_SET_GEOMETRY => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_GEOMETRY -> ok");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_GEOMETRY -> ok");
 
             InputValidator::validate_non_null_ptr(
                 data,
@@ -186,8 +190,7 @@ pub unsafe fn env_cb_av(
             Ok(true)
         }
         RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_PIXEL_FORMAT -> ok");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_PIXEL_FORMAT -> ok");
 
             InputValidator::validate_non_null_ptr(
                 data,
@@ -207,19 +210,67 @@ pub unsafe fn env_cb_av(
             Ok(true)
         }
         RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE -> ok");
+            logger::debug(
+                LogTarget::Env,
+                "RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE -> ok",
+            );
+
+            if InputValidator::validate_non_null_mut_ptr(
+                data,
+                "ptr data in RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE",
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
+
+            unsafe {
+                *(data as *mut u32) = core_ctx.av_info.output.as_env_flags();
+            }
+
+            Ok(true)
+        }
+        RETRO_ENVIRONMENT_GET_FASTFORWARDING => {
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_FASTFORWARDING -> ok");
+
+            if InputValidator::validate_non_null_mut_ptr(
+                data,
+                "ptr data in RETRO_ENVIRONMENT_GET_FASTFORWARDING",
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
 
             unsafe {
-                *(data as *mut u32) = 1 << 0 | 1 << 1;
+                *(data as *mut bool) = core_ctx.speed.is_fast_forwarding();
+            }
+
+            Ok(true)
+        }
+        RETRO_ENVIRONMENT_GET_THROTTLE_STATE => {
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_THROTTLE_STATE -> ok");
+
+            if InputValidator::validate_non_null_mut_ptr(
+                data,
+                "ptr data in RETRO_ENVIRONMENT_GET_THROTTLE_STATE",
+            )
+            .is_err()
+            {
+                return Ok(false);
+            }
+
+            let fps = *core_ctx.av_info.timing.fps.read()?;
+
+            unsafe {
+                *(data as *mut retro_throttle_state) = core_ctx.speed.throttle_state(fps);
             }
 
             Ok(true)
         }
         #[cfg(feature = "hw")]
         RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER");
 
             unsafe {
                 *(data as *mut retro_hw_context_type) =
@@ -230,8 +281,7 @@ pub unsafe fn env_cb_av(
         }
         #[cfg(feature = "hw")]
         RETRO_ENVIRONMENT_SET_HW_RENDER => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_HW_RENDER");
+            logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_HW_RENDER");
 
             if InputValidator::validate_non_null_ptr(
                 data,
@@ -245,10 +295,12 @@ pub unsafe fn env_cb_av(
             unsafe {
                 let hw_cb = &mut *(data as *mut retro_hw_render_callback);
 
-                hw_cb.context_reset = Some(context_reset);
-                hw_cb.context_destroy = Some(context_destroy);
-                hw_cb.get_current_framebuffer = Some(get_current_frame_buffer);
-                hw_cb.get_proc_address = Some(get_proc_address);
+                let callbacks = slot_callbacks(core_ctx.slot);
+
+                hw_cb.context_reset = callbacks.context_reset;
+                hw_cb.context_destroy = callbacks.context_destroy;
+                hw_cb.get_current_framebuffer = callbacks.get_current_framebuffer;
+                hw_cb.get_proc_address = callbacks.get_proc_address;
 
                 Ok(core_ctx
                     .av_info
@@ -1,23 +1,24 @@
-#[cfg(feature = "core_logs")]
-use crate::tools::ffi_tools::get_str_from_ptr;
+use crate::managers::message_manager::CoreMessage;
 use crate::{
     RetroCoreIns,
     core_env::{
-        env_directory::env_cb_directory, env_gamepads_io::env_cb_gamepad_io,
+        core_registry::get_core, env_directory::env_cb_directory,
+        env_disk_control::env_cb_disk_control, env_gamepads_io::env_cb_gamepad_io,
         env_option::env_cb_option, env_video::env_cb_av,
     },
     libretro_sys::{
         binding_libretro::{
             RETRO_ENVIRONMENT_GET_LANGUAGE, RETRO_ENVIRONMENT_GET_LOG_INTERFACE,
             RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION, RETRO_ENVIRONMENT_GET_PERF_INTERFACE,
-            RETRO_ENVIRONMENT_GET_VARIABLE, RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
-            RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL, RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME,
-            retro_language::{self, RETRO_LANGUAGE_PORTUGUESE_BRAZIL},
-            retro_log_level, retro_perf_callback, retro_rumble_effect,
+            RETRO_ENVIRONMENT_GET_USERNAME, RETRO_ENVIRONMENT_GET_VARIABLE,
+            RETRO_ENVIRONMENT_SET_MEMORY_MAPS, RETRO_ENVIRONMENT_SET_MESSAGE,
+            RETRO_ENVIRONMENT_SET_MESSAGE_EXT, RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL,
+            RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS, RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME,
+            retro_language, retro_log_level, retro_memory_map, retro_message, retro_message_ext,
+            retro_perf_callback, retro_rumble_effect,
         },
         binding_log_interface::configure_log_interface,
     },
-    retro_core::RetroCore,
     retro_perf::{
         core_get_perf_counter, core_perf_log, core_perf_register, core_perf_start, core_perf_stop,
         get_cpu_features, get_features_get_time_usec,
@@ -25,13 +26,13 @@ use crate::{
 };
 use crate::{av_info::AvInfo, tools::validation::InputValidator};
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogLevel, LogTarget};
+use std::os::raw::c_void;
 use std::sync::Arc;
 use std::{
-    ffi::{c_char, c_uint},
-    rc::Rc,
+    ffi::{CStr, c_char, c_uint},
     sync::atomic::Ordering,
 };
-use std::{os::raw::c_void, ptr::addr_of};
 
 pub struct RetroEnvCallbacks {
     pub video: Box<dyn RetroVideoEnvCallbacks>,
@@ -39,7 +40,7 @@ pub struct RetroEnvCallbacks {
     pub controller: Box<dyn RetroControllerEnvCallbacks>,
 }
 
-pub trait RetroVideoEnvCallbacks {
+pub trait RetroVideoEnvCallbacks: Send + Sync {
     fn video_refresh_callback(
         &self,
         data: *const c_void,
@@ -55,7 +56,7 @@ pub trait RetroVideoEnvCallbacks {
     fn context_destroy(&self) -> Result<(), ErrorHandle>;
 }
 
-pub trait RetroAudioEnvCallbacks {
+pub trait RetroAudioEnvCallbacks: Send + Sync {
     fn audio_sample_callback(
         &self,
         left: i16,
@@ -70,7 +71,7 @@ pub trait RetroAudioEnvCallbacks {
     ) -> Result<usize, ErrorHandle>;
 }
 
-pub trait RetroControllerEnvCallbacks {
+pub trait RetroControllerEnvCallbacks: Send + Sync {
     fn input_poll_callback(&self) -> Result<(), ErrorHandle>;
     fn input_state_callback(
         &self,
@@ -87,46 +88,50 @@ pub trait RetroControllerEnvCallbacks {
     ) -> Result<bool, ErrorHandle>;
 }
 
-#[doc = "pelo amor de deus MANTENHA isso dentro desse diretório"]
-pub static mut CORE_CONTEXT: Option<Rc<RetroCore>> = None;
-
-//noinspection RsPlaceExpression
-pub fn configure(core_ctx: RetroCoreIns) {
-    unsafe {
-        CORE_CONTEXT = Some(core_ctx);
+unsafe extern "C" fn core_log(level: retro_log_level, log: *const c_char) {
+    if log.is_null() {
+        return;
     }
-}
 
-//noinspection RsPlaceExpression
-pub fn delete_local_core_ctx() {
-    unsafe {
-        CORE_CONTEXT = None;
+    let level = match level {
+        retro_log_level::RETRO_LOG_DEBUG => LogLevel::Debug,
+        retro_log_level::RETRO_LOG_WARN => LogLevel::Warn,
+        retro_log_level::RETRO_LOG_ERROR => LogLevel::Error,
+        _ => LogLevel::Info,
+    };
+
+    if !logger::log_enabled(LogTarget::Core, level) {
+        return;
     }
-}
 
-unsafe extern "C" fn core_log(_level: retro_log_level, _log: *const c_char) {
-    #[cfg(feature = "core_logs")]
-    println!("[{:?}]: {:?}", _level, get_str_from_ptr(_log));
+    // os núcleos quase sempre terminam a mensagem com uma quebra de linha
+    let message = unsafe { CStr::from_ptr(log) }.to_string_lossy();
+    logger::log(LogTarget::Core, level, message.trim_end());
 }
 
-fn handle_env_result(core_ctx: &Rc<RetroCore>, core_env_result: Result<bool, ErrorHandle>) -> bool {
+fn handle_env_result(core_ctx: &RetroCoreIns, core_env_result: Result<bool, ErrorHandle>) -> bool {
     match core_env_result {
         Ok(val) => val,
         Err(err) => {
-            println!("[CORE_ENV]: {:?}", err);
+            logger::error(LogTarget::Env, format!("{err:?}"));
             let _ = core_ctx.de_init();
             false
         }
     }
 }
 
-pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> bool {
+pub unsafe extern "C" fn core_environment<const SLOT: usize>(
+    cmd: c_uint,
+    data: *mut c_void,
+) -> bool {
     unsafe {
-        match &*addr_of!(CORE_CONTEXT) {
+        match &get_core(SLOT) {
             Some(core_ctx) => match cmd {
                 RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME => {
-                    #[cfg(feature = "core_ev_logs")]
-                    println!("RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME -> ok");
+                    logger::debug(
+                        LogTarget::Env,
+                        "RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME -> ok",
+                    );
 
                     let result = InputValidator::validate_non_null_ptr(
                         data,
@@ -145,32 +150,119 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
                     }
                 }
                 RETRO_ENVIRONMENT_GET_LANGUAGE => {
-                    #[cfg(feature = "core_ev_logs")]
-                    println!("RETRO_ENVIRONMENT_GET_LANGUAGE -> ok");
+                    logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_LANGUAGE -> ok");
 
-                    *(data as *mut retro_language) = RETRO_LANGUAGE_PORTUGUESE_BRAZIL;
+                    if InputValidator::validate_non_null_mut_ptr(
+                        data,
+                        "data in RETRO_ENVIRONMENT_GET_LANGUAGE",
+                    )
+                    .is_err()
+                    {
+                        return false;
+                    }
+
+                    *(data as *mut retro_language) = core_ctx.settings.retro_language();
 
                     true
                 }
+                RETRO_ENVIRONMENT_GET_USERNAME => {
+                    logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_USERNAME -> ok");
+
+                    if InputValidator::validate_non_null_mut_ptr(
+                        data,
+                        "data in RETRO_ENVIRONMENT_GET_USERNAME",
+                    )
+                    .is_err()
+                    {
+                        return false;
+                    }
+
+                    match &core_ctx.username {
+                        Some(username) => {
+                            *(data as *mut *const c_char) = username.as_ptr();
+                            true
+                        }
+                        None => false,
+                    }
+                }
                 RETRO_ENVIRONMENT_GET_LOG_INTERFACE => {
-                    #[cfg(feature = "core_ev_logs")]
-                    println!("RETRO_ENVIRONMENT_GET_LOG_INTERFACE -> ok");
+                    logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_LOG_INTERFACE -> ok");
 
                     configure_log_interface(Some(core_log), data);
 
                     true
                 }
                 RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION => {
-                    #[cfg(feature = "core_ev_logs")]
-                    println!("RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO -> OK");
+                    logger::debug(
+                        LogTarget::Env,
+                        "RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION -> OK",
+                    );
 
-                    *(data as *mut usize) = 1;
+                    *(data as *mut c_uint) = 1;
 
                     true
                 }
+                RETRO_ENVIRONMENT_SET_MESSAGE => {
+                    logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_MESSAGE -> ok");
+
+                    if InputValidator::validate_non_null_mut_ptr(
+                        data,
+                        "data in RETRO_ENVIRONMENT_SET_MESSAGE",
+                    )
+                    .is_err()
+                    {
+                        return false;
+                    }
+
+                    let fps = core_ctx
+                        .av_info
+                        .timing
+                        .fps
+                        .read()
+                        .map(|fps| *fps)
+                        .unwrap_or(0.0);
+                    let message = CoreMessage::from_legacy(&*(data as *const retro_message), fps);
+
+                    core_ctx.messages.push(message).is_ok()
+                }
+                RETRO_ENVIRONMENT_SET_MESSAGE_EXT => {
+                    logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_MESSAGE_EXT -> ok");
+
+                    if InputValidator::validate_non_null_mut_ptr(
+                        data,
+                        "data in RETRO_ENVIRONMENT_SET_MESSAGE_EXT",
+                    )
+                    .is_err()
+                    {
+                        return false;
+                    }
+
+                    let message = CoreMessage::from_ext(&*(data as *const retro_message_ext));
+
+                    core_ctx.messages.push(message).is_ok()
+                }
+                RETRO_ENVIRONMENT_SET_MEMORY_MAPS => {
+                    logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_SET_MEMORY_MAPS -> ok");
+
+                    if InputValidator::validate_non_null_mut_ptr(
+                        data,
+                        "data in RETRO_ENVIRONMENT_SET_MEMORY_MAPS",
+                    )
+                    .is_err()
+                    {
+                        return false;
+                    }
+
+                    core_ctx
+                        .memory
+                        .set_memory_maps(&*(data as *const retro_memory_map))
+                        .is_ok()
+                }
                 RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL => {
-                    #[cfg(feature = "core_ev_logs")]
-                    println!("RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL -> OK");
+                    logger::debug(
+                        LogTarget::Env,
+                        "RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL -> OK",
+                    );
 
                     let result = InputValidator::validate_non_null_mut_ptr(
                         data,
@@ -186,17 +278,40 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
 
                             true
                         }
-                        Err(_err) => {
-                            #[cfg(feature = "core_ev_logs")]
-                            println!("Error: {:?}", _err);
+                        Err(err) => {
+                            logger::error(LogTarget::Env, format!("{err:?}"));
+
+                            false
+                        }
+                    }
+                }
+                RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS => {
+                    logger::debug(
+                        LogTarget::Env,
+                        "RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS -> OK",
+                    );
+
+                    let result = InputValidator::validate_non_null_mut_ptr(
+                        data,
+                        "ptr data in RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS",
+                    );
+
+                    match result {
+                        Ok(_) => {
+                            let quirks = data as *mut u64;
+                            *quirks = core_ctx.serialization.set_quirks(*quirks);
+
+                            true
+                        }
+                        Err(err) => {
+                            logger::error(LogTarget::Env, format!("{err:?}"));
 
                             false
                         }
                     }
                 }
                 RETRO_ENVIRONMENT_GET_PERF_INTERFACE => {
-                    #[cfg(feature = "core_ev_logs")]
-                    println!("RETRO_ENVIRONMENT_GET_PERF_INTERFACE -> ok");
+                    logger::debug(LogTarget::Env, "RETRO_ENVIRONMENT_GET_PERF_INTERFACE -> ok");
 
                     let result = InputValidator::validate_non_null_mut_ptr(
                         data,
@@ -217,9 +332,8 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
 
                             true
                         }
-                        Err(_err) => {
-                            #[cfg(feature = "core_ev_logs")]
-                            println!("Error: {:?}", _err);
+                        Err(err) => {
+                            logger::error(LogTarget::Env, format!("{err:?}"));
                             false
                         }
                     }
@@ -229,14 +343,13 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
                         || handle_env_result(core_ctx, env_cb_gamepad_io(core_ctx, cmd, data))
                         || handle_env_result(core_ctx, env_cb_option(core_ctx, cmd, data))
                         || handle_env_result(core_ctx, env_cb_directory(core_ctx, cmd, data))
+                        || handle_env_result(core_ctx, env_cb_disk_control(core_ctx, cmd, data))
                     {
                         return true;
                     }
 
-                    if cmd != RETRO_ENVIRONMENT_GET_VARIABLE
-                        && cmd != RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS
-                    {
-                        println!("new core cmd -> {:?}", cmd);
+                    if cmd != RETRO_ENVIRONMENT_GET_VARIABLE {
+                        logger::debug(LogTarget::Env, format!("new core cmd -> {cmd:?}"));
                     }
 
                     false
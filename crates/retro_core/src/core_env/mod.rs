@@ -1,4 +1,6 @@
+mod core_registry;
 mod env_directory;
+mod env_disk_control;
 mod env_gamepads_io;
 mod env_option;
 mod env_video;
@@ -10,8 +12,9 @@ mod environment;
 // pub mod migration_example;
 // pub mod safe_context;
 
-pub use env_gamepads_io::{input_poll_callback, input_state_callback};
-pub use env_video::{audio_sample_batch_callback, audio_sample_callback, video_refresh_callback};
+pub(crate) use core_registry::{
+    attach_core, detach_core, release_core_slot, reserve_core_slot, slot_callbacks,
+};
 pub use environment::*;
 
 // // Export new safe interfaces
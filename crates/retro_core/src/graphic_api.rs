@@ -35,6 +35,9 @@ pub struct GraphicApi {
 
     #[doc = " Creates a debug context."]
     pub debug_context: AtomicBool,
+
+    #[doc = "o núcleo pediu renderização por hardware com SET_HW_RENDER"]
+    pub hw_render: AtomicBool,
 }
 
 impl Default for GraphicApi {
@@ -49,6 +52,7 @@ impl Default for GraphicApi {
             minor: AtomicU8::new(0),
             cache_context: AtomicBool::new(false),
             debug_context: AtomicBool::new(false),
+            hw_render: AtomicBool::new(false),
         }
     }
 }
@@ -74,6 +78,7 @@ impl GraphicApi {
             .store(hw_cb.cache_context, Ordering::SeqCst);
         self.debug_context
             .store(hw_cb.debug_context, Ordering::SeqCst);
+        self.hw_render.store(true, Ordering::SeqCst);
 
         true
     }
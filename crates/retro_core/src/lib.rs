@@ -11,10 +11,25 @@ pub mod av_info;
 pub mod graphic_api;
 mod retro_core;
 pub mod system;
+pub mod vfs;
 
 pub use core_env::{
     RetroAudioEnvCallbacks, RetroControllerEnvCallbacks, RetroEnvCallbacks, RetroVideoEnvCallbacks,
 };
 pub use managers::args_manager;
+pub use managers::cheat_finder_manager;
+pub use managers::cheat_manager;
+pub use managers::controller_type_manager;
+pub use managers::disk_control_manager;
+pub use managers::memory_manager;
+pub use managers::message_manager;
 pub use managers::option_manager;
+pub use managers::playtime_manager;
+pub use managers::rewind_manager;
+pub use managers::run_ahead_manager;
+pub use managers::save_ram_manager;
+pub use managers::save_state_manager;
+pub use managers::serialization_manager;
+pub use managers::speed_manager;
 pub use retro_core::{RetroCore, RetroCoreIns};
+pub use tools::save_state_tools;
@@ -0,0 +1,219 @@
+use crate::managers::cheat_manager::{Cheat, MemoryCheat};
+use crate::managers::memory_manager::ValueWidth;
+use generics::error_handle::ErrorHandle;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFilter {
+    #[doc = "o valor continua igual ao da última busca"]
+    Equal,
+    Changed,
+    Increased,
+    Decreased,
+    Value(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheatCandidate {
+    #[doc = "posição dentro da RETRO_MEMORY_SYSTEM_RAM"]
+    pub offset: usize,
+    pub value: u32,
+}
+
+struct CheatSearch {
+    width: ValueWidth,
+    big_endian: bool,
+    snapshot: Vec<u8>,
+    candidates: Vec<usize>,
+}
+
+impl CheatSearch {
+    fn value_at(&self, memory: &[u8], offset: usize) -> u32 {
+        self.width.decode(&memory[offset..], self.big_endian)
+    }
+}
+
+/// RAM search used to find the address of a value, like the lives counter.
+///
+/// Every filter compares the system RAM with the snapshot taken by the previous search and
+/// keeps only the offsets that still match, then the surviving offset can become a cheat.
+#[derive(Default)]
+pub struct CheatFinderManager {
+    search: Mutex<Option<CheatSearch>>,
+}
+
+impl CheatFinderManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "começa uma nova busca com todas as posições da memória e retorna quantas são"]
+    pub fn start(
+        &self,
+        memory: &[u8],
+        width: ValueWidth,
+        big_endian: bool,
+    ) -> Result<usize, ErrorHandle> {
+        let candidates: Vec<usize> =
+            (0..(memory.len() + 1).saturating_sub(width.bytes())).collect();
+        let count = candidates.len();
+
+        *self.search.lock()? = Some(CheatSearch {
+            width,
+            big_endian,
+            snapshot: memory.to_vec(),
+            candidates,
+        });
+
+        Ok(count)
+    }
+
+    #[doc = "remove os candidatos que não passam no filtro e retorna quantos sobraram"]
+    pub fn filter(&self, memory: &[u8], filter: SearchFilter) -> Result<usize, ErrorHandle> {
+        let mut search = self.search.lock()?;
+        let search = search
+            .as_mut()
+            .ok_or_else(|| ErrorHandle::new("Nenhuma busca de cheat foi iniciada"))?;
+
+        // o núcleo pode trocar o tamanho da RAM ao trocar de jogo ou de modo
+        if memory.len() != search.snapshot.len() {
+            return Err(ErrorHandle::new(
+                "O tamanho da memória mudou desde o início da busca",
+            ));
+        }
+
+        let candidates = search
+            .candidates
+            .iter()
+            .copied()
+            .filter(|offset| {
+                let previous = search.value_at(&search.snapshot, *offset);
+                let current = search.value_at(memory, *offset);
+
+                match filter {
+                    SearchFilter::Equal => current == previous,
+                    SearchFilter::Changed => current != previous,
+                    SearchFilter::Increased => current > previous,
+                    SearchFilter::Decreased => current < previous,
+                    SearchFilter::Value(value) => current == value,
+                }
+            })
+            .collect();
+
+        search.candidates = candidates;
+        search.snapshot = memory.to_vec();
+
+        Ok(search.candidates.len())
+    }
+
+    #[doc = "os primeiros candidatos com o valor da última busca, limitados a `limit`"]
+    pub fn candidates(&self, limit: usize) -> Result<Vec<CheatCandidate>, ErrorHandle> {
+        let search = self.search.lock()?;
+
+        Ok(search
+            .iter()
+            .flat_map(|search| {
+                search
+                    .candidates
+                    .iter()
+                    .take(limit)
+                    .map(|offset| CheatCandidate {
+                        offset: *offset,
+                        value: search.value_at(&search.snapshot, *offset),
+                    })
+            })
+            .collect())
+    }
+
+    pub fn count(&self) -> Result<usize, ErrorHandle> {
+        Ok(self
+            .search
+            .lock()?
+            .as_ref()
+            .map(|search| search.candidates.len())
+            .unwrap_or(0))
+    }
+
+    #[doc = "cria um cheat que mantém o valor no offset, usando a largura e a ordem dos bytes da busca"]
+    pub fn make_cheat(&self, offset: usize, value: u32, desc: &str) -> Result<Cheat, ErrorHandle> {
+        let search = self.search.lock()?;
+        let search = search
+            .as_ref()
+            .ok_or_else(|| ErrorHandle::new("Nenhuma busca de cheat foi iniciada"))?;
+
+        if offset + search.width.bytes() > search.snapshot.len() {
+            return Err(ErrorHandle::new(&format!(
+                "O offset {offset:#x} está fora da memória"
+            )));
+        }
+
+        Ok(Cheat {
+            desc: desc.to_string(),
+            code: String::new(),
+            enabled: true,
+            memory: Some(MemoryCheat {
+                offset,
+                value,
+                width: search.width,
+                big_endian: search.big_endian,
+            }),
+        })
+    }
+
+    pub fn reset(&self) -> Result<(), ErrorHandle> {
+        self.search.lock()?.take();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_decreasing_value() {
+        let finder = CheatFinderManager::new();
+        let mut ram = vec![0u8; 64];
+        ram[10] = 5;
+        ram[20] = 5;
+
+        assert!(finder.filter(&ram, SearchFilter::Equal).is_err());
+        assert_eq!(finder.start(&ram, ValueWidth::U8, false).unwrap(), 64);
+
+        // só o byte 10 representa as vidas, o 20 muda para outro valor qualquer
+        ram[10] = 4;
+        ram[20] = 9;
+        assert_eq!(finder.filter(&ram, SearchFilter::Decreased).unwrap(), 1);
+        assert_eq!(
+            finder.candidates(10).unwrap(),
+            vec![CheatCandidate {
+                offset: 10,
+                value: 4
+            }]
+        );
+
+        assert_eq!(finder.filter(&ram, SearchFilter::Value(4)).unwrap(), 1);
+        assert_eq!(finder.filter(&ram, SearchFilter::Changed).unwrap(), 0);
+
+        let cheat = finder.make_cheat(10, 9, "Vidas").unwrap();
+        assert_eq!(cheat.memory.unwrap().width, ValueWidth::U8);
+        assert!(finder.make_cheat(64, 9, "Fora").is_err());
+    }
+
+    #[test]
+    fn test_wide_values_respect_byte_order() {
+        let finder = CheatFinderManager::new();
+        let mut ram = vec![0u8; 8];
+
+        assert_eq!(finder.start(&ram, ValueWidth::U16, true).unwrap(), 7);
+
+        ram[2] = 0x01;
+        ram[3] = 0x00;
+        assert_eq!(finder.filter(&ram, SearchFilter::Value(0x0100)).unwrap(), 1);
+        assert_eq!(finder.candidates(1).unwrap()[0].offset, 2);
+
+        assert_eq!(finder.start(&ram, ValueWidth::U32, false).unwrap(), 5);
+        finder.reset().unwrap();
+        assert_eq!(finder.count().unwrap(), 0);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::managers::memory_manager::ValueWidth;
+use crate::tools::game_tools::RomTools;
+use generics::constants::CHEAT_EXTENSION_FILE;
+use generics::error_handle::ErrorHandle;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cheat {
+    pub desc: String,
+    pub code: String,
+    pub enabled: bool,
+    #[doc = "cheats criados pela busca na RAM não têm código, o frontend escreve o valor a cada frame"]
+    pub memory: Option<MemoryCheat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryCheat {
+    #[doc = "posição dentro da RETRO_MEMORY_SYSTEM_RAM"]
+    pub offset: usize,
+    pub value: u32,
+    pub width: ValueWidth,
+    pub big_endian: bool,
+}
+
+/// Cheats of the loaded rom, read from RetroArch `.cht` files.
+///
+/// The codes are looked up first in `<cheats>/<library>/<rom>.cht`, which is where the
+/// enabled set of each game is saved, and then in `<cheats>/<rom>.cht`.
+pub struct CheatManager {
+    file_path: RwLock<Option<PathBuf>>,
+    cheats: RwLock<Vec<Cheat>>,
+}
+
+impl Default for CheatManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CheatManager {
+    pub fn new() -> Self {
+        Self {
+            file_path: RwLock::new(None),
+            cheats: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn load(
+        &self,
+        cheats_dir: &str,
+        library_name: &str,
+        rom_name: &str,
+    ) -> Result<(), ErrorHandle> {
+        let file_name = format!(
+            "{}.{}",
+            RomTools::sanitize_filename(rom_name),
+            CHEAT_EXTENSION_FILE
+        );
+
+        let game_file = Path::new(cheats_dir)
+            .join(RomTools::sanitize_filename(library_name))
+            .join(&file_name);
+        let shared_file = Path::new(cheats_dir).join(&file_name);
+
+        let cheats = if game_file.exists() {
+            parse_cht(&fs::read_to_string(&game_file)?)
+        } else if shared_file.exists() {
+            parse_cht(&fs::read_to_string(&shared_file)?)
+        } else {
+            Vec::new()
+        };
+
+        *self.cheats.write()? = cheats;
+        self.file_path.write()?.replace(game_file);
+
+        Ok(())
+    }
+
+    pub fn get_cheats(&self) -> Result<Vec<Cheat>, ErrorHandle> {
+        Ok(self.cheats.read()?.clone())
+    }
+
+    #[doc = "valores que o frontend precisa escrever na memória depois de cada frame"]
+    pub fn enabled_memory_cheats(&self) -> Result<Vec<MemoryCheat>, ErrorHandle> {
+        Ok(self
+            .cheats
+            .read()?
+            .iter()
+            .filter(|cheat| cheat.enabled)
+            .filter_map(|cheat| cheat.memory)
+            .collect())
+    }
+
+    pub fn set_enabled(&self, index: usize, enabled: bool) -> Result<(), ErrorHandle> {
+        let mut cheats = self.cheats.write()?;
+
+        let cheat = cheats
+            .get_mut(index)
+            .ok_or_else(|| ErrorHandle::new(&format!("Cheat index {} does not exist", index)))?;
+        cheat.enabled = enabled;
+
+        self.save(&cheats)
+    }
+
+    #[doc = "adiciona o cheat no fim da lista do jogo e retorna o índice dele"]
+    pub fn add(&self, cheat: Cheat) -> Result<usize, ErrorHandle> {
+        // o formato .cht não tem escape, um valor assim quebraria as linhas seguintes do arquivo
+        for value in [&cheat.desc, &cheat.code] {
+            if value.contains(['"', '\n', '\r']) {
+                return Err(ErrorHandle::new(
+                    "A descrição e o código do cheat não podem ter aspas ou quebras de linha",
+                ));
+            }
+        }
+
+        let mut cheats = self.cheats.write()?;
+        cheats.push(cheat);
+
+        self.save(&cheats)?;
+
+        Ok(cheats.len() - 1)
+    }
+
+    fn save(&self, cheats: &[Cheat]) -> Result<(), ErrorHandle> {
+        let file_path = match self.file_path.read()?.clone() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        RomTools::write_file_atomically(&file_path, write_cht(cheats).as_bytes())
+    }
+
+    pub fn clear(&self) -> Result<(), ErrorHandle> {
+        self.cheats.write()?.clear();
+        self.file_path.write()?.take();
+
+        Ok(())
+    }
+}
+
+fn parse_cht(content: &str) -> Vec<Cheat> {
+    let mut values: BTreeMap<&str, &str> = BTreeMap::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+
+            values.insert(key.trim(), value);
+        }
+    }
+
+    let count = values
+        .get("cheats")
+        .and_then(|count| count.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    (0..count)
+        .map(|index| {
+            let get = |field: &str| {
+                values
+                    .get(format!("cheat{}_{}", index, field).as_str())
+                    .copied()
+                    .unwrap_or_default()
+            };
+
+            // handler 1 é o formato do RetroArch para cheats aplicados pelo frontend
+            let memory = if get("handler") == "1" {
+                let width = match get("memory_search_size") {
+                    "3" => Some(ValueWidth::U8),
+                    "4" => Some(ValueWidth::U16),
+                    "5" => Some(ValueWidth::U32),
+                    _ => None,
+                };
+
+                match (width, get("address").parse(), get("value").parse()) {
+                    (Some(width), Ok(offset), Ok(value)) => Some(MemoryCheat {
+                        offset,
+                        value,
+                        width,
+                        big_endian: get("big_endian") == "true",
+                    }),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+
+            Cheat {
+                desc: get("desc").to_string(),
+                code: get("code").to_string(),
+                enabled: get("enable") == "true",
+                memory,
+            }
+        })
+        .collect()
+}
+
+fn write_cht(cheats: &[Cheat]) -> String {
+    let mut content = format!("cheats = {}\n", cheats.len());
+
+    for (index, cheat) in cheats.iter().enumerate() {
+        content.push_str(&format!(
+            "\ncheat{index}_desc = \"{}\"\ncheat{index}_code = \"{}\"\ncheat{index}_enable = {}\n",
+            cheat.desc, cheat.code, cheat.enabled
+        ));
+
+        if let Some(memory) = cheat.memory {
+            let search_size = match memory.width {
+                ValueWidth::U8 => 3,
+                ValueWidth::U16 => 4,
+                ValueWidth::U32 => 5,
+            };
+
+            content.push_str(&format!(
+                "cheat{index}_handler = 1\ncheat{index}_cheat_type = 1\ncheat{index}_address = {}\ncheat{index}_value = {}\ncheat{index}_memory_search_size = {search_size}\ncheat{index}_big_endian = {}\n",
+                memory.offset, memory.value, memory.big_endian
+            ));
+        }
+    }
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CHT: &str = r#"cheats = 2
+
+cheat0_desc = "Infinite Lives"
+cheat0_code = "SXIOPO"
+cheat0_enable = false
+
+cheat1_desc = "Start on World 8"
+cheat1_code = "AAAAAA+BBBBBB"
+cheat1_enable = true
+"#;
+
+    #[test]
+    fn test_parse_cht() {
+        let cheats = parse_cht(CHT);
+
+        assert_eq!(cheats.len(), 2);
+        assert_eq!(cheats[0].desc, "Infinite Lives");
+        assert_eq!(cheats[0].code, "SXIOPO");
+        assert!(!cheats[0].enabled);
+        assert_eq!(cheats[1].code, "AAAAAA+BBBBBB");
+        assert!(cheats[1].enabled);
+
+        assert_eq!(parse_cht(&write_cht(&cheats)), cheats);
+    }
+
+    #[test]
+    fn test_memory_cheat_round_trip() {
+        let cheats = vec![Cheat {
+            desc: "99 Lives".to_string(),
+            code: String::new(),
+            enabled: true,
+            memory: Some(MemoryCheat {
+                offset: 0x75A,
+                value: 99,
+                width: ValueWidth::U16,
+                big_endian: true,
+            }),
+        }];
+
+        let content = write_cht(&cheats);
+        assert!(content.contains("cheat0_handler = 1"));
+        assert_eq!(parse_cht(&content), cheats);
+    }
+
+    #[test]
+    fn test_add_rejects_values_that_break_the_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = CheatManager::new();
+        manager
+            .load(temp_dir.path().to_str().unwrap(), "Mesen", "../game")
+            .unwrap();
+
+        let cheat = |desc: &str| Cheat {
+            desc: desc.to_string(),
+            code: "SXIOPO".to_string(),
+            ..Default::default()
+        };
+
+        assert!(manager.add(cheat("Lives\ncheats = 0")).is_err());
+        assert!(manager.add(cheat("\"Lives\"")).is_err());
+        assert_eq!(manager.add(cheat("Lives")).unwrap(), 0);
+
+        // o nome da rom não pode escapar do diretório do núcleo
+        let files: Vec<_> = fs::read_dir(temp_dir.path().join("Mesen"))
+            .unwrap()
+            .collect();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_enabled_set_is_saved_per_game() {
+        let temp_dir = TempDir::new().unwrap();
+        let cheats_dir = temp_dir.path().to_str().unwrap();
+        fs::write(temp_dir.path().join("game.cht"), CHT).unwrap();
+
+        let manager = CheatManager::new();
+        manager.load(cheats_dir, "Mesen", "game").unwrap();
+        manager.set_enabled(0, true).unwrap();
+        assert!(manager.set_enabled(5, true).is_err());
+
+        let game_file = temp_dir.path().join("Mesen").join("game.cht");
+        assert!(game_file.exists());
+
+        let manager = CheatManager::new();
+        manager.load(cheats_dir, "Mesen", "game").unwrap();
+        assert!(manager.get_cheats().unwrap()[0].enabled);
+
+        // o arquivo original não é alterado
+        assert!(
+            !parse_cht(&fs::read_to_string(temp_dir.path().join("game.cht")).unwrap())[0].enabled
+        );
+    }
+}
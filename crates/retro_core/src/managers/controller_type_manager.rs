@@ -0,0 +1,89 @@
+use crate::tools::game_tools::RomTools;
+use generics::constants::CONTROLLER_TYPES_EXTENSION_FILE;
+use generics::error_handle::ErrorHandle;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Controller type chosen by the user for each port, such as a multitap or a light gun.
+///
+/// The choice belongs to the core, not to the game, so it's saved in
+/// `<opt>/<library>.ctrl` and applied every time the core is loaded again.
+pub struct ControllerTypeManager {
+    file_path: PathBuf,
+    types: RwLock<BTreeMap<u32, u32>>,
+}
+
+impl ControllerTypeManager {
+    pub fn new(opt_dir: &str, library_name: &str) -> Self {
+        let file_path = Path::new(opt_dir).join(format!(
+            "{}.{}",
+            RomTools::sanitize_filename(library_name),
+            CONTROLLER_TYPES_EXTENSION_FILE
+        ));
+
+        // um arquivo que não pode ser lido apenas volta para os controles padrão
+        let types = fs::read_to_string(&file_path)
+            .map(|content| parse_types(&content))
+            .unwrap_or_default();
+
+        Self {
+            file_path,
+            types: RwLock::new(types),
+        }
+    }
+
+    pub fn get(&self, port: u32) -> Result<Option<u32>, ErrorHandle> {
+        Ok(self.types.read()?.get(&port).copied())
+    }
+
+    pub fn set(&self, port: u32, device: u32) -> Result<(), ErrorHandle> {
+        let mut types = self.types.write()?;
+        types.insert(port, device);
+
+        RomTools::write_file_atomically(&self.file_path, write_types(&types).as_bytes())
+    }
+}
+
+fn parse_types(content: &str) -> BTreeMap<u32, u32> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (port, device) = line.split_once('=')?;
+            Some((port.trim().parse().ok()?, device.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn write_types(types: &BTreeMap<u32, u32>) -> String {
+    types
+        .iter()
+        .map(|(port, device)| format!("{port} = {device}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_types_are_remembered_per_core() {
+        let temp_dir = TempDir::new().unwrap();
+        let opt_dir = temp_dir.path().to_str().unwrap();
+
+        let manager = ControllerTypeManager::new(opt_dir, "Mesen");
+        assert_eq!(manager.get(0).unwrap(), None);
+
+        manager.set(0, 513).unwrap();
+        manager.set(1, 1).unwrap();
+
+        let manager = ControllerTypeManager::new(opt_dir, "Mesen");
+        assert_eq!(manager.get(0).unwrap(), Some(513));
+        assert_eq!(manager.get(1).unwrap(), Some(1));
+
+        let other_core = ControllerTypeManager::new(opt_dir, "Snes9x");
+        assert_eq!(other_core.get(0).unwrap(), None);
+    }
+}
@@ -0,0 +1,361 @@
+use crate::tools::game_tools::RomInfo;
+use crate::tools::m3u_tools::M3uEntry;
+use generics::error_handle::ErrorHandle;
+use libretro_sys::binding_libretro::{
+    retro_disk_control_callback, retro_disk_control_ext_callback, retro_get_image_label_t,
+};
+use std::ffi::{CStr, c_char, c_uint};
+use std::path::Path;
+use std::sync::RwLock;
+
+const MAX_DISK_LABEL_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskState {
+    pub ejected: bool,
+    pub index: usize,
+    pub count: usize,
+    pub labels: Vec<String>,
+}
+
+/// Disk control interface given by the core, plus the `.m3u` playlist the game was loaded from.
+///
+/// The legacy interface (`RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE`) is stored as an
+/// extended one with the extra callbacks left empty.
+pub struct DiskControlManager {
+    interface: RwLock<Option<retro_disk_control_ext_callback>>,
+    playlist: RwLock<Vec<M3uEntry>>,
+    // o núcleo pode guardar os ponteiros do retro_game_info de cada disco adicionado, então
+    // eles só são liberados quando o jogo é descarregado
+    images: RwLock<Vec<RomInfo>>,
+}
+
+impl Default for DiskControlManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiskControlManager {
+    pub fn new() -> Self {
+        Self {
+            interface: RwLock::new(None),
+            playlist: RwLock::new(Vec::new()),
+            images: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn set_interface(&self, callback: &retro_disk_control_callback) -> Result<(), ErrorHandle> {
+        self.interface
+            .write()?
+            .replace(retro_disk_control_ext_callback {
+                set_eject_state: callback.set_eject_state,
+                get_eject_state: callback.get_eject_state,
+                get_image_index: callback.get_image_index,
+                set_image_index: callback.set_image_index,
+                get_num_images: callback.get_num_images,
+                replace_image_index: callback.replace_image_index,
+                add_image_index: callback.add_image_index,
+                set_initial_image: None,
+                get_image_path: None,
+                get_image_label: None,
+            });
+
+        Ok(())
+    }
+
+    pub fn set_ext_interface(
+        &self,
+        callback: &retro_disk_control_ext_callback,
+    ) -> Result<(), ErrorHandle> {
+        self.interface.write()?.replace(*callback);
+        Ok(())
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.interface
+            .read()
+            .is_ok_and(|interface| interface.is_some())
+    }
+
+    pub fn set_playlist(&self, playlist: Vec<M3uEntry>) -> Result<(), ErrorHandle> {
+        *self.playlist.write()? = playlist;
+        Ok(())
+    }
+
+    #[doc = "só deve ser chamado depois do retro_unload_game, libera os discos adicionados"]
+    pub fn clear(&self) -> Result<(), ErrorHandle> {
+        self.playlist.write()?.clear();
+        self.images.write()?.clear();
+        Ok(())
+    }
+
+    fn get_interface(&self) -> Result<retro_disk_control_ext_callback, ErrorHandle> {
+        self.interface.read()?.ok_or_else(|| {
+            ErrorHandle::new("O núcleo não possui uma interface de controle de disco")
+        })
+    }
+
+    pub fn is_ejected(&self) -> Result<bool, ErrorHandle> {
+        let interface = self.get_interface()?;
+
+        match interface.get_eject_state {
+            Some(get_eject_state) => Ok(unsafe { get_eject_state() }),
+            None => Ok(false),
+        }
+    }
+
+    pub fn set_ejected(&self, ejected: bool) -> Result<(), ErrorHandle> {
+        let set_eject_state = self
+            .get_interface()?
+            .set_eject_state
+            .ok_or_else(|| ErrorHandle::new("O núcleo não permite ejetar o disco"))?;
+
+        if !unsafe { set_eject_state(ejected) } {
+            return Err(ErrorHandle::new(
+                "O núcleo recusou a mudança de estado da bandeja",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Swap to another disc. The tray is opened for the swap and closed again if it was
+    /// closed before, as the core only accepts a new index with the tray open.
+    pub fn select_index(&self, index: usize) -> Result<(), ErrorHandle> {
+        let interface = self.get_interface()?;
+        let count = self.get_num_images(&interface);
+
+        if index >= count {
+            return Err(ErrorHandle::new(&format!(
+                "Disco {} não existe, o jogo possui {} discos",
+                index, count
+            )));
+        }
+
+        let set_image_index = interface
+            .set_image_index
+            .ok_or_else(|| ErrorHandle::new("O núcleo não permite trocar o disco"))?;
+
+        let was_ejected = self.is_ejected()?;
+
+        if !was_ejected {
+            self.set_ejected(true)?;
+        }
+
+        if !unsafe { set_image_index(index as c_uint) } {
+            return Err(ErrorHandle::new("O núcleo recusou a troca de disco"));
+        }
+
+        if !was_ejected {
+            self.set_ejected(false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add a new disc at the end of the core image list.
+    /// The image is kept until [clear](Self::clear), as the core may keep its data and path
+    pub(crate) fn append_image(&self, image: RomInfo) -> Result<(), ErrorHandle> {
+        let interface = self.get_interface()?;
+
+        let (add_image_index, replace_image_index) =
+            match (interface.add_image_index, interface.replace_image_index) {
+                (Some(add), Some(replace)) => (add, replace),
+                _ => {
+                    return Err(ErrorHandle::new(
+                        "O núcleo não permite adicionar novos discos",
+                    ));
+                }
+            };
+
+        // os buffers do RomInfo continuam no mesmo lugar mesmo se o Vec for realocado
+        let game_info = image.to_core_native();
+        self.images.write()?.push(image);
+
+        unsafe {
+            if !add_image_index() {
+                return Err(ErrorHandle::new("O núcleo recusou um novo disco"));
+            }
+
+            let index = self.get_num_images(&interface).saturating_sub(1);
+
+            if !replace_image_index(index as c_uint, &game_info) {
+                return Err(ErrorHandle::new("O núcleo recusou um novo disco"));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_state(&self) -> Result<DiskState, ErrorHandle> {
+        let interface = self.get_interface()?;
+        let count = self.get_num_images(&interface);
+
+        let index = match interface.get_image_index {
+            Some(get_image_index) => unsafe { get_image_index() as usize },
+            None => 0,
+        };
+
+        let labels = (0..count)
+            .map(|index| self.get_label(&interface, index))
+            .collect::<Result<Vec<String>, ErrorHandle>>()?;
+
+        Ok(DiskState {
+            ejected: self.is_ejected()?,
+            index,
+            count,
+            labels,
+        })
+    }
+
+    fn get_num_images(&self, interface: &retro_disk_control_ext_callback) -> usize {
+        match interface.get_num_images {
+            Some(get_num_images) => unsafe { get_num_images() as usize },
+            None => 0,
+        }
+    }
+
+    // ordem de preferência: label do núcleo, label do m3u, nome do arquivo e por fim "Disc N"
+    fn get_label(
+        &self,
+        interface: &retro_disk_control_ext_callback,
+        index: usize,
+    ) -> Result<String, ErrorHandle> {
+        if let Some(label) = read_core_string(interface.get_image_label, index) {
+            return Ok(label);
+        }
+
+        if let Some(entry) = self.playlist.read()?.get(index) {
+            if let Some(label) = &entry.label {
+                return Ok(label.clone());
+            }
+
+            if let Some(name) = file_stem(&entry.path) {
+                return Ok(name);
+            }
+        }
+
+        if let Some(path) = read_core_string(interface.get_image_path, index)
+            && let Some(name) = file_stem(Path::new(&path))
+        {
+            return Ok(name);
+        }
+
+        Ok(format!("Disc {}", index + 1))
+    }
+}
+
+// get_image_path e get_image_label possuem a mesma assinatura
+fn read_core_string(callback: retro_get_image_label_t, index: usize) -> Option<String> {
+    let callback = callback?;
+    let mut buffer = [0 as c_char; MAX_DISK_LABEL_SIZE];
+
+    let found = unsafe { callback(index as c_uint, buffer.as_mut_ptr(), buffer.len()) };
+
+    if !found {
+        return None;
+    }
+
+    // garante o terminador nulo mesmo se o núcleo preencher todo o buffer
+    buffer[MAX_DISK_LABEL_SIZE - 1] = 0;
+
+    let value = unsafe { CStr::from_ptr(buffer.as_ptr()) }
+        .to_string_lossy()
+        .trim()
+        .to_string();
+
+    (!value.is_empty()).then_some(value)
+}
+
+fn file_stem(path: &Path) -> Option<String> {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    static EJECTED: AtomicBool = AtomicBool::new(false);
+    static INDEX: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn set_eject_state(ejected: bool) -> bool {
+        EJECTED.store(ejected, Ordering::SeqCst);
+        true
+    }
+
+    unsafe extern "C" fn get_eject_state() -> bool {
+        EJECTED.load(Ordering::SeqCst)
+    }
+
+    unsafe extern "C" fn get_image_index() -> c_uint {
+        INDEX.load(Ordering::SeqCst)
+    }
+
+    // assim como os núcleos reais, só aceita a troca com a bandeja aberta
+    unsafe extern "C" fn set_image_index(index: c_uint) -> bool {
+        if !EJECTED.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        INDEX.store(index, Ordering::SeqCst);
+        true
+    }
+
+    unsafe extern "C" fn get_num_images() -> c_uint {
+        3
+    }
+
+    #[test]
+    fn test_select_index_and_labels() {
+        let manager = DiskControlManager::new();
+        assert!(!manager.is_available());
+        assert!(manager.get_state().is_err());
+
+        manager
+            .set_interface(&retro_disk_control_callback {
+                set_eject_state: Some(set_eject_state),
+                get_eject_state: Some(get_eject_state),
+                get_image_index: Some(get_image_index),
+                set_image_index: Some(set_image_index),
+                get_num_images: Some(get_num_images),
+                replace_image_index: None,
+                add_image_index: None,
+            })
+            .unwrap();
+
+        manager
+            .set_playlist(vec![
+                M3uEntry {
+                    path: PathBuf::from("/roms/Game (Disc 1).cue"),
+                    label: None,
+                },
+                M3uEntry {
+                    path: PathBuf::from("/roms/Game (Disc 2).cue"),
+                    label: Some("Second".to_string()),
+                },
+            ])
+            .unwrap();
+
+        manager.select_index(1).unwrap();
+        assert!(manager.select_index(3).is_err());
+
+        let state = manager.get_state().unwrap();
+        assert_eq!(
+            state,
+            DiskState {
+                ejected: false,
+                index: 1,
+                count: 3,
+                labels: vec![
+                    "Game (Disc 1)".to_string(),
+                    "Second".to_string(),
+                    "Disc 3".to_string(),
+                ],
+            }
+        );
+    }
+}
@@ -0,0 +1,572 @@
+use crate::tools::ffi_tools::get_str_from_ptr;
+use generics::error_handle::ErrorHandle;
+use libretro_sys::binding_libretro::{
+    RETRO_MEMDESC_BIGENDIAN, RETRO_MEMDESC_CONST, RETRO_MEMORY_RTC, RETRO_MEMORY_SAVE_RAM,
+    RETRO_MEMORY_SYSTEM_RAM, RETRO_MEMORY_VIDEO_RAM, retro_memory_descriptor, retro_memory_map,
+};
+use std::sync::{Mutex, RwLock};
+
+// os watches são lidos a cada frame, então não podem observar blocos grandes de memória
+const MAX_WATCH_LEN: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryRegion {
+    SaveRam,
+    Rtc,
+    SystemRam,
+    VideoRam,
+}
+
+impl MemoryRegion {
+    pub fn id(self) -> u32 {
+        match self {
+            MemoryRegion::SaveRam => RETRO_MEMORY_SAVE_RAM,
+            MemoryRegion::Rtc => RETRO_MEMORY_RTC,
+            MemoryRegion::SystemRam => RETRO_MEMORY_SYSTEM_RAM,
+            MemoryRegion::VideoRam => RETRO_MEMORY_VIDEO_RAM,
+        }
+    }
+}
+
+#[doc = "tamanho de um valor numérico guardado na memória do jogo"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueWidth {
+    U8,
+    U16,
+    U32,
+}
+
+impl ValueWidth {
+    pub fn bytes(self) -> usize {
+        match self {
+            ValueWidth::U8 => 1,
+            ValueWidth::U16 => 2,
+            ValueWidth::U32 => 4,
+        }
+    }
+
+    #[doc = "bits acima da largura são descartados"]
+    pub fn encode(self, value: u32, big_endian: bool) -> Vec<u8> {
+        let bytes = if big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        };
+
+        if big_endian {
+            bytes[4 - self.bytes()..].to_vec()
+        } else {
+            bytes[..self.bytes()].to_vec()
+        }
+    }
+
+    #[doc = "lê apenas os primeiros bytes da largura, `data` precisa ter pelo menos esse tamanho"]
+    pub fn decode(self, data: &[u8], big_endian: bool) -> u32 {
+        let data = &data[..self.bytes()];
+
+        if big_endian {
+            data.iter()
+                .fold(0, |value, byte| (value << 8) | *byte as u32)
+        } else {
+            data.iter()
+                .rev()
+                .fold(0, |value, byte| (value << 8) | *byte as u32)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryAddress {
+    #[doc = "endereço visto pela cpu emulada, traduzido pelos descritores do SET_MEMORY_MAPS"]
+    Virtual(usize),
+    #[doc = "posição dentro de uma das regiões do retro_get_memory_data"]
+    Region(MemoryRegion, usize),
+}
+
+/// One entry of the address space declared by the core with `RETRO_ENVIRONMENT_SET_MEMORY_MAPS`.
+///
+/// `select` and `len` are already filled in when the core leaves one of them as zero, so an
+/// address belongs to the descriptor when `(address ^ start) & select == 0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryDescriptor {
+    pub flags: u64,
+    pub offset: usize,
+    pub start: usize,
+    pub select: usize,
+    pub disconnect: usize,
+    pub len: usize,
+    pub addrspace: String,
+    // o ponteiro é guardado como número para que o manager possa ser lido por outras threads,
+    // ele só é usado na thread do núcleo enquanto o jogo estiver carregado
+    ptr: usize,
+}
+
+impl MemoryDescriptor {
+    pub fn is_const(&self) -> bool {
+        self.flags & RETRO_MEMDESC_CONST as u64 != 0
+    }
+
+    pub fn is_big_endian(&self) -> bool {
+        self.flags & RETRO_MEMDESC_BIGENDIAN as u64 != 0
+    }
+
+    fn contains(&self, address: usize) -> bool {
+        (address ^ self.start) & self.select == 0
+    }
+
+    // subtrai o start, remove os bits do disconnect, aplica o len e soma o offset
+    fn physical_offset(&self, address: usize) -> usize {
+        let mut offset = reduce(address.wrapping_sub(self.start), self.disconnect);
+
+        // os bits que sobram acima do len são espelhos da mesma memória
+        while offset >= self.len {
+            offset -= highest_bit(offset);
+        }
+
+        self.offset + offset
+    }
+}
+
+#[doc = "valor observado que mudou desde o frame anterior, os bytes seguem a ordem da memória do núcleo"]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryWatchEvent {
+    pub id: u32,
+    pub address: MemoryAddress,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+}
+
+struct MemoryWatch {
+    id: u32,
+    address: MemoryAddress,
+    len: usize,
+    last: Option<Vec<u8>>,
+}
+
+#[doc = "retorna o ponteiro e o tamanho de uma região, None quando o núcleo não expõe a região"]
+pub(crate) type RegionLookup<'a> = dyn Fn(MemoryRegion) -> Option<(*mut u8, usize)> + 'a;
+
+/// Memory map of the loaded game and the addresses watched by the frontend.
+///
+/// Cores that don't send `RETRO_ENVIRONMENT_SET_MEMORY_MAPS` still get virtual addresses,
+/// which are then treated as offsets inside `RETRO_MEMORY_SYSTEM_RAM`.
+#[derive(Default)]
+pub struct MemoryManager {
+    descriptors: RwLock<Vec<MemoryDescriptor>>,
+    watches: Mutex<Vec<MemoryWatch>>,
+}
+
+impl MemoryManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_memory_maps(&self, map: &retro_memory_map) -> Result<(), ErrorHandle> {
+        let raw_descriptors: &[retro_memory_descriptor] =
+            if map.descriptors.is_null() || map.num_descriptors == 0 {
+                &[]
+            } else {
+                unsafe { std::slice::from_raw_parts(map.descriptors, map.num_descriptors as usize) }
+            };
+
+        *self.descriptors.write()? = preprocess_descriptors(raw_descriptors);
+
+        Ok(())
+    }
+
+    pub fn descriptors(&self) -> Result<Vec<MemoryDescriptor>, ErrorHandle> {
+        Ok(self.descriptors.read()?.clone())
+    }
+
+    #[doc = "registrar de novo o mesmo id substitui o watch anterior"]
+    pub fn add_watch(
+        &self,
+        id: u32,
+        address: MemoryAddress,
+        len: usize,
+    ) -> Result<(), ErrorHandle> {
+        if len == 0 {
+            return Err(ErrorHandle::new(
+                "Um watch precisa observar pelo menos um byte",
+            ));
+        }
+
+        if len > MAX_WATCH_LEN {
+            return Err(ErrorHandle::new(&format!(
+                "Um watch pode observar no máximo {MAX_WATCH_LEN} bytes"
+            )));
+        }
+
+        let mut watches = self.watches.lock()?;
+        watches.retain(|watch| watch.id != id);
+        watches.push(MemoryWatch {
+            id,
+            address,
+            len,
+            last: None,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_watch(&self, id: u32) -> Result<bool, ErrorHandle> {
+        let mut watches = self.watches.lock()?;
+        let count = watches.len();
+        watches.retain(|watch| watch.id != id);
+
+        Ok(watches.len() != count)
+    }
+
+    #[doc = "remove os descritores e os watches, que só valem para o jogo que foi descarregado"]
+    pub fn clear(&self) -> Result<(), ErrorHandle> {
+        self.descriptors.write()?.clear();
+        self.watches.lock()?.clear();
+
+        Ok(())
+    }
+
+    pub(crate) fn read(
+        &self,
+        address: MemoryAddress,
+        len: usize,
+        regions: &RegionLookup,
+    ) -> Result<Vec<u8>, ErrorHandle> {
+        let pointers = self.byte_pointers(address, len, regions, false)?;
+
+        Ok(pointers.into_iter().map(|ptr| unsafe { *ptr }).collect())
+    }
+
+    pub(crate) fn write(
+        &self,
+        address: MemoryAddress,
+        data: &[u8],
+        regions: &RegionLookup,
+    ) -> Result<(), ErrorHandle> {
+        let pointers = self.byte_pointers(address, data.len(), regions, true)?;
+
+        for (ptr, value) in pointers.into_iter().zip(data) {
+            unsafe { *ptr = *value };
+        }
+
+        Ok(())
+    }
+
+    #[doc = "compara os watches com o frame anterior, a primeira leitura de cada watch só guarda o valor"]
+    pub(crate) fn check_watches(
+        &self,
+        regions: &RegionLookup,
+    ) -> Result<Vec<MemoryWatchEvent>, ErrorHandle> {
+        let mut events = Vec::new();
+
+        for watch in &mut *self.watches.lock()? {
+            // um endereço que o jogo não expõe mais apenas deixa de ser comparado
+            let Ok(value) = self.read(watch.address, watch.len, regions) else {
+                continue;
+            };
+
+            match watch.last.replace(value.clone()) {
+                Some(old) if old != value => events.push(MemoryWatchEvent {
+                    id: watch.id,
+                    address: watch.address,
+                    old,
+                    new: value,
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(events)
+    }
+
+    // cada byte é traduzido sozinho porque uma leitura pode atravessar mais de um descritor
+    fn byte_pointers(
+        &self,
+        address: MemoryAddress,
+        len: usize,
+        regions: &RegionLookup,
+        write: bool,
+    ) -> Result<Vec<*mut u8>, ErrorHandle> {
+        let out_of_range = || ErrorHandle::new(&format!("O endereço {address:?} não está mapeado"));
+
+        match address {
+            MemoryAddress::Region(region, offset) => {
+                let (ptr, size) = regions(region)
+                    .filter(|(ptr, size)| !ptr.is_null() && *size > 0)
+                    .ok_or_else(|| {
+                        ErrorHandle::new(&format!("O núcleo não expõe a região {region:?}"))
+                    })?;
+
+                match offset.checked_add(len) {
+                    Some(end) if end <= size => {
+                        Ok((offset..end).map(|i| unsafe { ptr.add(i) }).collect())
+                    }
+                    _ => Err(out_of_range()),
+                }
+            }
+            MemoryAddress::Virtual(start) => {
+                let descriptors = self.descriptors.read()?;
+
+                if descriptors.is_empty() {
+                    return self.byte_pointers(
+                        MemoryAddress::Region(MemoryRegion::SystemRam, start),
+                        len,
+                        regions,
+                        write,
+                    );
+                }
+
+                // o len vem de fora, então nada é reservado antes dos endereços serem validados
+                start.checked_add(len).ok_or_else(out_of_range)?;
+                let mut pointers = Vec::new();
+
+                for i in 0..len {
+                    let virtual_address = start + i;
+                    let descriptor = descriptors
+                        .iter()
+                        .find(|descriptor| descriptor.contains(virtual_address))
+                        .ok_or_else(out_of_range)?;
+
+                    if write && descriptor.is_const() {
+                        return Err(ErrorHandle::new(&format!(
+                            "O endereço {virtual_address:#x} é somente leitura"
+                        )));
+                    }
+
+                    let offset = descriptor.physical_offset(virtual_address);
+                    pointers.push((descriptor.ptr as *mut u8).wrapping_add(offset));
+                }
+
+                Ok(pointers)
+            }
+        }
+    }
+}
+
+// completa o select e o len que o núcleo deixou em zero, seguindo as regras do libretro.h
+fn preprocess_descriptors(raw_descriptors: &[retro_memory_descriptor]) -> Vec<MemoryDescriptor> {
+    let mut top_addr: usize = 1;
+
+    for raw in raw_descriptors {
+        if raw.select != 0 {
+            top_addr |= raw.select;
+        } else {
+            top_addr |= raw.start.wrapping_add(raw.len).wrapping_sub(1);
+        }
+    }
+
+    let top_addr = add_bits_down(top_addr);
+    let mut descriptors = Vec::new();
+
+    for raw in raw_descriptors {
+        // sem memória ou sem como calcular o select o descritor não pode ser usado
+        if raw.ptr.is_null() || (raw.select == 0 && raw.len == 0) {
+            continue;
+        }
+
+        let select = if raw.select == 0 {
+            top_addr & !inflate(add_bits_down(raw.len - 1), raw.disconnect)
+        } else {
+            raw.select
+        };
+
+        let len = if raw.len == 0 {
+            add_bits_down(reduce(top_addr & !select, raw.disconnect)) + 1
+        } else {
+            raw.len
+        };
+
+        if raw.start & !select != 0 {
+            continue;
+        }
+
+        descriptors.push(MemoryDescriptor {
+            flags: raw.flags,
+            offset: raw.offset,
+            start: raw.start,
+            select,
+            disconnect: raw.disconnect,
+            len,
+            addrspace: get_str_from_ptr(raw.addrspace),
+            ptr: raw.ptr as usize,
+        });
+    }
+
+    descriptors
+}
+
+fn add_bits_down(mut n: usize) -> usize {
+    let mut shift = 1;
+
+    while shift < usize::BITS {
+        n |= n >> shift;
+        shift <<= 1;
+    }
+
+    n
+}
+
+fn highest_bit(n: usize) -> usize {
+    let n = add_bits_down(n);
+    n ^ (n >> 1)
+}
+
+// insere um bit zero em cada posição marcada na mascara
+fn inflate(mut addr: usize, mut mask: usize) -> usize {
+    while mask != 0 {
+        let tmp = (mask - 1) & !mask;
+        addr = ((addr & !tmp) << 1) | (addr & tmp);
+        mask &= mask - 1;
+    }
+
+    addr
+}
+
+// remove os bits marcados na mascara, o inverso do inflate
+fn reduce(mut addr: usize, mut mask: usize) -> usize {
+    while mask != 0 {
+        let tmp = (mask - 1) & !mask;
+        addr = (addr & tmp) | ((addr >> 1) & !tmp);
+        mask = (mask & (mask - 1)) >> 1;
+    }
+
+    addr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::ptr::null;
+
+    fn descriptor(
+        ptr: &mut [u8],
+        start: usize,
+        select: usize,
+        len: usize,
+    ) -> retro_memory_descriptor {
+        retro_memory_descriptor {
+            flags: 0,
+            ptr: ptr.as_mut_ptr() as *mut _,
+            offset: 0,
+            start,
+            select,
+            disconnect: 0,
+            len,
+            addrspace: null(),
+        }
+    }
+
+    #[test]
+    fn test_translate_virtual_addresses() {
+        let mut wram = vec![0u8; 0x20000];
+        let mut rom = vec![0u8; 0x8000];
+        let addrspace = CString::new("ROM").unwrap();
+
+        let mut rom_descriptor = descriptor(&mut rom, 0x8000, 0, 0x8000);
+        rom_descriptor.flags = RETRO_MEMDESC_CONST as u64;
+        rom_descriptor.addrspace = addrspace.as_ptr();
+
+        // a ordem importa, o espelho da low ram tem prioridade sobre a rom
+        let raw = [
+            descriptor(&mut wram, 0x7E0000, 0xFE0000, 0x20000),
+            descriptor(&mut wram, 0x000000, 0x40E000, 0x2000),
+            rom_descriptor,
+        ];
+        let map = retro_memory_map {
+            descriptors: raw.as_ptr(),
+            num_descriptors: raw.len() as u32,
+        };
+
+        let manager = MemoryManager::new();
+        manager.set_memory_maps(&map).unwrap();
+
+        let descriptors = manager.descriptors().unwrap();
+        assert_eq!(descriptors.len(), 3);
+        assert_eq!(descriptors[2].addrspace, "ROM");
+        assert_eq!(descriptors[2].select, 0xFF8000);
+
+        let no_regions = |_| None;
+        manager
+            .write(MemoryAddress::Virtual(0x7E1234), &[0xAB, 0xCD], &no_regions)
+            .unwrap();
+        assert_eq!(&wram[0x1234..0x1236], &[0xAB, 0xCD]);
+
+        // o espelho em outro banco aponta para o mesmo byte
+        assert_eq!(
+            manager
+                .read(MemoryAddress::Virtual(0x801234), 2, &no_regions)
+                .unwrap(),
+            vec![0xAB, 0xCD]
+        );
+
+        assert!(
+            manager
+                .write(MemoryAddress::Virtual(0x8000), &[1], &no_regions)
+                .is_err()
+        );
+        assert!(
+            manager
+                .read(MemoryAddress::Virtual(0x400000), 1, &no_regions)
+                .is_err()
+        );
+        assert!(
+            manager
+                .read(MemoryAddress::Virtual(0x7E0000), usize::MAX, &no_regions)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_value_width_byte_order() {
+        assert_eq!(ValueWidth::U16.encode(0x1234, false), vec![0x34, 0x12]);
+        assert_eq!(ValueWidth::U16.encode(0x1234, true), vec![0x12, 0x34]);
+        assert_eq!(ValueWidth::U8.encode(0x1FF, true), vec![0xFF]);
+        assert_eq!(ValueWidth::U32.decode(&[1, 0, 0, 0], false), 1);
+        assert_eq!(ValueWidth::U32.decode(&[0, 0, 0, 1], true), 1);
+        assert_eq!(ValueWidth::U16.decode(&[0x12, 0x34, 0x56], true), 0x1234);
+    }
+
+    #[test]
+    fn test_watches_fire_only_on_change() {
+        let mut ram = vec![0u8; 16];
+        let ptr = ram.as_mut_ptr();
+        let size = ram.len();
+        let regions = move |region| (region == MemoryRegion::SystemRam).then_some((ptr, size));
+
+        let manager = MemoryManager::new();
+        manager.add_watch(1, MemoryAddress::Virtual(4), 2).unwrap();
+        manager
+            .add_watch(2, MemoryAddress::Region(MemoryRegion::VideoRam, 0), 1)
+            .unwrap();
+        assert!(manager.add_watch(3, MemoryAddress::Virtual(0), 0).is_err());
+        assert!(
+            manager
+                .add_watch(3, MemoryAddress::Virtual(0), usize::MAX)
+                .is_err()
+        );
+
+        assert!(manager.check_watches(&regions).unwrap().is_empty());
+
+        manager
+            .write(
+                MemoryAddress::Region(MemoryRegion::SystemRam, 5),
+                &[7],
+                &regions,
+            )
+            .unwrap();
+
+        assert_eq!(
+            manager.check_watches(&regions).unwrap(),
+            vec![MemoryWatchEvent {
+                id: 1,
+                address: MemoryAddress::Virtual(4),
+                old: vec![0, 0],
+                new: vec![0, 7],
+            }]
+        );
+        assert!(manager.check_watches(&regions).unwrap().is_empty());
+
+        assert!(manager.remove_watch(1).unwrap());
+        assert!(!manager.remove_watch(1).unwrap());
+    }
+}
@@ -0,0 +1,204 @@
+use generics::error_handle::ErrorHandle;
+use libretro_sys::binding_libretro::{
+    retro_log_level, retro_message, retro_message_ext, retro_message_target, retro_message_type,
+};
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+// taxa usada para converter a duração em frames quando o núcleo ainda não informou o fps
+const FALLBACK_MESSAGE_FPS: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreMessageLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreMessageTarget {
+    #[doc = "mostrar na tela e também registrar no log"]
+    All,
+    Osd,
+    Log,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreMessageKind {
+    Notification,
+    #[doc = "notificação que o frontend pode exibir de forma alternativa, como em outro canto da tela"]
+    NotificationAlt,
+    #[doc = "indicador persistente, como o fps interno do núcleo"]
+    Status,
+    Progress,
+}
+
+/// A message the core wants to show to the user, like "Disk 2 inserted".
+///
+/// Messages sent with the legacy `RETRO_ENVIRONMENT_SET_MESSAGE` only carry a text and a
+/// duration in frames, so the remaining fields get the same defaults RetroArch uses for them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreMessage {
+    pub text: String,
+    pub duration_ms: u32,
+    #[doc = "mensagens com prioridade maior devem substituir as de prioridade menor na tela"]
+    pub priority: u32,
+    pub level: CoreMessageLevel,
+    pub target: CoreMessageTarget,
+    pub kind: CoreMessageKind,
+    #[doc = "porcentagem de 0 a 100, None quando o progresso é indeterminado ou não se aplica"]
+    pub progress: Option<u8>,
+}
+
+impl CoreMessage {
+    pub fn from_legacy(raw: &retro_message, fps: f64) -> Self {
+        let fps = if fps > 0.0 { fps } else { FALLBACK_MESSAGE_FPS };
+
+        Self {
+            text: text_from_ptr(raw.msg),
+            duration_ms: (raw.frames as f64 * 1000.0 / fps).round() as u32,
+            priority: 0,
+            level: CoreMessageLevel::Info,
+            target: CoreMessageTarget::All,
+            kind: CoreMessageKind::Notification,
+            progress: None,
+        }
+    }
+
+    pub fn from_ext(raw: &retro_message_ext) -> Self {
+        let level = match raw.level {
+            retro_log_level::RETRO_LOG_DEBUG => CoreMessageLevel::Debug,
+            retro_log_level::RETRO_LOG_WARN => CoreMessageLevel::Warn,
+            retro_log_level::RETRO_LOG_ERROR => CoreMessageLevel::Error,
+            _ => CoreMessageLevel::Info,
+        };
+
+        let target = match raw.target {
+            retro_message_target::RETRO_MESSAGE_TARGET_OSD => CoreMessageTarget::Osd,
+            retro_message_target::RETRO_MESSAGE_TARGET_LOG => CoreMessageTarget::Log,
+            _ => CoreMessageTarget::All,
+        };
+
+        let kind = match raw.type_ {
+            retro_message_type::RETRO_MESSAGE_TYPE_NOTIFICATION_ALT => {
+                CoreMessageKind::NotificationAlt
+            }
+            retro_message_type::RETRO_MESSAGE_TYPE_STATUS => CoreMessageKind::Status,
+            retro_message_type::RETRO_MESSAGE_TYPE_PROGRESS => CoreMessageKind::Progress,
+            _ => CoreMessageKind::Notification,
+        };
+
+        // o núcleo usa -1 para progresso indeterminado
+        let progress = if kind == CoreMessageKind::Progress && raw.progress >= 0 {
+            Some(raw.progress.min(100) as u8)
+        } else {
+            None
+        };
+
+        Self {
+            text: text_from_ptr(raw.msg),
+            duration_ms: raw.duration,
+            priority: raw.priority,
+            level,
+            target,
+            kind,
+            progress,
+        }
+    }
+}
+
+// mensagens de núcleos antigos nem sempre são utf-8 válido
+fn text_from_ptr(ptr: *const std::ffi::c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Messages sent by the core that the frontend hasn't picked up yet.
+///
+/// The core sends them from inside `retro_run` or `retro_load_game`, so they are queued
+/// here and taken by the frontend once the call returns.
+#[derive(Default)]
+pub struct MessageManager {
+    pending: Mutex<Vec<CoreMessage>>,
+}
+
+impl MessageManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, message: CoreMessage) -> Result<(), ErrorHandle> {
+        self.pending.lock()?.push(message);
+        Ok(())
+    }
+
+    pub fn take_all(&self) -> Result<Vec<CoreMessage>, ErrorHandle> {
+        Ok(std::mem::take(&mut *self.pending.lock()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_decode_messages() {
+        let text = CString::new("Disk 2 inserted").unwrap();
+
+        let legacy = CoreMessage::from_legacy(
+            &retro_message {
+                msg: text.as_ptr(),
+                frames: 180,
+            },
+            60.0,
+        );
+        assert_eq!(legacy.text, "Disk 2 inserted");
+        assert_eq!(legacy.duration_ms, 3000);
+        assert_eq!(legacy.target, CoreMessageTarget::All);
+
+        let mut raw_ext = retro_message_ext {
+            msg: text.as_ptr(),
+            duration: 1500,
+            priority: 3,
+            level: retro_log_level::RETRO_LOG_WARN,
+            target: retro_message_target::RETRO_MESSAGE_TARGET_OSD,
+            type_: retro_message_type::RETRO_MESSAGE_TYPE_PROGRESS,
+            progress: 42,
+        };
+
+        let ext = CoreMessage::from_ext(&raw_ext);
+        assert_eq!(ext.duration_ms, 1500);
+        assert_eq!(ext.priority, 3);
+        assert_eq!(ext.level, CoreMessageLevel::Warn);
+        assert_eq!(ext.target, CoreMessageTarget::Osd);
+        assert_eq!(ext.kind, CoreMessageKind::Progress);
+        assert_eq!(ext.progress, Some(42));
+
+        raw_ext.progress = -1;
+        assert_eq!(CoreMessage::from_ext(&raw_ext).progress, None);
+    }
+
+    #[test]
+    fn test_take_all_empties_the_queue() {
+        let manager = MessageManager::new();
+        let text = CString::new("Saved memory card").unwrap();
+        let message = CoreMessage::from_legacy(
+            &retro_message {
+                msg: text.as_ptr(),
+                frames: 60,
+            },
+            0.0,
+        );
+
+        manager.push(message.clone()).unwrap();
+        assert_eq!(manager.take_all().unwrap(), vec![message]);
+        assert!(manager.take_all().unwrap().is_empty());
+    }
+}
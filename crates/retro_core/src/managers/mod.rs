@@ -1,2 +1,15 @@
 pub mod args_manager;
+pub mod cheat_finder_manager;
+pub mod cheat_manager;
+pub mod controller_type_manager;
+pub mod disk_control_manager;
+pub mod memory_manager;
+pub mod message_manager;
 pub mod option_manager;
+pub mod playtime_manager;
+pub mod rewind_manager;
+pub mod run_ahead_manager;
+pub mod save_ram_manager;
+pub mod save_state_manager;
+pub mod serialization_manager;
+pub mod speed_manager;
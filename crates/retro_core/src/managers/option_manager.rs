@@ -1,18 +1,22 @@
-use crate::tools::ffi_tools::get_arc_string_from_ptr;
+use crate::tools::ffi_tools::{get_arc_string_from_ptr, get_str_from_ptr};
+use crate::tools::game_tools::RomTools;
 use crate::{
     libretro_sys::binding_libretro::{
-        retro_core_option_v2_category, retro_core_option_v2_definition, retro_core_options_v2_intl,
+        retro_core_option_definition, retro_core_option_v2_category,
+        retro_core_option_v2_definition, retro_core_options_intl,
+        retro_core_options_update_display_callback_t, retro_core_options_v2_intl, retro_variable,
     },
     tools::mutex_tools::get_string_rwlock_from_ptr,
 };
 use generics::constants::{CORE_OPTION_EXTENSION_FILE, MAX_CORE_OPTIONS};
 use generics::error_handle::ErrorHandle;
-use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
 use std::{
-    fs::File,
+    fs::{self, File},
     io::{Read, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Mutex, RwLock},
 };
 
@@ -44,12 +48,58 @@ pub struct Categories {
     pub desc: Arc<String>,
 }
 
+#[doc = "cópia de uma opção do núcleo que pode ser enviada para outras threads"]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreOptInfo {
+    pub key: String,
+    pub desc: String,
+    pub desc_categorized: String,
+    pub info: String,
+    pub category_key: String,
+    pub selected: String,
+    pub default_value: String,
+    #[doc = "pares de (valor, label) na ordem enviada pelo núcleo"]
+    pub values: Vec<(String, String)>,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryInfo {
+    pub key: String,
+    pub desc: String,
+    pub info: String,
+}
+
+#[doc = "arquivos que podem sobrescrever os valores salvos para todo o núcleo"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionOverride {
+    Game,
+    #[doc = "vale para todos os jogos que estão na mesma pasta do conteúdo"]
+    Directory,
+}
+
+/// Options chosen by the user for the current core.
+///
+/// Values are resolved in layers when a game is loaded: the core file
+/// (`<opt>/<library_name>.opt`), then the content directory override
+/// (`<opt>/<library_name>/directories/<dir_name>.opt`) and finally the game override
+/// (`<opt>/<library_name>/<rom_name>.opt`). Changes are written to the most specific
+/// layer that exists on disk.
 #[derive(Default, Debug)]
 pub struct OptionManager {
+    #[doc = "arquivo com os valores usados por todos os jogos do núcleo"]
     pub file_path: RwLock<PathBuf>,
+    game_file: RwLock<Option<PathBuf>>,
+    directory_file: RwLock<Option<PathBuf>>,
     pub categories: RwLock<Vec<Categories>>,
     pub updated_count: AtomicU16,
     pub opts: Mutex<Vec<CoreOpt>>,
+    // marcado sempre que as opções, os valores ou a visibilidade mudam, até o frontend ler
+    changed: AtomicBool,
+    // o núcleo usa essa callback para esconder ou mostrar opções que dependem de outras
+    display_callback: RwLock<retro_core_options_update_display_callback_t>,
+    // a callback só pode ser chamada entre o load e o unload do jogo
+    game_running: AtomicBool,
 }
 
 impl OptionManager {
@@ -61,13 +111,134 @@ impl OptionManager {
             updated_count: AtomicU16::new(0),
             categories: RwLock::new(Vec::new()),
             file_path: RwLock::new(file_path),
+            game_file: RwLock::new(None),
+            directory_file: RwLock::new(None),
             opts: Mutex::new(Vec::new()),
+            display_callback: RwLock::new(None),
+            game_running: AtomicBool::new(false),
+            changed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<CoreOptInfo>, ErrorHandle> {
+        let mut list = Vec::new();
+
+        for core_opt in &*self.opts.lock()? {
+            let values = core_opt
+                .values
+                .lock()?
+                .iter()
+                .map(|core_value| (core_value.value.to_string(), core_value.label.to_string()))
+                .collect();
+
+            list.push(CoreOptInfo {
+                key: core_opt.key.to_string(),
+                desc: core_opt.desc.to_string(),
+                desc_categorized: core_opt.desc_categorized.to_string(),
+                info: core_opt.info.to_string(),
+                category_key: core_opt.category_key.to_string(),
+                selected: core_opt.selected.read()?.clone(),
+                default_value: core_opt.default_value.to_string(),
+                values,
+                visible: core_opt.visibility.load(Ordering::SeqCst),
+            });
         }
+
+        Ok(list)
+    }
+
+    pub fn list_categories(&self) -> Result<Vec<CategoryInfo>, ErrorHandle> {
+        Ok(self
+            .categories
+            .read()?
+            .iter()
+            .map(|category| CategoryInfo {
+                key: category.key.to_string(),
+                desc: category.desc.to_string(),
+                info: category.info.to_string(),
+            })
+            .collect())
+    }
+
+    #[doc = "retorna true uma única vez depois de cada mudança nas opções"]
+    pub fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::SeqCst)
     }
 
+    #[doc = "o núcleo recebe o novo valor pelo GET_VARIABLE_UPDATE no próximo frame"]
     pub fn update_opt(&self, opt_key: &str, new_value_selected: &str) -> Result<(), ErrorHandle> {
-        self.change_value_selected(opt_key, new_value_selected)?;
+        if !self.change_value_selected(opt_key, new_value_selected)? {
+            return Err(ErrorHandle::new(&format!(
+                "A opção {opt_key} não aceita o valor {new_value_selected}"
+            )));
+        }
+
         self.write_all_options_in_file()?;
+        self.update_display()?;
+
+        Ok(())
+    }
+
+    pub fn set_display_callback(
+        &self,
+        callback: retro_core_options_update_display_callback_t,
+    ) -> Result<(), ErrorHandle> {
+        *self.display_callback.write()? = callback;
+        Ok(())
+    }
+
+    pub(crate) fn set_game_running(&self, running: bool) {
+        self.game_running.store(running, Ordering::SeqCst);
+    }
+
+    #[doc = "pede para o núcleo atualizar a visibilidade das opções, deve ser chamada na thread do núcleo"]
+    pub fn update_display(&self) -> Result<bool, ErrorHandle> {
+        // a callback chama RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY, então nenhum lock pode
+        // continuar preso enquanto ela roda
+        let callback = *self.display_callback.read()?;
+
+        match callback {
+            Some(callback) => Ok(unsafe { callback() }),
+            None => Ok(false),
+        }
+    }
+
+    #[doc = "o núcleo trocou o valor sozinho, então ele não precisa ser avisado pelo GET_VARIABLE_UPDATE"]
+    pub fn set_value_from_core(&self, opt_key: &str, value: &str) -> Result<bool, ErrorHandle> {
+        let mut changed = false;
+
+        for core_opt in &*self.opts.lock()? {
+            if *core_opt.key != opt_key {
+                continue;
+            }
+
+            if core_opt
+                .values
+                .lock()?
+                .iter()
+                .any(|core_value| *core_value.value == value)
+            {
+                *core_opt.selected.write()? = value.to_string();
+                changed = true;
+            }
+
+            break;
+        }
+
+        if changed {
+            self.changed.store(true, Ordering::SeqCst);
+            self.write_all_options_in_file()?;
+        }
+
+        Ok(changed)
+    }
+
+    // cada SET_VARIABLES ou SET_CORE_OPTIONS substitui todas as opções enviadas antes
+    fn clear(&self) -> Result<(), ErrorHandle> {
+        self.opts.lock()?.clear();
+        self.categories.write()?.clear();
+        self.updated_count.store(0, Ordering::SeqCst);
+        self.changed.store(true, Ordering::SeqCst);
 
         Ok(())
     }
@@ -102,14 +273,9 @@ impl OptionManager {
                 continue;
             }
 
-            core_opt.visibility.store(visibility, Ordering::SeqCst);
-
-            if !visibility && core_opt.need_update.load(Ordering::SeqCst) {
-                core_opt.need_update.store(false, Ordering::SeqCst);
-                self.updated_count.fetch_sub(1, Ordering::SeqCst);
-            } else if visibility && !core_opt.need_update.load(Ordering::SeqCst) {
-                core_opt.need_update.store(true, Ordering::SeqCst);
-                self.updated_count.fetch_add(1, Ordering::SeqCst);
+            // a visibilidade não muda o valor, então o núcleo não precisa reler a opção
+            if core_opt.visibility.swap(visibility, Ordering::SeqCst) != visibility {
+                self.changed.store(true, Ordering::SeqCst);
             }
         }
 
@@ -117,8 +283,16 @@ impl OptionManager {
     }
 
     fn write_all_options_in_file(&self) -> Result<(), ErrorHandle> {
-        let file_path = self.file_path.read()?.clone();
-        let mut file = File::create(file_path.clone())?;
+        let file_path = self.active_file()?;
+        self.write_all_options_to(&file_path)
+    }
+
+    fn write_all_options_to(&self, file_path: &Path) -> Result<(), ErrorHandle> {
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = File::create(file_path)?;
 
         for opt in &*self.opts.lock()? {
             let key = &*opt.key;
@@ -136,7 +310,7 @@ impl OptionManager {
         &self,
         opt_key: &str,
         new_value_selected: &str,
-    ) -> Result<(), ErrorHandle> {
+    ) -> Result<bool, ErrorHandle> {
         for core_opt in &*self.opts.lock()? {
             if !core_opt.key.clone().to_string().eq(&opt_key) {
                 continue;
@@ -147,64 +321,160 @@ impl OptionManager {
                     continue;
                 }
 
-                if !core_opt.need_update.load(Ordering::SeqCst) {
-                    *core_opt.selected.write()? = new_value_selected.to_string();
+                // um segundo valor antes do núcleo ler o primeiro apenas substitui o anterior
+                *core_opt.selected.write()? = new_value_selected.to_string();
 
+                if !core_opt.need_update.swap(true, Ordering::SeqCst) {
                     self.updated_count.fetch_add(1, Ordering::SeqCst);
-                    core_opt.need_update.store(true, Ordering::SeqCst);
                 }
 
-                return Ok(());
+                self.changed.store(true, Ordering::SeqCst);
+
+                return Ok(true);
             }
         }
 
-        Ok(())
+        Ok(false)
     }
 
-    fn load_all_option_in_file(&self) -> Result<(), ErrorHandle> {
+    pub fn try_reload_pref_option(&self) -> Result<(), ErrorHandle> {
         let file_path = self.file_path.read()?.clone();
 
-        let mut file = File::open(file_path)?;
+        //se o arquivo ainda não existe apenas
+        //crie um novo arquivo e salve a configuração padrão do núcleo
+        if !file_path.exists() {
+            self.write_all_options_to(&file_path)?;
+        }
 
-        let mut buf = String::new();
-        file.read_to_string(&mut buf)?;
+        self.apply_layers()
+    }
 
-        let lines: Vec<&str> = buf.split('\n').collect();
+    #[doc = "procura os overrides do conteúdo e aplica os valores deles por cima dos valores do núcleo"]
+    pub fn load_overrides(&self, content_path: &Path, rom_name: &str) -> Result<(), ErrorHandle> {
+        let file_path = self.file_path.read()?.clone();
+        let mut override_dir = file_path.clone();
+        override_dir.set_extension("");
+
+        let mut game_file = override_dir.join(RomTools::sanitize_filename(rom_name));
+        game_file.set_extension(CORE_OPTION_EXTENSION_FILE);
+
+        let directory_file = content_path
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .map(|dir_name| {
+                let mut directory_file = override_dir
+                    .join("directories")
+                    .join(RomTools::sanitize_filename(&dir_name.to_string_lossy()));
+                directory_file.set_extension(CORE_OPTION_EXTENSION_FILE);
+                directory_file
+            });
+
+        *self.game_file.write()? = Some(game_file);
+        *self.directory_file.write()? = directory_file;
+
+        self.apply_layers()
+    }
 
-        for line in &lines {
-            if line.is_empty() {
-                return Ok(());
-            }
+    #[doc = "volta a usar apenas os valores do núcleo"]
+    pub fn unload_overrides(&self) -> Result<(), ErrorHandle> {
+        self.game_file.write()?.take();
+        self.directory_file.write()?.take();
 
-            let values: Vec<&str> = line.split('=').collect();
+        self.apply_layers()
+    }
 
-            if let Some(opt_key) = values.first() {
-                let value_selected = values
-                    .get(1)
-                    .expect("nao foi possível recupera o valor do arquivo de opções")
-                    .split_ascii_whitespace()
-                    .next()
-                    .expect("nao foi possível recupera o valor do arquivo de opções");
+    #[doc = "salva os valores atuais de todas as opções no override, que passa a receber as próximas mudanças"]
+    pub fn save_override(&self, layer: OptionOverride) -> Result<(), ErrorHandle> {
+        let file_path = self.override_file(layer)?;
+        self.write_all_options_to(&file_path)
+    }
 
-                let _ = self.change_value_selected(opt_key, value_selected);
-            }
+    #[doc = "apaga o override e reaplica as camadas que sobraram"]
+    pub fn clear_override(&self, layer: OptionOverride) -> Result<(), ErrorHandle> {
+        let file_path = self.override_file(layer)?;
+
+        if file_path.exists() {
+            fs::remove_file(file_path)?;
         }
 
-        Ok(())
+        self.apply_layers()
     }
 
-    pub fn try_reload_pref_option(&self) -> Result<(), ErrorHandle> {
-        let file_path = self.file_path.read()?;
+    #[doc = "o override mais específico que existe no disco, None quando os valores vêm do núcleo"]
+    pub fn active_override(&self) -> Result<Option<OptionOverride>, ErrorHandle> {
+        let exists = |file: &Option<PathBuf>| file.as_ref().is_some_and(|file| file.exists());
 
-        //se o arquivo ainda não existe apenas
-        //crie um novo arquivo e salve a configuração padrão do núcleo
-        if !file_path.exists() {
-            self.write_all_options_in_file()
+        if exists(&*self.game_file.read()?) {
+            Ok(Some(OptionOverride::Game))
+        } else if exists(&*self.directory_file.read()?) {
+            Ok(Some(OptionOverride::Directory))
         } else {
-            self.load_all_option_in_file()
+            Ok(None)
+        }
+    }
+
+    fn override_file(&self, layer: OptionOverride) -> Result<PathBuf, ErrorHandle> {
+        let file = match layer {
+            OptionOverride::Game => self.game_file.read()?.clone(),
+            OptionOverride::Directory => self.directory_file.read()?.clone(),
+        };
+
+        file.ok_or_else(|| ErrorHandle::new("Nenhum conteúdo carregado para salvar o override"))
+    }
+
+    fn active_file(&self) -> Result<PathBuf, ErrorHandle> {
+        match self.active_override()? {
+            Some(layer) => self.override_file(layer),
+            None => Ok(self.file_path.read()?.clone()),
         }
     }
 
+    // só as opções cujo valor final mudou são marcadas, assim o núcleo não relê o resto
+    fn apply_layers(&self) -> Result<(), ErrorHandle> {
+        let layers = [
+            Some(self.file_path.read()?.clone()),
+            self.directory_file.read()?.clone(),
+            self.game_file.read()?.clone(),
+        ];
+
+        let mut saved_values = HashMap::new();
+
+        for file_path in layers.into_iter().flatten() {
+            if file_path.exists() {
+                saved_values.extend(read_option_file(&file_path)?);
+            }
+        }
+
+        let mut changes = Vec::new();
+
+        for core_opt in &*self.opts.lock()? {
+            // um valor que o núcleo não aceita mais volta para o padrão
+            let values = core_opt.values.lock()?;
+            let value = saved_values
+                .get(core_opt.key.as_str())
+                .filter(|value| values.iter().any(|core_value| *core_value.value == **value))
+                .cloned()
+                .unwrap_or_else(|| core_opt.default_value.to_string());
+
+            if *core_opt.selected.read()? != value {
+                changes.push((core_opt.key.clone(), value));
+            }
+        }
+
+        let changed = !changes.is_empty();
+
+        for (key, value) in changes {
+            self.change_value_selected(&key, &value)?;
+        }
+
+        // opções que dependem das que mudaram podem precisar aparecer ou sumir
+        if changed && self.game_running.load(Ordering::SeqCst) {
+            self.update_display()?;
+        }
+
+        Ok(())
+    }
+
     //===============================================
     //=================v2_intl=======================
     //===============================================
@@ -264,12 +534,20 @@ impl OptionManager {
                     let need_update = AtomicBool::new(false);
 
                     for retro_value in definition.values {
-                        if !retro_value.label.is_null() {
-                            let value = get_arc_string_from_ptr(retro_value.value);
-                            let label = get_arc_string_from_ptr(retro_value.label);
-
-                            values.lock()?.push(CoreValue { label, value });
+                        if retro_value.value.is_null() {
+                            break;
                         }
+
+                        let value = get_arc_string_from_ptr(retro_value.value);
+
+                        // o label é opcional, sem ele o próprio valor é exibido
+                        let label = if retro_value.label.is_null() {
+                            value.clone()
+                        } else {
+                            get_arc_string_from_ptr(retro_value.label)
+                        };
+
+                        values.lock()?.push(CoreValue { label, value });
                     }
 
                     self.opts.lock()?.push(CoreOpt {
@@ -298,6 +576,8 @@ impl OptionManager {
         &self,
         option_intl_v2: &mut retro_core_options_v2_intl,
     ) -> Result<(), ErrorHandle> {
+        self.clear()?;
+
         if option_intl_v2.local.is_null() {
             let us = unsafe {
                 option_intl_v2
@@ -323,4 +603,379 @@ impl OptionManager {
         Ok(())
     }
     //===============================================
+    //=================v1============================
+    //===============================================
+
+    fn get_v1_definitions(
+        &self,
+        definitions: *const retro_core_option_definition,
+    ) -> Result<(), ErrorHandle> {
+        let mut opts = Vec::new();
+        let mut index = 0;
+
+        // a lista termina com uma definição de key nula
+        while let Some(definition) = unsafe { definitions.add(index).as_ref() } {
+            if definition.key.is_null() {
+                break;
+            }
+
+            let mut values = Vec::new();
+
+            for retro_value in definition.values {
+                if retro_value.value.is_null() {
+                    break;
+                }
+
+                let value = get_arc_string_from_ptr(retro_value.value);
+
+                let label = if retro_value.label.is_null() {
+                    value.clone()
+                } else {
+                    get_arc_string_from_ptr(retro_value.label)
+                };
+
+                values.push(CoreValue { value, label });
+            }
+
+            let default_value = if definition.default_value.is_null() {
+                values
+                    .first()
+                    .map(|core_value| core_value.value.clone())
+                    .unwrap_or_default()
+            } else {
+                get_arc_string_from_ptr(definition.default_value)
+            };
+
+            let desc = get_arc_string_from_ptr(definition.desc);
+            let info = get_arc_string_from_ptr(definition.info);
+
+            opts.push(CoreOpt {
+                key: get_arc_string_from_ptr(definition.key),
+                visibility: AtomicBool::new(true),
+                need_update: AtomicBool::new(false),
+                selected: RwLock::new(default_value.to_string()),
+                desc_categorized: desc.clone(),
+                desc,
+                info_categorized: info.clone(),
+                info,
+                category_key: Arc::default(),
+                values: Mutex::new(values),
+                default_value,
+            });
+
+            index += 1;
+        }
+
+        self.opts.lock()?.extend(opts);
+
+        Ok(())
+    }
+
+    pub(crate) fn convert_option_v1(
+        &self,
+        definitions: *const retro_core_option_definition,
+    ) -> Result<(), ErrorHandle> {
+        self.clear()?;
+        self.get_v1_definitions(definitions)
+    }
+
+    pub fn convert_option_v1_intl(
+        &self,
+        option_intl: &retro_core_options_intl,
+    ) -> Result<(), ErrorHandle> {
+        // a tradução pode não existir para o idioma do usuário
+        let definitions = if option_intl.local.is_null() {
+            option_intl.us
+        } else {
+            option_intl.local
+        };
+
+        if definitions.is_null() {
+            return Err(ErrorHandle::new("us is null in option_intl"));
+        }
+
+        self.convert_option_v1(definitions)
+    }
+
+    //===============================================
+    //=================v0============================
+    //===============================================
+
+    pub(crate) fn convert_variables(
+        &self,
+        variables: *const retro_variable,
+    ) -> Result<(), ErrorHandle> {
+        self.clear()?;
+
+        let mut opts = Vec::new();
+        let mut index = 0;
+
+        while let Some(variable) = unsafe { variables.add(index).as_ref() } {
+            if variable.key.is_null() {
+                break;
+            }
+
+            let key = get_str_from_ptr(variable.key);
+            let value = get_str_from_ptr(variable.value);
+
+            if let Some(core_opt) = parse_v0_variable(&key, &value) {
+                opts.push(core_opt);
+            }
+
+            index += 1;
+        }
+
+        self.opts.lock()?.extend(opts);
+
+        Ok(())
+    }
+    //===============================================
+}
+
+fn read_option_file(file_path: &Path) -> Result<HashMap<String, String>, ErrorHandle> {
+    let mut file = File::open(file_path)?;
+
+    let mut buf = String::new();
+    file.read_to_string(&mut buf)?;
+
+    let mut values = HashMap::new();
+
+    for line in buf.lines() {
+        if line.is_empty() {
+            break;
+        }
+
+        // linhas quebradas são ignoradas para que um arquivo editado à mão não derrube o jogo
+        let Some((opt_key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        // valores como "No Stretching" têm espaços, então só as pontas são removidas
+        values.insert(opt_key.to_string(), value.trim().to_string());
+    }
+
+    Ok(values)
+}
+
+#[doc = "converte o formato `\"Descrição; valor1|valor2|valor3\"` do v0, o primeiro valor é o padrão"]
+pub fn parse_v0_variable(key: &str, raw_value: &str) -> Option<CoreOpt> {
+    let (desc, values) = raw_value.split_once(';')?;
+    let values: Vec<CoreValue> = values
+        .trim_start()
+        .split('|')
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            let value = Arc::new(value.to_string());
+
+            CoreValue {
+                label: value.clone(),
+                value,
+            }
+        })
+        .collect();
+
+    let default_value = values.first()?.value.clone();
+    let desc = Arc::new(desc.trim().to_string());
+
+    Some(CoreOpt {
+        key: Arc::new(key.to_string()),
+        visibility: AtomicBool::new(true),
+        need_update: AtomicBool::new(false),
+        selected: RwLock::new(default_value.to_string()),
+        desc_categorized: desc.clone(),
+        desc,
+        info: Arc::default(),
+        info_categorized: Arc::default(),
+        category_key: Arc::default(),
+        values: Mutex::new(values),
+        default_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::ptr::null;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_parse_v0_variable() {
+        let opt =
+            parse_v0_variable("snes9x_overclock", "SuperFX Overclock; 10MHz|20MHz|40MHz").unwrap();
+
+        assert_eq!(*opt.desc, "SuperFX Overclock");
+        assert_eq!(*opt.default_value, "10MHz");
+        assert_eq!(*opt.selected.read().unwrap(), "10MHz");
+
+        let values: Vec<String> = opt
+            .values
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|core_value| core_value.value.to_string())
+            .collect();
+        assert_eq!(values, vec!["10MHz", "20MHz", "40MHz"]);
+
+        assert!(parse_v0_variable("broken", "sem separador").is_none());
+        assert!(parse_v0_variable("empty", "Desc;").is_none());
+    }
+
+    #[test]
+    fn test_convert_variables_replaces_previous_options() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = OptionManager::new(&dir.path().display().to_string(), "core".to_string());
+
+        let key = CString::new("core_region").unwrap();
+        let value = CString::new("Region; auto|ntsc|pal").unwrap();
+        let variables = [
+            retro_variable {
+                key: key.as_ptr(),
+                value: value.as_ptr(),
+            },
+            retro_variable {
+                key: null(),
+                value: null(),
+            },
+        ];
+
+        manager.convert_variables(variables.as_ptr()).unwrap();
+        manager.convert_variables(variables.as_ptr()).unwrap();
+        assert_eq!(manager.opts.lock().unwrap().len(), 1);
+
+        assert!(manager.set_value_from_core("core_region", "pal").unwrap());
+        assert!(!manager.set_value_from_core("core_region", "secam").unwrap());
+        assert_eq!(
+            *manager.opts.lock().unwrap()[0].selected.read().unwrap(),
+            "pal"
+        );
+    }
+
+    #[test]
+    fn test_update_opt_is_reported_to_core_and_frontend() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manager = OptionManager::new(&dir.path().display().to_string(), "core".to_string());
+
+        let key = CString::new("core_region").unwrap();
+        let value = CString::new("Region; auto|ntsc|pal").unwrap();
+        let variables = [
+            retro_variable {
+                key: key.as_ptr(),
+                value: value.as_ptr(),
+            },
+            retro_variable {
+                key: null(),
+                value: null(),
+            },
+        ];
+
+        manager.convert_variables(variables.as_ptr()).unwrap();
+        assert!(manager.take_changed());
+        assert!(!manager.take_changed());
+
+        assert!(manager.update_opt("core_region", "secam").is_err());
+        manager.update_opt("core_region", "ntsc").unwrap();
+        manager.update_opt("core_region", "pal").unwrap();
+        assert_eq!(manager.updated_count.load(Ordering::SeqCst), 1);
+        assert!(manager.take_changed());
+
+        let list = manager.list().unwrap();
+        assert_eq!(list[0].selected, "pal");
+        assert_eq!(list[0].default_value, "auto");
+        assert!(list[0].visible);
+
+        assert_eq!(
+            manager.get_opt_value("core_region").unwrap(),
+            Some("pal".to_string())
+        );
+        assert_eq!(manager.updated_count.load(Ordering::SeqCst), 0);
+        assert_eq!(manager.get_opt_value("core_region").unwrap(), None);
+
+        manager
+            .change_visibility(&"core_region".to_string(), false)
+            .unwrap();
+        assert!(manager.take_changed());
+        assert!(!manager.list().unwrap()[0].visible);
+        assert_eq!(manager.updated_count.load(Ordering::SeqCst), 0);
+    }
+
+    static DISPLAY_UPDATES: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn count_display_update() -> bool {
+        DISPLAY_UPDATES.fetch_add(1, Ordering::SeqCst);
+        true
+    }
+
+    #[test]
+    fn test_overrides_are_layered_over_core_values() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let opt_dir = dir.path().display().to_string();
+        let manager = OptionManager::new(&opt_dir, "core".to_string());
+
+        let key = CString::new("core_region").unwrap();
+        let value = CString::new("Region; auto|ntsc|pal").unwrap();
+        let variables = [
+            retro_variable {
+                key: key.as_ptr(),
+                value: value.as_ptr(),
+            },
+            retro_variable {
+                key: null(),
+                value: null(),
+            },
+        ];
+        manager.convert_variables(variables.as_ptr()).unwrap();
+        manager.try_reload_pref_option().unwrap();
+        let selected = |manager: &OptionManager| manager.list().unwrap()[0].selected.clone();
+
+        // sem jogo carregado não existe override para salvar
+        assert!(manager.save_override(OptionOverride::Game).is_err());
+
+        let sonic = dir.path().join("genesis").join("sonic.md");
+        let streets = dir.path().join("genesis").join("streets.md");
+
+        manager.load_overrides(&sonic, "sonic").unwrap();
+        manager.save_override(OptionOverride::Directory).unwrap();
+        manager.update_opt("core_region", "ntsc").unwrap();
+        assert_eq!(
+            manager.active_override().unwrap(),
+            Some(OptionOverride::Directory)
+        );
+
+        manager.save_override(OptionOverride::Game).unwrap();
+        manager.update_opt("core_region", "pal").unwrap();
+        assert_eq!(
+            manager.active_override().unwrap(),
+            Some(OptionOverride::Game)
+        );
+
+        // o outro jogo da pasta usa o override da pasta, não o do sonic
+        manager.load_overrides(&streets, "streets").unwrap();
+        assert_eq!(selected(&manager), "ntsc");
+
+        manager.load_overrides(&sonic, "sonic").unwrap();
+        assert_eq!(selected(&manager), "pal");
+
+        manager
+            .set_display_callback(Some(count_display_update))
+            .unwrap();
+        manager.set_game_running(true);
+
+        manager.clear_override(OptionOverride::Game).unwrap();
+        assert_eq!(selected(&manager), "ntsc");
+        assert_eq!(DISPLAY_UPDATES.load(Ordering::SeqCst), 1);
+
+        manager.clear_override(OptionOverride::Directory).unwrap();
+        assert_eq!(manager.active_override().unwrap(), None);
+        assert_eq!(selected(&manager), "auto");
+        assert_eq!(DISPLAY_UPDATES.load(Ordering::SeqCst), 2);
+
+        // sem nenhum valor diferente a visibilidade não muda
+        manager.clear_override(OptionOverride::Directory).unwrap();
+        assert_eq!(DISPLAY_UPDATES.load(Ordering::SeqCst), 2);
+
+        manager.set_game_running(false);
+        manager.unload_overrides().unwrap();
+        assert!(manager.save_override(OptionOverride::Directory).is_err());
+    }
 }
@@ -0,0 +1,91 @@
+use generics::error_handle::ErrorHandle;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// um intervalo maior que isso entre dois frames é uma pausa e não conta como tempo de jogo
+const MAX_FRAME_GAP: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct Playtime {
+    total: Duration,
+    last_frame: Option<Instant>,
+}
+
+/// Wall clock time spent running the loaded game.
+///
+/// Only the time between frames counts, so pauses are left out. Loading a save state
+/// restores the playtime stored in it.
+#[derive(Default)]
+pub struct PlaytimeManager {
+    playtime: Mutex<Playtime>,
+}
+
+impl PlaytimeManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "chamado a cada frame executado"]
+    pub fn tick(&self) -> Result<(), ErrorHandle> {
+        self.tick_at(Instant::now())
+    }
+
+    fn tick_at(&self, now: Instant) -> Result<(), ErrorHandle> {
+        let mut playtime = self.playtime.lock()?;
+
+        if let Some(gap) = playtime
+            .last_frame
+            .map(|last| now.saturating_duration_since(last))
+            .filter(|gap| *gap <= MAX_FRAME_GAP)
+        {
+            playtime.total += gap;
+        }
+
+        playtime.last_frame = Some(now);
+
+        Ok(())
+    }
+
+    pub fn elapsed(&self) -> Result<Duration, ErrorHandle> {
+        Ok(self.playtime.lock()?.total)
+    }
+
+    pub fn set(&self, total: Duration) -> Result<(), ErrorHandle> {
+        let mut playtime = self.playtime.lock()?;
+        playtime.total = total;
+        playtime.last_frame = None;
+
+        Ok(())
+    }
+
+    pub fn reset(&self) -> Result<(), ErrorHandle> {
+        self.set(Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pauses_are_not_counted() {
+        let playtime = PlaytimeManager::new();
+        let start = Instant::now();
+
+        playtime.tick_at(start).unwrap();
+        playtime.tick_at(start + Duration::from_millis(16)).unwrap();
+        playtime.tick_at(start + Duration::from_millis(32)).unwrap();
+        assert_eq!(playtime.elapsed().unwrap(), Duration::from_millis(32));
+
+        // o jogo ficou pausado por um minuto
+        playtime.tick_at(start + Duration::from_secs(60)).unwrap();
+        assert_eq!(playtime.elapsed().unwrap(), Duration::from_millis(32));
+
+        playtime.set(Duration::from_secs(3600)).unwrap();
+        playtime.tick_at(start + Duration::from_secs(61)).unwrap();
+        assert_eq!(playtime.elapsed().unwrap(), Duration::from_secs(3600));
+
+        playtime.reset().unwrap();
+        assert_eq!(playtime.elapsed().unwrap(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,259 @@
+use generics::constants::{REWIND_BUFFER_SIZE_MB, REWIND_SNAPSHOT_INTERVAL};
+use generics::error_handle::ErrorHandle;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// In-memory rewind history.
+///
+/// Only the most recent snapshot is kept in full, every older one is stored as a
+/// delta that turns the snapshot after it back into itself. The oldest deltas are
+/// dropped once the history goes over the configured buffer size.
+pub struct RewindManager {
+    enabled: AtomicBool,
+    interval: AtomicUsize,
+    max_buffer_size: AtomicUsize,
+    frame_count: AtomicUsize,
+    history: Mutex<RewindHistory>,
+}
+
+#[derive(Default)]
+struct RewindHistory {
+    current: Vec<u8>,
+    deltas: VecDeque<Vec<u8>>,
+    deltas_size: usize,
+}
+
+impl Default for RewindManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RewindManager {
+    pub fn new() -> Self {
+        Self {
+            // cada snapshot custa um retro_serialize, então o frontend precisa ativar o rewind
+            enabled: AtomicBool::new(false),
+            interval: AtomicUsize::new(REWIND_SNAPSHOT_INTERVAL),
+            max_buffer_size: AtomicUsize::new(REWIND_BUFFER_SIZE_MB * 1024 * 1024),
+            frame_count: AtomicUsize::new(0),
+            history: Mutex::new(RewindHistory::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), ErrorHandle> {
+        self.enabled.store(enabled, Ordering::SeqCst);
+
+        if !enabled {
+            self.clear()?;
+        }
+
+        Ok(())
+    }
+
+    /// Number of frames between two snapshots.
+    pub fn set_interval(&self, frames: usize) {
+        self.interval.store(frames.max(1), Ordering::SeqCst);
+    }
+
+    pub fn set_max_buffer_size(&self, bytes: usize) {
+        self.max_buffer_size.store(bytes, Ordering::SeqCst);
+    }
+
+    /// Count a new frame and tell whether a snapshot should be taken after it.
+    pub fn should_capture(&self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let frame = self.frame_count.fetch_add(1, Ordering::SeqCst) + 1;
+        frame.is_multiple_of(self.interval.load(Ordering::SeqCst))
+    }
+
+    pub fn push(&self, state: Vec<u8>) -> Result<(), ErrorHandle> {
+        let mut history = self.history.lock()?;
+
+        // o tamanho do state mudou, os deltas antigos não podem mais ser aplicados
+        if history.current.len() != state.len() {
+            history.deltas.clear();
+            history.deltas_size = 0;
+            history.current = state;
+            return Ok(());
+        }
+
+        let delta = encode_delta(&history.current, &state);
+        history.deltas_size += delta.len();
+        history.deltas.push_back(delta);
+        history.current = state;
+
+        let max_buffer_size = self.max_buffer_size.load(Ordering::SeqCst);
+
+        while history.deltas_size + history.current.len() > max_buffer_size {
+            match history.deltas.pop_front() {
+                Some(oldest) => history.deltas_size -= oldest.len(),
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Go one snapshot back, returning the state that must be sent to the core.
+    /// Returns `None` when the history has nothing older to offer.
+    pub fn step_back(&self) -> Result<Option<Vec<u8>>, ErrorHandle> {
+        let mut history = self.history.lock()?;
+
+        let delta = match history.deltas.pop_back() {
+            Some(delta) => delta,
+            None => return Ok(None),
+        };
+
+        history.deltas_size -= delta.len();
+        apply_delta(&mut history.current, &delta)?;
+
+        Ok(Some(history.current.clone()))
+    }
+
+    pub fn available_snapshots(&self) -> Result<usize, ErrorHandle> {
+        Ok(self.history.lock()?.deltas.len())
+    }
+
+    pub fn clear(&self) -> Result<(), ErrorHandle> {
+        *self.history.lock()? = RewindHistory::default();
+        self.frame_count.store(0, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+// O delta é o XOR entre os dois states, compactado como uma sequência de
+// [bytes iguais a zero: u32][bytes diferentes: u32][bytes diferentes...].
+// Como o XOR é simétrico, o mesmo delta aplicado ao state mais novo devolve o anterior.
+fn encode_delta(previous: &[u8], next: &[u8]) -> Vec<u8> {
+    let mut delta = Vec::new();
+    let mut index = 0;
+
+    while index < next.len() {
+        let zeros_start = index;
+        while index < next.len() && previous[index] == next[index] {
+            index += 1;
+        }
+
+        let literal_start = index;
+        while index < next.len() && previous[index] != next[index] {
+            index += 1;
+        }
+
+        delta.extend_from_slice(&((literal_start - zeros_start) as u32).to_le_bytes());
+        delta.extend_from_slice(&((index - literal_start) as u32).to_le_bytes());
+        delta.extend(
+            previous[literal_start..index]
+                .iter()
+                .zip(&next[literal_start..index])
+                .map(|(a, b)| a ^ b),
+        );
+    }
+
+    delta
+}
+
+fn apply_delta(state: &mut [u8], delta: &[u8]) -> Result<(), ErrorHandle> {
+    let corrupted = || ErrorHandle::new("Rewind delta is corrupted");
+    let read_u32 = |at: usize| -> Result<usize, ErrorHandle> {
+        let bytes = delta.get(at..at + 4).ok_or_else(corrupted)?;
+        Ok(u32::from_le_bytes(bytes.try_into().map_err(|_| corrupted())?) as usize)
+    };
+
+    let mut cursor = 0;
+    let mut index = 0;
+
+    while cursor < delta.len() {
+        let zeros = read_u32(cursor)?;
+        let literals = read_u32(cursor + 4)?;
+        cursor += 8;
+        index += zeros;
+
+        let changes = delta.get(cursor..cursor + literals).ok_or_else(corrupted)?;
+        let target = state
+            .get_mut(index..index + literals)
+            .ok_or_else(corrupted)?;
+
+        for (byte, change) in target.iter_mut().zip(changes) {
+            *byte ^= change;
+        }
+
+        cursor += literals;
+        index += literals;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delta_round_trip() {
+        let previous = vec![0u8, 1, 2, 3, 4, 5, 6, 7];
+        let next = vec![0u8, 1, 9, 9, 4, 5, 6, 8];
+
+        let delta = encode_delta(&previous, &next);
+
+        let mut state = next.clone();
+        apply_delta(&mut state, &delta).unwrap();
+        assert_eq!(state, previous);
+
+        // states iguais geram um delta sem nenhum byte literal
+        assert_eq!(encode_delta(&next, &next).len(), 8);
+    }
+
+    #[test]
+    fn test_step_back_through_history() {
+        let rewind = RewindManager::new();
+
+        for value in 0..4u8 {
+            rewind.push(vec![value; 16]).unwrap();
+        }
+
+        assert_eq!(rewind.available_snapshots().unwrap(), 3);
+        assert_eq!(rewind.step_back().unwrap(), Some(vec![2; 16]));
+        assert_eq!(rewind.step_back().unwrap(), Some(vec![1; 16]));
+        assert_eq!(rewind.step_back().unwrap(), Some(vec![0; 16]));
+        assert_eq!(rewind.step_back().unwrap(), None);
+    }
+
+    #[test]
+    fn test_history_stays_inside_buffer_size() {
+        let rewind = RewindManager::new();
+        rewind.set_max_buffer_size(64);
+
+        for value in 0..32u8 {
+            rewind.push(vec![value; 16]).unwrap();
+        }
+
+        let history = rewind.history.lock().unwrap();
+        assert!(history.deltas_size + history.current.len() <= 64);
+        assert!(!history.deltas.is_empty());
+    }
+
+    #[test]
+    fn test_should_capture_respects_interval() {
+        let rewind = RewindManager::new();
+        assert!(!rewind.should_capture());
+
+        rewind.set_enabled(true).unwrap();
+        rewind.set_interval(3);
+
+        let captures: Vec<bool> = (0..6).map(|_| rewind.should_capture()).collect();
+        assert_eq!(captures, vec![false, false, true, false, false, true]);
+
+        rewind.set_enabled(false).unwrap();
+        assert!(!rewind.should_capture());
+    }
+}
@@ -0,0 +1,79 @@
+use generics::constants::MAX_RUN_AHEAD_FRAMES;
+use generics::logger::{self, LogTarget};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Run-ahead settings of the loaded game.
+///
+/// `frames` is the amount of hidden frames emulated after every visible one, zero turns the
+/// mode off. When the core can't serialize its state the mode is marked as unsupported and
+/// the frame loop goes back to running one frame at a time until the next game is loaded.
+pub struct RunAheadManager {
+    frames: AtomicUsize,
+    unsupported: AtomicBool,
+}
+
+impl Default for RunAheadManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunAheadManager {
+    pub fn new() -> Self {
+        Self {
+            frames: AtomicUsize::new(0),
+            unsupported: AtomicBool::new(false),
+        }
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames.load(Ordering::SeqCst)
+    }
+
+    pub fn set_frames(&self, frames: usize) {
+        self.frames
+            .store(frames.min(MAX_RUN_AHEAD_FRAMES), Ordering::SeqCst);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.frames() > 0 && !self.unsupported.load(Ordering::SeqCst)
+    }
+
+    pub fn is_unsupported(&self) -> bool {
+        self.unsupported.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_unsupported(&self) {
+        if !self.unsupported.swap(true, Ordering::SeqCst) {
+            logger::warn(
+                LogTarget::Frontend,
+                "Run-ahead desativado: o núcleo não suporta salvar o estado a cada frame",
+            );
+        }
+    }
+
+    pub fn reset(&self) {
+        self.unsupported.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_ahead_activation() {
+        let run_ahead = RunAheadManager::new();
+        assert!(!run_ahead.is_active());
+
+        run_ahead.set_frames(MAX_RUN_AHEAD_FRAMES + 10);
+        assert_eq!(run_ahead.frames(), MAX_RUN_AHEAD_FRAMES);
+        assert!(run_ahead.is_active());
+
+        run_ahead.mark_unsupported();
+        assert!(!run_ahead.is_active());
+
+        run_ahead.reset();
+        assert!(run_ahead.is_active());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::tools::game_tools::RomTools;
+use generics::constants::SAVE_RAM_AUTOSAVE_INTERVAL;
+use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Keeps the battery-backed SRAM of the loaded rom in sync with its `.srm` file.
+pub struct SaveRamManager {
+    file_path: RwLock<Option<PathBuf>>,
+    last_flushed: Mutex<Vec<u8>>,
+    last_autosave: RwLock<Instant>,
+    autosave_interval: RwLock<Option<Duration>>,
+}
+
+impl Default for SaveRamManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaveRamManager {
+    pub fn new() -> Self {
+        Self {
+            file_path: RwLock::new(None),
+            last_flushed: Mutex::new(Vec::new()),
+            last_autosave: RwLock::new(Instant::now()),
+            autosave_interval: RwLock::new(Some(Duration::from_secs(SAVE_RAM_AUTOSAVE_INTERVAL))),
+        }
+    }
+
+    pub fn file_path(&self) -> Result<Option<PathBuf>, ErrorHandle> {
+        Ok(self.file_path.read()?.clone())
+    }
+
+    pub fn set_file_path(&self, path: PathBuf) -> Result<(), ErrorHandle> {
+        self.file_path.write()?.replace(path);
+        self.last_flushed.lock()?.clear();
+        *self.last_autosave.write()? = Instant::now();
+
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), ErrorHandle> {
+        self.file_path.write()?.take();
+        self.last_flushed.lock()?.clear();
+
+        Ok(())
+    }
+
+    /// Pass `None` to turn the periodic autosave off.
+    pub fn set_autosave_interval(&self, interval: Option<Duration>) -> Result<(), ErrorHandle> {
+        *self.autosave_interval.write()? = interval;
+        Ok(())
+    }
+
+    pub fn autosave_is_due(&self) -> Result<bool, ErrorHandle> {
+        let interval = match *self.autosave_interval.read()? {
+            Some(interval) => interval,
+            None => return Ok(false),
+        };
+
+        let mut last_autosave = self.last_autosave.write()?;
+
+        if last_autosave.elapsed() < interval {
+            return Ok(false);
+        }
+
+        *last_autosave = Instant::now();
+        Ok(true)
+    }
+
+    /// Copy the `.srm` file into the core memory. Returns false when there is nothing to restore.
+    pub fn restore(&self, memory: &mut [u8]) -> Result<bool, ErrorHandle> {
+        let path = match self.file_path()? {
+            Some(path) if path.exists() => path,
+            _ => return Ok(false),
+        };
+
+        let data = fs::read(&path)
+            .map_err(|e| ErrorHandle::new(&format!("Failed to read SRAM file: {}", e)))?;
+
+        if data.len() != memory.len() {
+            logger::warn(
+                LogTarget::Frontend,
+                format!(
+                    "SRAM file has {} bytes but the core expects {}",
+                    data.len(),
+                    memory.len()
+                ),
+            );
+        }
+
+        let size = data.len().min(memory.len());
+        memory[..size].copy_from_slice(&data[..size]);
+
+        *self.last_flushed.lock()? = memory.to_vec();
+
+        Ok(true)
+    }
+
+    /// Write the core memory to the `.srm` file if it changed since the last flush.
+    pub fn flush(&self, memory: &[u8]) -> Result<Option<PathBuf>, ErrorHandle> {
+        let path = match self.file_path()? {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let mut last_flushed = self.last_flushed.lock()?;
+
+        if last_flushed.as_slice() == memory {
+            return Ok(None);
+        }
+
+        RomTools::write_file_atomically(&path, memory)?;
+
+        last_flushed.clear();
+        last_flushed.extend_from_slice(memory);
+
+        Ok(Some(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_flush_only_writes_when_memory_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("game.srm");
+
+        let manager = SaveRamManager::new();
+        assert!(manager.flush(&[1, 2, 3]).unwrap().is_none());
+
+        manager.set_file_path(path.clone()).unwrap();
+
+        assert_eq!(manager.flush(&[1, 2, 3]).unwrap(), Some(path.clone()));
+        assert!(manager.flush(&[1, 2, 3]).unwrap().is_none());
+        assert!(manager.flush(&[1, 2, 4]).unwrap().is_some());
+
+        assert_eq!(fs::read(&path).unwrap(), vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("game.srm");
+
+        let manager = SaveRamManager::new();
+        manager.set_file_path(path.clone()).unwrap();
+
+        let mut memory = [0u8; 4];
+        assert!(!manager.restore(&mut memory).unwrap());
+
+        fs::write(&path, [9, 8, 7, 6, 5]).unwrap();
+        assert!(manager.restore(&mut memory).unwrap());
+        assert_eq!(memory, [9, 8, 7, 6]);
+
+        // nothing changed since the restore, so there is nothing to write
+        assert!(manager.flush(&memory).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_autosave_interval() {
+        let manager = SaveRamManager::new();
+
+        manager.set_autosave_interval(None).unwrap();
+        assert!(!manager.autosave_is_due().unwrap());
+
+        manager
+            .set_autosave_interval(Some(Duration::from_secs(0)))
+            .unwrap();
+        assert!(manager.autosave_is_due().unwrap());
+    }
+}
@@ -0,0 +1,383 @@
+use crate::tools::save_state_tools::{SaveStateHeader, SaveStateTools};
+use crate::tools::validation::InputValidator;
+use generics::constants::{SAVE_EXTENSION_FILE, SAVE_IMAGE_EXTENSION_FILE};
+use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::UNIX_EPOCH;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SaveSlot {
+    #[doc = "escrito ao fechar o jogo e opcionalmente carregado ao abrir"]
+    Auto,
+    Index(usize),
+}
+
+impl SaveSlot {
+    pub fn file_stem(&self) -> String {
+        match self {
+            SaveSlot::Auto => "auto".to_string(),
+            SaveSlot::Index(slot) => format!("{:02}", slot),
+        }
+    }
+
+    fn from_file_stem(stem: &str) -> Option<Self> {
+        if stem == "auto" {
+            return Some(SaveSlot::Auto);
+        }
+
+        if stem.is_empty() || !stem.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        let slot = stem.parse().ok()?;
+        InputValidator::validate_save_slot(slot).ok()?;
+
+        Some(SaveSlot::Index(slot))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveStateSlotInfo {
+    pub slot: SaveSlot,
+    pub path: PathBuf,
+    #[doc = "imagem salva pelo frontend ao lado do state"]
+    pub preview_path: Option<PathBuf>,
+    #[doc = "None para states criados antes do cabeçalho existir"]
+    pub header: Option<SaveStateHeader>,
+    pub size: u64,
+    #[doc = "segundos desde UNIX_EPOCH da última modificação do arquivo"]
+    pub modified: u64,
+}
+
+// o conteúdo que um save sobrescreveu, None quando o arquivo ainda não existia
+pub(crate) struct SaveBackup {
+    slot: SaveSlot,
+    state: Option<Vec<u8>>,
+    preview: Option<Vec<u8>>,
+}
+
+/// Save state slots of the loaded game, stored as `<slot>.save` files with an optional
+/// `<slot>.png` preview inside `<save>/<library>/<rom>/`.
+///
+/// It also keeps what the last save overwrote and the state replaced by the last load,
+/// so each of them can be undone once.
+#[derive(Default)]
+pub struct SaveStateManager {
+    dir: RwLock<Option<PathBuf>>,
+    last_save: Mutex<Option<SaveBackup>>,
+    last_load: Mutex<Option<Vec<u8>>>,
+}
+
+impl SaveStateManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn set_dir(&self, dir: PathBuf) -> Result<(), ErrorHandle> {
+        self.dir.write()?.replace(dir);
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), ErrorHandle> {
+        self.dir.write()?.take();
+        self.last_save.lock()?.take();
+        self.last_load.lock()?.take();
+
+        Ok(())
+    }
+
+    pub fn path(&self, slot: SaveSlot) -> Result<PathBuf, ErrorHandle> {
+        if let SaveSlot::Index(slot) = slot {
+            InputValidator::validate_save_slot(slot)?;
+        }
+
+        let dir = self.dir.read()?;
+        let dir = dir
+            .as_ref()
+            .ok_or_else(|| ErrorHandle::new("Uma rom precisa ser carregada primeiro"))?;
+
+        Ok(dir.join(format!("{}.{}", slot.file_stem(), SAVE_EXTENSION_FILE)))
+    }
+
+    pub fn preview_path(&self, slot: SaveSlot) -> Result<PathBuf, ErrorHandle> {
+        Ok(self.path(slot)?.with_extension(SAVE_IMAGE_EXTENSION_FILE))
+    }
+
+    pub fn exists(&self, slot: SaveSlot) -> Result<bool, ErrorHandle> {
+        Ok(self.path(slot)?.is_file())
+    }
+
+    #[doc = "todos os slots com um state salvo, o auto primeiro e depois os números em ordem"]
+    pub fn list(&self) -> Result<Vec<SaveStateSlotInfo>, ErrorHandle> {
+        let dir = match self.dir.read()?.clone() {
+            Some(dir) => dir,
+            None => return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro")),
+        };
+
+        // o diretório só é criado no primeiro save
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let mut slots: Vec<SaveStateSlotInfo> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == SAVE_EXTENSION_FILE)
+            })
+            .filter_map(|path| {
+                let slot = SaveSlot::from_file_stem(path.file_stem()?.to_str()?)?;
+                Self::slot_info(slot, path)
+            })
+            .collect();
+
+        slots.sort_by_key(|info| info.slot);
+
+        Ok(slots)
+    }
+
+    pub fn delete(&self, slot: SaveSlot) -> Result<(), ErrorHandle> {
+        let path = self.path(slot)?;
+
+        if !path.is_file() {
+            return Err(ErrorHandle::new(&format!(
+                "O slot {} não possui um save state",
+                slot.file_stem()
+            )));
+        }
+
+        fs::remove_file(&path)?;
+        Self::remove_if_exists(&path.with_extension(SAVE_IMAGE_EXTENSION_FILE))
+    }
+
+    #[doc = "copia o state e a imagem, substituindo o que existir no slot de destino"]
+    pub fn copy(&self, from: SaveSlot, to: SaveSlot) -> Result<(), ErrorHandle> {
+        self.transfer(from, to, false)
+    }
+
+    #[doc = "move o state e a imagem para outro slot, substituindo o que existir no destino"]
+    pub fn rename(&self, from: SaveSlot, to: SaveSlot) -> Result<(), ErrorHandle> {
+        self.transfer(from, to, true)
+    }
+
+    #[doc = "lê o conteúdo do slot antes de um save sobrescrever ele"]
+    pub(crate) fn read_save_backup(&self, slot: SaveSlot) -> Result<SaveBackup, ErrorHandle> {
+        let path = self.path(slot)?;
+
+        Ok(SaveBackup {
+            slot,
+            state: fs::read(&path).ok(),
+            preview: fs::read(path.with_extension(SAVE_IMAGE_EXTENSION_FILE)).ok(),
+        })
+    }
+
+    #[doc = "só deve ser chamado depois que o save terminou, senão o undo apagaria um slot que não mudou"]
+    pub(crate) fn set_save_backup(&self, backup: SaveBackup) -> Result<(), ErrorHandle> {
+        self.last_save.lock()?.replace(backup);
+        Ok(())
+    }
+
+    #[doc = "devolve o slot ao que era antes do último save, retorna o slot restaurado"]
+    pub fn undo_save(&self) -> Result<Option<SaveSlot>, ErrorHandle> {
+        let backup = match self.last_save.lock()?.take() {
+            Some(backup) => backup,
+            None => return Ok(None),
+        };
+
+        let path = self.path(backup.slot)?;
+        let preview_path = path.with_extension(SAVE_IMAGE_EXTENSION_FILE);
+
+        for (path, data) in [(&path, backup.state), (&preview_path, backup.preview)] {
+            match data {
+                Some(data) => fs::write(path, data)?,
+                None => Self::remove_if_exists(path)?,
+            }
+        }
+
+        Ok(Some(backup.slot))
+    }
+
+    pub(crate) fn set_load_backup(&self, state: Vec<u8>) -> Result<(), ErrorHandle> {
+        self.last_load.lock()?.replace(state);
+        Ok(())
+    }
+
+    pub(crate) fn take_load_backup(&self) -> Result<Option<Vec<u8>>, ErrorHandle> {
+        Ok(self.last_load.lock()?.take())
+    }
+
+    fn transfer(
+        &self,
+        from: SaveSlot,
+        to: SaveSlot,
+        remove_source: bool,
+    ) -> Result<(), ErrorHandle> {
+        if from == to {
+            return Ok(());
+        }
+
+        let source = self.path(from)?;
+        let target = self.path(to)?;
+
+        if !source.is_file() {
+            return Err(ErrorHandle::new(&format!(
+                "O slot {} não possui um save state",
+                from.file_stem()
+            )));
+        }
+
+        let source_preview = source.with_extension(SAVE_IMAGE_EXTENSION_FILE);
+        let target_preview = target.with_extension(SAVE_IMAGE_EXTENSION_FILE);
+
+        if remove_source {
+            fs::rename(&source, &target)?;
+        } else {
+            fs::copy(&source, &target)?;
+        }
+
+        // sem isso o slot de destino ficaria com a imagem do state antigo
+        if !source_preview.is_file() {
+            return Self::remove_if_exists(&target_preview);
+        }
+
+        if remove_source {
+            fs::rename(&source_preview, &target_preview)?;
+        } else {
+            fs::copy(&source_preview, &target_preview)?;
+        }
+
+        Ok(())
+    }
+
+    fn slot_info(slot: SaveSlot, path: PathBuf) -> Option<SaveStateSlotInfo> {
+        let metadata = fs::metadata(&path).ok()?;
+
+        let header = SaveStateTools::read_file_header(&path).unwrap_or_else(|e| {
+            logger::warn(
+                LogTarget::Frontend,
+                format!("Cabeçalho inválido em {}: {e:?}", path.display()),
+            );
+            None
+        });
+
+        let preview_path = path.with_extension(SAVE_IMAGE_EXTENSION_FILE);
+
+        Some(SaveStateSlotInfo {
+            slot,
+            preview_path: preview_path.is_file().then_some(preview_path),
+            header,
+            size: metadata.len(),
+            modified: metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|time| time.as_secs())
+                .unwrap_or_default(),
+            path,
+        })
+    }
+
+    fn remove_if_exists(path: &Path) -> Result<(), ErrorHandle> {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn manager(dir: &TempDir) -> SaveStateManager {
+        let manager = SaveStateManager::new();
+        manager.set_dir(dir.path().to_path_buf()).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_list_copy_rename_and_delete() {
+        let dir = TempDir::new().unwrap();
+        let manager = manager(&dir);
+
+        fs::write(manager.path(SaveSlot::Index(3)).unwrap(), b"three").unwrap();
+        fs::write(manager.preview_path(SaveSlot::Index(3)).unwrap(), b"png").unwrap();
+        fs::write(manager.path(SaveSlot::Auto).unwrap(), b"auto").unwrap();
+        fs::write(dir.path().join("notes.save"), b"ignored").unwrap();
+
+        let slots: Vec<SaveSlot> = manager
+            .list()
+            .unwrap()
+            .iter()
+            .map(|info| info.slot)
+            .collect();
+        assert_eq!(slots, vec![SaveSlot::Auto, SaveSlot::Index(3)]);
+
+        manager
+            .copy(SaveSlot::Index(3), SaveSlot::Index(10))
+            .unwrap();
+        manager.rename(SaveSlot::Auto, SaveSlot::Index(3)).unwrap();
+
+        let slots = manager.list().unwrap();
+        assert_eq!(slots.len(), 2);
+        assert_eq!(fs::read(&slots[0].path).unwrap(), b"auto");
+        // a imagem do state antigo não pode ficar no slot que recebeu o auto
+        assert_eq!(slots[0].preview_path, None);
+        assert!(slots[1].preview_path.is_some());
+        assert_eq!(slots[1].header, None);
+
+        manager.delete(SaveSlot::Index(10)).unwrap();
+        assert!(!manager.preview_path(SaveSlot::Index(10)).unwrap().exists());
+        assert!(manager.delete(SaveSlot::Index(10)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_slots() {
+        let dir = TempDir::new().unwrap();
+        let manager = manager(&dir);
+
+        fs::write(manager.path(SaveSlot::Index(3)).unwrap(), b"three").unwrap();
+
+        assert!(
+            manager
+                .copy(SaveSlot::Index(3), SaveSlot::Index(5000))
+                .is_err()
+        );
+        assert!(
+            manager
+                .rename(SaveSlot::Index(100), SaveSlot::Index(3))
+                .is_err()
+        );
+        assert!(manager.delete(SaveSlot::Index(100)).is_err());
+        assert!(!dir.path().join("5000.save").exists());
+    }
+
+    #[test]
+    fn test_undo_save_restores_previous_files() {
+        let dir = TempDir::new().unwrap();
+        let manager = manager(&dir);
+        let path = manager.path(SaveSlot::Index(1)).unwrap();
+
+        assert_eq!(manager.undo_save().unwrap(), None);
+
+        let backup = manager.read_save_backup(SaveSlot::Index(1)).unwrap();
+        fs::write(&path, b"first").unwrap();
+        manager.set_save_backup(backup).unwrap();
+        assert_eq!(manager.undo_save().unwrap(), Some(SaveSlot::Index(1)));
+        assert!(!path.exists());
+
+        fs::write(&path, b"first").unwrap();
+        let backup = manager.read_save_backup(SaveSlot::Index(1)).unwrap();
+        fs::write(&path, b"second").unwrap();
+        manager.set_save_backup(backup).unwrap();
+        manager.undo_save().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+    }
+}
@@ -0,0 +1,230 @@
+use crate::tools::save_state_tools::SaveStateHeader;
+use generics::error_handle::ErrorHandle;
+use libretro_sys::binding_libretro::{
+    RETRO_SERIALIZATION_QUIRK_CORE_VARIABLE_SIZE, RETRO_SERIALIZATION_QUIRK_ENDIAN_DEPENDENT,
+    RETRO_SERIALIZATION_QUIRK_FRONT_VARIABLE_SIZE, RETRO_SERIALIZATION_QUIRK_INCOMPLETE,
+    RETRO_SERIALIZATION_QUIRK_MUST_INITIALIZE, RETRO_SERIALIZATION_QUIRK_PLATFORM_DEPENDENT,
+    RETRO_SERIALIZATION_QUIRK_SINGLE_SESSION,
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Serialization quirks reported by the core with `SET_SERIALIZATION_QUIRKS`.
+///
+/// Save states, rewind and run-ahead ask it before calling `retro_serialize` or
+/// `retro_unserialize`. It also tracks whether the first `retro_run` of the game happened,
+/// because `MUST_INITIALIZE` cores can only serialize after it.
+#[derive(Default)]
+pub struct SerializationManager {
+    quirks: AtomicU64,
+    frame_ran: AtomicBool,
+    // segundos desde UNIX_EPOCH em que o jogo foi carregado, usado pelo SINGLE_SESSION
+    session_started: AtomicU64,
+    pending_auto_load: AtomicBool,
+}
+
+impl SerializationManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "guarda os quirks do núcleo e retorna os mesmos flags com as confirmações do frontend"]
+    pub fn set_quirks(&self, quirks: u64) -> u64 {
+        let mut quirks = quirks;
+
+        // o tamanho é consultado de novo antes de cada save, então o frontend aceita variar
+        if quirks & RETRO_SERIALIZATION_QUIRK_CORE_VARIABLE_SIZE as u64 != 0 {
+            quirks |= RETRO_SERIALIZATION_QUIRK_FRONT_VARIABLE_SIZE as u64;
+        }
+
+        self.quirks.store(quirks, Ordering::SeqCst);
+        quirks
+    }
+
+    pub fn quirks(&self) -> u64 {
+        self.quirks.load(Ordering::SeqCst)
+    }
+
+    fn has(&self, quirk: u32) -> bool {
+        self.quirks() & quirk as u64 != 0
+    }
+
+    pub fn is_variable_size(&self) -> bool {
+        self.has(RETRO_SERIALIZATION_QUIRK_CORE_VARIABLE_SIZE)
+    }
+
+    #[doc = "rewind e run-ahead precisam de states completos a cada frame"]
+    pub fn can_serialize_every_frame(&self) -> bool {
+        !self.has(RETRO_SERIALIZATION_QUIRK_INCOMPLETE) && self.can_serialize()
+    }
+
+    pub fn can_serialize(&self) -> bool {
+        !self.has(RETRO_SERIALIZATION_QUIRK_MUST_INITIALIZE)
+            || self.frame_ran.load(Ordering::SeqCst)
+    }
+
+    pub fn ensure_can_serialize(&self) -> Result<(), ErrorHandle> {
+        if self.can_serialize() {
+            Ok(())
+        } else {
+            Err(ErrorHandle::new(
+                "O núcleo só consegue usar save states depois do primeiro frame do jogo",
+            ))
+        }
+    }
+
+    pub fn start_session(&self, started_at: u64) {
+        self.session_started.store(started_at, Ordering::SeqCst);
+        self.frame_ran.store(false, Ordering::SeqCst);
+        self.pending_auto_load.store(false, Ordering::SeqCst);
+    }
+
+    pub fn mark_frame_ran(&self) {
+        self.frame_ran.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn set_pending_auto_load(&self) {
+        self.pending_auto_load.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn take_pending_auto_load(&self) -> bool {
+        self.pending_auto_load.swap(false, Ordering::SeqCst)
+    }
+
+    #[doc = "recusa states de outra sessão ou plataforma quando o núcleo avisa que eles não funcionam"]
+    pub fn check_state(
+        &self,
+        found: Option<&SaveStateHeader>,
+        current: &SaveStateHeader,
+    ) -> Result<(), ErrorHandle> {
+        if self.has(RETRO_SERIALIZATION_QUIRK_SINGLE_SESSION) {
+            let session_started = self.session_started.load(Ordering::SeqCst);
+
+            if found.is_none_or(|found| found.timestamp < session_started) {
+                return Err(ErrorHandle::new(
+                    "O núcleo só carrega save states criados desde que o jogo foi aberto",
+                ));
+            }
+        }
+
+        // states antigos não guardam a plataforma, então não dá para comparar
+        let found = match found {
+            Some(found) if !found.platform.is_empty() => found,
+            _ => return Ok(()),
+        };
+
+        if self.has(RETRO_SERIALIZATION_QUIRK_ENDIAN_DEPENDENT)
+            && found.big_endian != current.big_endian
+        {
+            return Err(ErrorHandle::new(&format!(
+                "O save state foi criado em uma máquina {} e o núcleo não converte a ordem dos bytes",
+                if found.big_endian {
+                    "big-endian"
+                } else {
+                    "little-endian"
+                }
+            )));
+        }
+
+        if self.has(RETRO_SERIALIZATION_QUIRK_PLATFORM_DEPENDENT)
+            && found.platform != current.platform
+        {
+            return Err(ErrorHandle::new(&format!(
+                "O save state foi criado em {} e o núcleo só carrega states da mesma plataforma ({})",
+                found.platform, current.platform
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn reset(&self) {
+        self.start_session(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::save_state_tools::{SAVE_STATE_FORMAT_VERSION, current_platform};
+
+    fn header(timestamp: u64) -> SaveStateHeader {
+        SaveStateHeader {
+            format_version: SAVE_STATE_FORMAT_VERSION,
+            library_name: "Mesen".to_string(),
+            library_version: "0.9.9".to_string(),
+            rom_crc32: 0,
+            timestamp,
+            playtime: 0,
+            compressed: false,
+            state_size: 16,
+            platform: current_platform(),
+            big_endian: cfg!(target_endian = "big"),
+        }
+    }
+
+    #[test]
+    fn test_must_initialize_waits_for_first_frame() {
+        let manager = SerializationManager::new();
+        manager.set_quirks(RETRO_SERIALIZATION_QUIRK_MUST_INITIALIZE as u64);
+        manager.start_session(100);
+
+        assert!(manager.ensure_can_serialize().is_err());
+        assert!(!manager.can_serialize_every_frame());
+
+        manager.mark_frame_ran();
+        assert!(manager.ensure_can_serialize().is_ok());
+
+        // um novo jogo precisa rodar o primeiro frame de novo
+        manager.start_session(200);
+        assert!(!manager.can_serialize());
+    }
+
+    #[test]
+    fn test_variable_size_is_acknowledged() {
+        let manager = SerializationManager::new();
+
+        let quirks = manager.set_quirks(RETRO_SERIALIZATION_QUIRK_CORE_VARIABLE_SIZE as u64);
+        assert_ne!(
+            quirks & RETRO_SERIALIZATION_QUIRK_FRONT_VARIABLE_SIZE as u64,
+            0
+        );
+        assert!(manager.is_variable_size());
+
+        assert_eq!(manager.set_quirks(0), 0);
+    }
+
+    #[test]
+    fn test_states_from_other_sessions_and_platforms_are_refused() {
+        let manager = SerializationManager::new();
+        let current = header(200);
+        manager.start_session(100);
+
+        manager.set_quirks(RETRO_SERIALIZATION_QUIRK_SINGLE_SESSION as u64);
+        assert!(manager.check_state(Some(&header(150)), &current).is_ok());
+        assert!(manager.check_state(Some(&header(50)), &current).is_err());
+        assert!(manager.check_state(None, &current).is_err());
+
+        manager.set_quirks(
+            (RETRO_SERIALIZATION_QUIRK_ENDIAN_DEPENDENT
+                | RETRO_SERIALIZATION_QUIRK_PLATFORM_DEPENDENT) as u64,
+        );
+        assert!(manager.check_state(None, &current).is_ok());
+
+        let other_endian = SaveStateHeader {
+            big_endian: !current.big_endian,
+            ..current.clone()
+        };
+        assert!(manager.check_state(Some(&other_endian), &current).is_err());
+
+        let other_platform = SaveStateHeader {
+            platform: "sparc-solaris".to_string(),
+            ..current.clone()
+        };
+        assert!(
+            manager
+                .check_state(Some(&other_platform), &current)
+                .is_err()
+        );
+        assert!(manager.check_state(Some(&current), &current).is_ok());
+    }
+}
@@ -0,0 +1,166 @@
+use generics::constants::{DEFAULT_FAST_FORWARD_RATIO, DEFAULT_SLOW_MOTION_RATIO};
+use libretro_sys::binding_libretro::{
+    RETRO_THROTTLE_FAST_FORWARD, RETRO_THROTTLE_FRAME_STEPPING, RETRO_THROTTLE_NONE,
+    RETRO_THROTTLE_SLOW_MOTION, retro_throttle_state,
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Emulation speed chosen by the frontend.
+///
+/// Fast-forward and slow-motion are independent switches, so a hold hotkey and a toggle can
+/// be used together; when both are on fast-forward wins. A fast-forward ratio of zero means
+/// running unthrottled, as fast as the host allows. Frame stepping overrides both, frames
+/// are run on demand and don't follow the wall clock.
+pub struct SpeedManager {
+    frame_stepping: AtomicBool,
+    fast_forward: AtomicBool,
+    slow_motion: AtomicBool,
+    // f64 guardado como bits para não precisar de um lock a cada frame
+    fast_forward_ratio: AtomicU64,
+    slow_motion_ratio: AtomicU64,
+}
+
+impl Default for SpeedManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpeedManager {
+    pub fn new() -> Self {
+        Self {
+            frame_stepping: AtomicBool::new(false),
+            fast_forward: AtomicBool::new(false),
+            slow_motion: AtomicBool::new(false),
+            fast_forward_ratio: AtomicU64::new(DEFAULT_FAST_FORWARD_RATIO.to_bits()),
+            slow_motion_ratio: AtomicU64::new(DEFAULT_SLOW_MOTION_RATIO.to_bits()),
+        }
+    }
+
+    pub fn is_frame_stepping(&self) -> bool {
+        self.frame_stepping.load(Ordering::SeqCst)
+    }
+
+    pub fn set_frame_stepping(&self, active: bool) {
+        self.frame_stepping.store(active, Ordering::SeqCst);
+    }
+
+    pub fn is_fast_forwarding(&self) -> bool {
+        self.fast_forward.load(Ordering::SeqCst)
+    }
+
+    pub fn is_slow_motion(&self) -> bool {
+        !self.is_fast_forwarding() && self.slow_motion.load(Ordering::SeqCst)
+    }
+
+    pub fn set_fast_forward(&self, active: bool) {
+        self.fast_forward.store(active, Ordering::SeqCst);
+    }
+
+    pub fn set_slow_motion(&self, active: bool) {
+        self.slow_motion.store(active, Ordering::SeqCst);
+    }
+
+    pub fn fast_forward_ratio(&self) -> f64 {
+        f64::from_bits(self.fast_forward_ratio.load(Ordering::SeqCst))
+    }
+
+    pub fn slow_motion_ratio(&self) -> f64 {
+        f64::from_bits(self.slow_motion_ratio.load(Ordering::SeqCst))
+    }
+
+    /// Values below 1x make no sense for fast-forward and are treated as unthrottled.
+    pub fn set_fast_forward_ratio(&self, ratio: f64) {
+        let ratio = if ratio.is_finite() && ratio >= 1.0 {
+            ratio
+        } else {
+            0.0
+        };
+
+        self.fast_forward_ratio
+            .store(ratio.to_bits(), Ordering::SeqCst);
+    }
+
+    pub fn set_slow_motion_ratio(&self, ratio: f64) {
+        let ratio = if ratio.is_finite() && ratio > 0.0 {
+            ratio.min(1.0)
+        } else {
+            DEFAULT_SLOW_MOTION_RATIO
+        };
+
+        self.slow_motion_ratio
+            .store(ratio.to_bits(), Ordering::SeqCst);
+    }
+
+    /// Current speed multiplier, `None` while running unthrottled or frame stepping.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.is_frame_stepping() {
+            return None;
+        }
+
+        if self.is_fast_forwarding() {
+            let ratio = self.fast_forward_ratio();
+            return (ratio > 0.0).then_some(ratio);
+        }
+
+        if self.is_slow_motion() {
+            return Some(self.slow_motion_ratio());
+        }
+
+        Some(1.0)
+    }
+
+    pub fn throttle_state(&self, fps: f64) -> retro_throttle_state {
+        let (mode, rate) = match self.ratio() {
+            None if self.is_frame_stepping() => (RETRO_THROTTLE_FRAME_STEPPING, 0.0),
+            // o libretro usa rate 0 para um fast forward sem limite
+            None => (RETRO_THROTTLE_FAST_FORWARD, 0.0),
+            Some(ratio) if self.is_fast_forwarding() => (RETRO_THROTTLE_FAST_FORWARD, fps * ratio),
+            Some(ratio) if self.is_slow_motion() => (RETRO_THROTTLE_SLOW_MOTION, fps * ratio),
+            Some(_) => (RETRO_THROTTLE_NONE, fps),
+        };
+
+        retro_throttle_state {
+            mode,
+            rate: rate as f32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_ratio_and_throttle_state() {
+        let speed = SpeedManager::new();
+        assert_eq!(speed.ratio(), Some(1.0));
+        assert_eq!(speed.throttle_state(60.0).mode, RETRO_THROTTLE_NONE);
+
+        speed.set_slow_motion(true);
+        let state = speed.throttle_state(60.0);
+        assert_eq!(state.mode, RETRO_THROTTLE_SLOW_MOTION);
+        assert_eq!(state.rate, 30.0);
+
+        speed.set_fast_forward(true);
+        assert!(!speed.is_slow_motion());
+        assert_eq!(speed.ratio(), Some(DEFAULT_FAST_FORWARD_RATIO));
+        assert_eq!(speed.throttle_state(60.0).mode, RETRO_THROTTLE_FAST_FORWARD);
+
+        speed.set_fast_forward_ratio(0.5);
+        assert_eq!(speed.ratio(), None);
+        let state = speed.throttle_state(60.0);
+        assert_eq!(state.mode, RETRO_THROTTLE_FAST_FORWARD);
+        assert_eq!(state.rate, 0.0);
+
+        speed.set_fast_forward(false);
+        assert_eq!(speed.ratio(), Some(DEFAULT_SLOW_MOTION_RATIO));
+
+        speed.set_frame_stepping(true);
+        assert_eq!(speed.ratio(), None);
+        assert_eq!(
+            speed.throttle_state(60.0).mode,
+            RETRO_THROTTLE_FRAME_STEPPING
+        );
+    }
+}
@@ -1,37 +1,86 @@
 use crate::av_info::AvInfo;
 use crate::core_env::{self, RetroEnvCallbacks};
 use crate::graphic_api::GraphicApi;
+use crate::managers::cheat_finder_manager::{CheatFinderManager, SearchFilter};
+use crate::managers::cheat_manager::CheatManager;
+use crate::managers::controller_type_manager::ControllerTypeManager;
+use crate::managers::disk_control_manager::{DiskControlManager, DiskState};
+use crate::managers::memory_manager::{
+    MemoryAddress, MemoryManager, MemoryRegion, MemoryWatchEvent, ValueWidth,
+};
+use crate::managers::message_manager::MessageManager;
+use crate::managers::option_manager::OptionManager;
+use crate::managers::playtime_manager::PlaytimeManager;
+use crate::managers::rewind_manager::RewindManager;
+use crate::managers::run_ahead_manager::RunAheadManager;
+use crate::managers::save_ram_manager::SaveRamManager;
+use crate::managers::save_state_manager::{SaveSlot, SaveStateManager};
+use crate::managers::serialization_manager::SerializationManager;
+use crate::managers::speed_manager::SpeedManager;
+use crate::system::{ControllerDescription, InputDescriptor, System};
 use crate::tools::game_tools::{RomTools, SaveInfo};
+use crate::tools::m3u_tools::{M3uEntry, M3uTools};
+use crate::tools::save_state_tools::{
+    SAVE_STATE_FORMAT_VERSION, SaveStateHeader, current_platform,
+};
 use crate::tools::validation::InputValidator;
-use crate::{managers::option_manager::OptionManager, system::System};
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 use generics::retro_paths::RetroPaths;
-use libretro_sys::binding_libretro::LibretroRaw;
-use std::ffi::{c_uint, c_void};
-use std::path::PathBuf;
-use std::rc::Rc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use generics::retro_settings::RetroSettings;
+use libretro_sys::binding_libretro::{LibretroRaw, RETRO_MEMORY_SAVE_RAM};
+use std::ffi::{CString, c_uint, c_void};
+use std::path::{Path, PathBuf};
+use std::slice;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-pub type RetroCoreIns = Rc<RetroCore>;
+pub type RetroCoreIns = Arc<RetroCore>;
 
 pub struct RetroCore {
     pub rom_name: RwLock<String>,
+    #[doc = "CRC32 do conteúdo carregado, 0 quando não foi possível ler o arquivo"]
+    pub rom_crc32: AtomicU32,
     pub initialized: AtomicBool,
     pub game_loaded: AtomicBool,
     pub support_no_game: AtomicBool,
     pub av_info: Arc<AvInfo>,
     pub system: System,
     pub paths: RetroPaths,
+    pub settings: RetroSettings,
     pub options: Arc<OptionManager>,
+    pub save_ram: SaveRamManager,
+    pub rewind: RewindManager,
+    pub run_ahead: RunAheadManager,
+    pub speed: SpeedManager,
+    pub cheats: CheatManager,
+    pub cheat_finder: CheatFinderManager,
+    pub controller_types: ControllerTypeManager,
+    pub disk_control: DiskControlManager,
+    pub messages: MessageManager,
+    pub memory: MemoryManager,
+    pub playtime: PlaytimeManager,
+    pub save_states: SaveStateManager,
+    pub serialization: SerializationManager,
     pub callbacks: RetroEnvCallbacks,
+    pub(crate) slot: usize,
+    // o núcleo guarda o ponteiro do GET_USERNAME, então a string precisa viver tanto quanto ele
+    pub(crate) username: Option<CString>,
     raw: Arc<LibretroRaw>,
 }
 
+impl Drop for RetroCore {
+    fn drop(&mut self) {
+        core_env::release_core_slot(self.slot);
+    }
+}
+
 impl RetroCore {
     pub fn new(
         core_path: &PathBuf,
         paths: RetroPaths,
+        settings: RetroSettings,
         callbacks: RetroEnvCallbacks,
         graphic_api: GraphicApi,
     ) -> Result<RetroCoreIns, ErrorHandle> {
@@ -44,6 +93,17 @@ impl RetroCore {
             })
         }?;
 
+        // a feature mantém o comportamento antigo de mostrar tudo que o núcleo envia
+        #[cfg(feature = "core_logs")]
+        {
+            logger::set_log_level(LogTarget::Core, Some(logger::LogLevel::Debug));
+            logger::set_log_level(LogTarget::Env, Some(logger::LogLevel::Debug));
+        }
+
+        #[cfg(feature = "core_ev_logs")]
+        logger::set_log_level(LogTarget::Env, Some(logger::LogLevel::Debug));
+
+        let slot = core_env::reserve_core_slot(core_path)?;
         let system = System::new(&raw);
 
         let options = Arc::new(OptionManager::new(
@@ -51,41 +111,57 @@ impl RetroCore {
             system.info.library_name.clone().to_string(),
         ));
 
-        let core = Rc::new(RetroCore {
+        let controller_types = ControllerTypeManager::new(&paths.opt, &system.info.library_name);
+
+        let core = Arc::new(RetroCore {
             raw: Arc::new(raw),
             initialized: AtomicBool::new(false),
             game_loaded: AtomicBool::new(false),
             support_no_game: AtomicBool::new(false),
             av_info: Arc::new(AvInfo::new(graphic_api)),
             rom_name: RwLock::new("".to_string()),
+            rom_crc32: AtomicU32::new(0),
             system,
             paths,
+            username: settings.username_c_string(),
+            settings,
             options,
+            save_ram: SaveRamManager::new(),
+            rewind: RewindManager::new(),
+            run_ahead: RunAheadManager::new(),
+            speed: SpeedManager::new(),
+            cheats: CheatManager::new(),
+            cheat_finder: CheatFinderManager::new(),
+            controller_types,
+            disk_control: DiskControlManager::new(),
+            messages: MessageManager::new(),
+            memory: MemoryManager::new(),
+            playtime: PlaytimeManager::new(),
+            save_states: SaveStateManager::new(),
+            serialization: SerializationManager::new(),
             callbacks,
+            slot,
         });
 
-        core_env::configure(core.clone());
+        core_env::attach_core(slot, &core);
+        let slot_callbacks = core_env::slot_callbacks(slot);
 
         unsafe {
-            core.raw
-                .retro_set_environment(Some(core_env::core_environment));
+            core.raw.retro_set_environment(slot_callbacks.environment);
 
             core.init()?;
 
-            core.raw
-                .retro_set_audio_sample(Some(core_env::audio_sample_callback));
+            core.raw.retro_set_audio_sample(slot_callbacks.audio_sample);
 
             core.raw
-                .retro_set_audio_sample_batch(Some(core_env::audio_sample_batch_callback));
+                .retro_set_audio_sample_batch(slot_callbacks.audio_sample_batch);
 
             core.raw
-                .retro_set_video_refresh(Some(core_env::video_refresh_callback));
+                .retro_set_video_refresh(slot_callbacks.video_refresh);
 
-            core.raw
-                .retro_set_input_poll(Some(core_env::input_poll_callback));
+            core.raw.retro_set_input_poll(slot_callbacks.input_poll);
 
-            core.raw
-                .retro_set_input_state(Some(core_env::input_state_callback));
+            core.raw.retro_set_input_state(slot_callbacks.input_state);
         }
 
         Ok(core)
@@ -117,17 +193,213 @@ impl RetroCore {
             ));
         }
 
-        let loaded = RomTools::try_load_game(&self.raw, &self.system.info, &path)?;
-        self.game_loaded.store(loaded, Ordering::SeqCst);
+        let playlist = if M3uTools::is_m3u(Path::new(path)) {
+            M3uTools::parse_file(Path::new(path))?
+        } else {
+            Vec::new()
+        };
+
+        // núcleos que aceitam .m3u leem a playlist sozinhos, os outros recebem o primeiro disco
+        let core_reads_playlist = playlist.is_empty()
+            || InputValidator::validate_rom_extension(
+                Path::new(path),
+                &self.system.info.valid_extensions,
+            )
+            .is_ok();
+
+        let first_disc = if core_reads_playlist {
+            path.to_string()
+        } else {
+            playlist[0].path.to_string_lossy().to_string()
+        };
+
+        let rom_name = RomTools::get_rom_name(&PathBuf::from(path))?;
 
-        if loaded {
-            *self.rom_name.write()? = RomTools::get_rom_name(&PathBuf::from(path))?;
+        // o núcleo costuma ler as opções dentro do retro_load_game
+        self.options.load_overrides(Path::new(path), &rom_name)?;
 
-            self.av_info.update_av_info(&self.raw)?;
+        let loaded =
+            RomTools::try_load_game(&self.raw, &self.system.info, &first_disc, &self.paths.temps);
 
-            Ok(self.av_info.clone())
+        if !matches!(loaded, Ok(true)) {
+            self.options.unload_overrides()?;
+        }
+
+        if !loaded? {
+            return Err(ErrorHandle::new("nao foi possível carregar a rom"));
+        }
+
+        let discs = if core_reads_playlist {
+            &[][..]
         } else {
-            Err(ErrorHandle::new("nao foi possível carregar a rom"))
+            &playlist[1..]
+        };
+
+        let prepared = self
+            .append_discs(discs)
+            .and_then(|_| self.disk_control.set_playlist(playlist))
+            .and_then(|_| self.prepare_loaded_game(rom_name, &first_disc));
+
+        if let Err(e) = prepared {
+            self.abort_load();
+            return Err(e);
+        }
+
+        self.game_loaded.store(true, Ordering::SeqCst);
+        self.options.set_game_running(true);
+        self.load_auto_state_on_start();
+
+        Ok(self.av_info.clone())
+    }
+
+    /// Carrega vários conteúdos de uma vez usando um subsystem do núcleo, como o Super Game Boy.
+    /// Os caminhos seguem a ordem das roms do subsystem e um caminho vazio pula um conteúdo opcional.
+    pub fn load_game_special(
+        &self,
+        subsystem_ident: &str,
+        paths: Vec<String>,
+    ) -> Result<Arc<AvInfo>, ErrorHandle> {
+        if self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErrorHandle::new("Ja existe uma rom carregada no momento"));
+        }
+
+        if !self.initialized.load(Ordering::SeqCst) {
+            return Err(ErrorHandle::new(
+                "Para carregar uma rom o núcleo deve esta inicializado",
+            ));
+        }
+
+        // o nome usado nos saves vem do primeiro conteúdo informado
+        let content_path = paths
+            .iter()
+            .find(|path| !path.is_empty())
+            .ok_or_else(|| ErrorHandle::new("Nenhum conteúdo foi informado"))?;
+        let rom_name = RomTools::get_rom_name(Path::new(content_path))?;
+
+        // o núcleo pode enviar SET_SUBSYSTEM_INFO durante o load, então o lock não pode
+        // continuar aberto enquanto ele roda
+        let subsystem = self
+            .system
+            .subsystem
+            .read()?
+            .iter()
+            .find(|subsystem| subsystem.ident.as_str() == subsystem_ident)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorHandle::new(&format!(
+                    "O núcleo não possui o subsystem '{}'",
+                    subsystem_ident
+                ))
+            })?;
+
+        self.options
+            .load_overrides(Path::new(content_path), &rom_name)?;
+
+        let loaded = RomTools::try_load_game_special(
+            &self.raw,
+            &self.system.info,
+            &subsystem,
+            &paths,
+            &self.paths.temps,
+        );
+
+        if !matches!(loaded, Ok(true)) {
+            self.options.unload_overrides()?;
+        }
+
+        if !loaded? {
+            return Err(ErrorHandle::new("nao foi possível carregar a rom"));
+        }
+
+        if let Err(e) = self.prepare_loaded_game(rom_name, content_path) {
+            self.abort_load();
+            return Err(e);
+        }
+
+        self.game_loaded.store(true, Ordering::SeqCst);
+        self.options.set_game_running(true);
+        self.load_auto_state_on_start();
+
+        Ok(self.av_info.clone())
+    }
+
+    fn prepare_loaded_game(&self, rom_name: String, content_path: &str) -> Result<(), ErrorHandle> {
+        self.save_ram.set_file_path(RomTools::get_save_ram_path(
+            &self.paths.save,
+            &self.system.info.library_name,
+            &rom_name,
+        )?)?;
+        self.restore_save_ram()?;
+
+        self.cheats.load(
+            &self.paths.cheats,
+            &self.system.info.library_name,
+            &rom_name,
+        )?;
+        self.apply_cheats()?;
+
+        // sem o CRC o save state só perde a verificação da rom, então o jogo continua carregado
+        let crc32 = RomTools::get_rom_crc32(content_path, &self.system.info.valid_extensions)
+            .unwrap_or_else(|e| {
+                logger::warn(
+                    LogTarget::Frontend,
+                    format!("Não foi possível calcular o CRC32 da rom: {e:?}"),
+                );
+                0
+            });
+        self.rom_crc32.store(crc32, Ordering::SeqCst);
+        self.playtime.reset()?;
+        self.serialization.start_session(Self::now_secs());
+
+        self.save_states.set_dir(RomTools::get_save_state_dir(
+            &self.paths.save,
+            &self.system.info.library_name,
+            &rom_name,
+        )?)?;
+
+        *self.rom_name.write()? = rom_name;
+
+        self.av_info.update_av_info(&self.raw)?;
+
+        Ok(())
+    }
+
+    // desfaz um load que o núcleo aceitou mas que não pôde ser preparado, sem gravar a SRAM
+    // do núcleo por cima do .srm que não foi restaurado
+    fn abort_load(&self) {
+        unsafe {
+            self.raw.retro_unload_game();
+        }
+
+        let _ = self.save_ram.clear();
+        let _ = self.cheats.clear();
+        let _ = self.save_states.clear();
+        let _ = self.options.unload_overrides();
+        let _ = self.disk_control.clear();
+    }
+
+    fn load_auto_state_on_start(&self) {
+        if !self.settings.load_auto_state
+            || !self.save_states.exists(SaveSlot::Auto).unwrap_or(false)
+        {
+            return;
+        }
+
+        // núcleos MUST_INITIALIZE só aceitam o state depois do primeiro retro_run
+        if self.serialization.can_serialize() {
+            self.load_auto_state();
+        } else {
+            self.serialization.set_pending_auto_load();
+        }
+    }
+
+    // um auto state que o núcleo não aceita não deve impedir o jogo de abrir
+    fn load_auto_state(&self) {
+        if let Err(e) = self.load_state_slot(SaveSlot::Auto) {
+            logger::warn(
+                LogTarget::Frontend,
+                format!("Não foi possível carregar o auto save state: {e:?}"),
+            );
         }
     }
 
@@ -157,15 +429,21 @@ impl RetroCore {
         }
 
         unsafe { self.raw.retro_run() }
+        self.serialization.mark_frame_ran();
 
-        Ok(())
+        if self.serialization.take_pending_auto_load() {
+            self.load_auto_state();
+        }
+
+        self.playtime.tick()?;
+        self.apply_memory_cheats()
     }
 
     pub fn de_init(&self) -> Result<(), ErrorHandle> {
         //Se uma *rom* estive carrega ela deve ser descarregada primeiro
         if let Err(e) = self.unload_game() {
             self.initialized.store(false, Ordering::SeqCst);
-            core_env::delete_local_core_ctx();
+            core_env::detach_core(self.slot);
 
             return Err(e);
         }
@@ -174,7 +452,7 @@ impl RetroCore {
             self.raw.retro_deinit();
         }
         self.initialized.store(false, Ordering::SeqCst);
-        core_env::delete_local_core_ctx();
+        core_env::detach_core(self.slot);
 
         Ok(())
     }
@@ -196,6 +474,58 @@ impl RetroCore {
         Ok(())
     }
 
+    #[doc = "tipos de controle que o núcleo declarou para a porta, ex: multitap ou Super Scope"]
+    pub fn get_controller_types(
+        &self,
+        port: u32,
+    ) -> Result<Vec<ControllerDescription>, ErrorHandle> {
+        Ok(self
+            .system
+            .ports
+            .read()?
+            .iter()
+            .filter(|description| *description.port == port)
+            .cloned()
+            .collect())
+    }
+
+    #[doc = "tipo escolhido pelo usuário para a porta, ou `default` se nenhum foi escolhido"]
+    pub fn get_controller_type(&self, port: u32, default: u32) -> Result<u32, ErrorHandle> {
+        Ok(self.controller_types.get(port)?.unwrap_or(default))
+    }
+
+    /// Connects `device` to `port` and remembers the choice for the next time this core is
+    /// loaded. The device must be one of the types the core declared for the port.
+    pub fn set_controller_type(&self, port: i16, device: u32) -> Result<(), ErrorHandle> {
+        let valid_port = InputValidator::validate_controller_port(port)? as u32;
+        let types = self.get_controller_types(valid_port)?;
+
+        if !types.iter().any(|description| *description.id == device) {
+            return Err(ErrorHandle::new(&format!(
+                "O núcleo não aceita o controle {device} na porta {port}"
+            )));
+        }
+
+        self.connect_controller(port, device)?;
+        self.controller_types.set(valid_port, device)
+    }
+
+    #[doc = "o que cada botão faz no jogo atual, de acordo com o núcleo, para o device na porta informada"]
+    pub fn get_input_descriptors(
+        &self,
+        port: u32,
+        device: u32,
+    ) -> Result<Vec<InputDescriptor>, ErrorHandle> {
+        Ok(self
+            .system
+            .input_descriptors
+            .read()?
+            .iter()
+            .filter(|descriptor| descriptor.matches(port, device))
+            .cloned()
+            .collect())
+    }
+
     pub fn unload_game(&self) -> Result<(), ErrorHandle> {
         if !self.game_loaded.load(Ordering::SeqCst) {
             return Ok(());
@@ -207,15 +537,424 @@ impl RetroCore {
             ));
         }
 
+        // a SRAM precisa ser salva antes do core liberar a memória do jogo
+        let flushed = self.flush_save_ram();
+
+        if unsafe { self.raw.retro_serialize_size() } > 0
+            && self.serialization.can_serialize()
+            && let Err(e) = self.save_state_slot(SaveSlot::Auto, None)
+        {
+            logger::warn(
+                LogTarget::Frontend,
+                format!("Não foi possível criar o auto save state: {e:?}"),
+            );
+        }
+
+        self.options.set_game_running(false);
+
         unsafe {
             self.raw.retro_unload_game();
         }
         self.game_loaded.store(false, Ordering::SeqCst);
+        self.save_ram.clear()?;
+        self.rewind.clear()?;
+        self.run_ahead.reset();
+        self.serialization.reset();
+        self.cheats.clear()?;
+        self.cheat_finder.reset()?;
+        self.memory.clear()?;
+        self.playtime.reset()?;
+        self.save_states.clear()?;
+        self.rom_crc32.store(0, Ordering::SeqCst);
+        self.options.unload_overrides()?;
+        self.disk_control.clear()?;
+        self.system.input_descriptors.write()?.clear();
+
+        flushed.map(|_| ())
+    }
+
+    fn append_discs(&self, discs: &[M3uEntry]) -> Result<(), ErrorHandle> {
+        if discs.is_empty() {
+            return Ok(());
+        }
+
+        if !self.disk_control.is_available() {
+            logger::warn(
+                LogTarget::Frontend,
+                "O núcleo não possui controle de disco, apenas o primeiro disco da playlist foi carregado",
+            );
+            return Ok(());
+        }
+
+        for disc in discs {
+            let rom_info = RomTools::create_validated_game_info(
+                &disc.path.to_string_lossy(),
+                &self.system.info,
+                &self.paths.temps,
+            )?;
+
+            self.disk_control.append_image(rom_info)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_disk_state(&self) -> Result<DiskState, ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.disk_control.get_state()
+    }
+
+    pub fn set_disk_ejected(&self, ejected: bool) -> Result<DiskState, ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.disk_control.set_ejected(ejected)?;
+        self.disk_control.get_state()
+    }
+
+    pub fn select_disk(&self, index: usize) -> Result<DiskState, ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.disk_control.select_index(index)?;
+        self.disk_control.get_state()
+    }
+
+    pub fn run_with_run_ahead(&self) -> Result<(), ErrorHandle> {
+        if !self.run_ahead.is_active() {
+            return self.run();
+        }
+
+        // antes do primeiro frame um núcleo MUST_INITIALIZE ainda não consegue serializar
+        if !self.serialization.can_serialize() {
+            return self.run();
+        }
+
+        if !self.can_serialize_every_frame() {
+            self.run_ahead.mark_unsupported();
+            return self.run();
+        }
+
+        let result = self.run_ahead_frames();
+        self.av_info.output.set(true, true);
+
+        result
+    }
+
+    // O primeiro frame é o real: apenas o áudio dele é reproduzido. Os frames
+    // seguintes são escondidos, menos o último, que é o que aparece na tela.
+    // No final o estado do primeiro frame é restaurado.
+    fn run_ahead_frames(&self) -> Result<(), ErrorHandle> {
+        let output = &self.av_info.output;
+
+        output.set(false, true);
+        self.run()?;
+
+        output.fast_savestates.store(true, Ordering::SeqCst);
+        let state = self.serialize();
+        output.fast_savestates.store(false, Ordering::SeqCst);
+
+        let mut state = match state {
+            Some(state) => state,
+            None => {
+                self.run_ahead.mark_unsupported();
+                return Ok(());
+            }
+        };
+
+        let frames = self.run_ahead.frames();
+
+        for frame in 1..=frames {
+            output.set(frame == frames, false);
+            self.run()?;
+        }
+
+        output.fast_savestates.store(true, Ordering::SeqCst);
+        let restored = self.unserialize(&mut state);
+        output.fast_savestates.store(false, Ordering::SeqCst);
+
+        if !restored {
+            self.run_ahead.mark_unsupported();
+        }
+
+        Ok(())
+    }
+
+    fn can_serialize_every_frame(&self) -> bool {
+        self.serialization.can_serialize_every_frame()
+            && unsafe { self.raw.retro_serialize_size() } > 0
+    }
+
+    fn serialize(&self) -> Option<Vec<u8>> {
+        let size = unsafe { self.raw.retro_serialize_size() };
+
+        if size == 0 {
+            return None;
+        }
+
+        let mut data = vec![0u8; size];
+
+        let serialized = unsafe {
+            self.raw
+                .retro_serialize(data.as_mut_ptr() as *mut c_void, size)
+        };
+
+        serialized.then_some(data)
+    }
+
+    fn unserialize(&self, data: &mut [u8]) -> bool {
+        unsafe {
+            self.raw
+                .retro_unserialize(data.as_mut_ptr() as *mut c_void, data.len())
+        }
+    }
+
+    pub fn rewind_capture(&self) -> Result<(), ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst)
+            || !self.rewind.should_capture()
+            || !self.serialization.can_serialize_every_frame()
+        {
+            return Ok(());
+        }
+
+        if let Some(data) = self.serialize() {
+            self.rewind.push(data)?;
+        }
 
         Ok(())
     }
 
-    pub fn save_state(&self, slot: usize) -> Result<PathBuf, ErrorHandle> {
+    pub fn rewind_step_back(&self) -> Result<bool, ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let mut data = match self.rewind.step_back()? {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+
+        if !self.unserialize(&mut data) {
+            return Err(ErrorHandle::new(
+                "O núcleo não conseguiu restaurar o snapshot do rewind",
+            ));
+        }
+
+        Ok(true)
+    }
+
+    /// Volta um snapshot e roda um frame só para mostrar o estado restaurado.
+    /// O frame não toca áudio e não aplica os cheats de memória, já que ele anda para frente.
+    pub fn rewind_frame(&self) -> Result<bool, ErrorHandle> {
+        if !self.rewind_step_back()? {
+            return Ok(false);
+        }
+
+        let output = &self.av_info.output;
+
+        output.set(true, false);
+        unsafe { self.raw.retro_run() }
+        output.set(true, true);
+
+        Ok(true)
+    }
+
+    pub fn set_cheat_enabled(&self, index: usize, enabled: bool) -> Result<(), ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.cheats.set_enabled(index, enabled)?;
+        self.apply_cheats()
+    }
+
+    fn apply_cheats(&self) -> Result<(), ErrorHandle> {
+        let cheats = self.cheats.get_cheats()?;
+
+        unsafe {
+            self.raw.retro_cheat_reset();
+        }
+
+        for (index, cheat) in cheats.iter().enumerate() {
+            // os cheats de memória são aplicados pelo frontend depois de cada frame
+            if !cheat.enabled || cheat.memory.is_some() {
+                continue;
+            }
+
+            let code = CString::new(cheat.code.as_str())?;
+
+            unsafe {
+                self.raw
+                    .retro_cheat_set(index as c_uint, true, code.as_ptr());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_memory_cheats(&self) -> Result<(), ErrorHandle> {
+        for cheat in self.cheats.enabled_memory_cheats()? {
+            let address = MemoryAddress::Region(MemoryRegion::SystemRam, cheat.offset);
+            let data = cheat.width.encode(cheat.value, cheat.big_endian);
+
+            // um cheat que não cabe mais na memória é ignorado para não interromper o jogo
+            let _ = self
+                .memory
+                .write(address, &data, &|region| self.region_memory(region));
+        }
+
+        Ok(())
+    }
+
+    #[doc = "começa uma busca na SYSTEM_RAM, de preferência com o jogo pausado"]
+    pub fn start_cheat_search(
+        &self,
+        width: ValueWidth,
+        big_endian: bool,
+    ) -> Result<usize, ErrorHandle> {
+        let memory = self.system_ram()?;
+        self.cheat_finder.start(memory, width, big_endian)
+    }
+
+    pub fn filter_cheat_search(&self, filter: SearchFilter) -> Result<usize, ErrorHandle> {
+        let memory = self.system_ram()?;
+        self.cheat_finder.filter(memory, filter)
+    }
+
+    #[doc = "salva o candidato como um cheat do jogo, que já fica ativo"]
+    pub fn add_cheat_from_search(
+        &self,
+        offset: usize,
+        value: u32,
+        desc: &str,
+    ) -> Result<usize, ErrorHandle> {
+        self.ensure_game_loaded()?;
+
+        let index = self
+            .cheats
+            .add(self.cheat_finder.make_cheat(offset, value, desc)?)?;
+        self.apply_memory_cheats()?;
+
+        Ok(index)
+    }
+
+    fn system_ram(&self) -> Result<&[u8], ErrorHandle> {
+        self.ensure_game_loaded()?;
+
+        match self.region_memory(MemoryRegion::SystemRam) {
+            Some((data, size)) => Ok(unsafe { slice::from_raw_parts(data, size) }),
+            None => Err(ErrorHandle::new("O núcleo não expõe a SYSTEM_RAM")),
+        }
+    }
+
+    pub fn autosave_save_ram(&self) -> Result<Option<PathBuf>, ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) || !self.save_ram.autosave_is_due()? {
+            return Ok(None);
+        }
+
+        self.flush_save_ram()
+    }
+
+    pub fn flush_save_ram(&self) -> Result<Option<PathBuf>, ErrorHandle> {
+        match self.get_save_ram() {
+            Some(memory) => self.save_ram.flush(memory),
+            None => Ok(None),
+        }
+    }
+
+    fn restore_save_ram(&self) -> Result<(), ErrorHandle> {
+        unsafe {
+            let size = self.raw.retro_get_memory_size(RETRO_MEMORY_SAVE_RAM);
+            let data = self.raw.retro_get_memory_data(RETRO_MEMORY_SAVE_RAM);
+
+            if size == 0 || data.is_null() {
+                return Ok(());
+            }
+
+            self.save_ram
+                .restore(slice::from_raw_parts_mut(data as *mut u8, size))?;
+        }
+
+        Ok(())
+    }
+
+    fn get_save_ram(&self) -> Option<&[u8]> {
+        unsafe {
+            let size = self.raw.retro_get_memory_size(RETRO_MEMORY_SAVE_RAM);
+            let data = self.raw.retro_get_memory_data(RETRO_MEMORY_SAVE_RAM);
+
+            if size == 0 || data.is_null() {
+                None
+            } else {
+                Some(slice::from_raw_parts(data as *const u8, size))
+            }
+        }
+    }
+
+    #[doc = "lê a memória do jogo carregado, deve ser chamada na thread do núcleo"]
+    pub fn read_memory(&self, address: MemoryAddress, len: usize) -> Result<Vec<u8>, ErrorHandle> {
+        self.ensure_game_loaded()?;
+        self.memory
+            .read(address, len, &|region| self.region_memory(region))
+    }
+
+    #[doc = "escreve na memória do jogo carregado, deve ser chamada na thread do núcleo"]
+    pub fn write_memory(&self, address: MemoryAddress, data: &[u8]) -> Result<(), ErrorHandle> {
+        self.ensure_game_loaded()?;
+        self.memory
+            .write(address, data, &|region| self.region_memory(region))
+    }
+
+    #[doc = "deve ser chamada depois de cada frame, retorna os watches cujo valor mudou"]
+    pub fn check_memory_watches(&self) -> Result<Vec<MemoryWatchEvent>, ErrorHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Ok(Vec::new());
+        }
+
+        self.memory
+            .check_watches(&|region| self.region_memory(region))
+    }
+
+    fn ensure_game_loaded(&self) -> Result<(), ErrorHandle> {
+        if self.game_loaded.load(Ordering::SeqCst) {
+            Ok(())
+        } else {
+            Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"))
+        }
+    }
+
+    fn region_memory(&self, region: MemoryRegion) -> Option<(*mut u8, usize)> {
+        unsafe {
+            let size = self.raw.retro_get_memory_size(region.id());
+            let data = self.raw.retro_get_memory_data(region.id());
+
+            if size == 0 || data.is_null() {
+                None
+            } else {
+                Some((data as *mut u8, size))
+            }
+        }
+    }
+
+    #[doc = "`thumbnail` é um PNG da tela que fica guardado dentro do save state"]
+    pub fn save_state(
+        &self,
+        slot: usize,
+        thumbnail: Option<&[u8]>,
+    ) -> Result<PathBuf, ErrorHandle> {
+        self.save_state_slot(SaveSlot::Index(slot), thumbnail)
+    }
+
+    pub fn save_state_slot(
+        &self,
+        slot: SaveSlot,
+        thumbnail: Option<&[u8]>,
+    ) -> Result<PathBuf, ErrorHandle> {
         if !self.game_loaded.load(Ordering::SeqCst) {
             return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"));
         }
@@ -226,23 +965,42 @@ impl RetroCore {
             ));
         }
 
+        self.serialization.ensure_can_serialize()?;
+
         let rom_name = self.rom_name.read()?.to_string();
 
+        // núcleos CORE_VARIABLE_SIZE podem mudar o tamanho a cada frame, então ele é sempre consultado
+        let buffer_size = unsafe { self.raw.retro_serialize_size() };
+
         let save_info = SaveInfo::new(
             &self.paths.save,
             &self.system.info.library_name,
             &rom_name,
             slot,
-            unsafe { self.raw.retro_serialize_size() },
+            buffer_size,
         )?;
 
-        RomTools::create_save_state(save_info, |data, size| unsafe {
-            self.raw
-                .retro_serialize(data.as_mut_ptr() as *mut c_void, size)
-        })
+        let header = self.save_state_header(buffer_size)?;
+
+        let backup = self.save_states.read_save_backup(slot)?;
+
+        let path =
+            RomTools::create_save_state(save_info, &header, thumbnail, |data, size| unsafe {
+                self.raw
+                    .retro_serialize(data.as_mut_ptr() as *mut c_void, size)
+            })?;
+
+        // um save que falhou não mudou o slot, então o backup anterior continua valendo
+        self.save_states.set_save_backup(backup)?;
+
+        Ok(path)
     }
 
     pub fn load_state(&self, slot: usize) -> Result<(), ErrorHandle> {
+        self.load_state_slot(SaveSlot::Index(slot))
+    }
+
+    pub fn load_state_slot(&self, slot: SaveSlot) -> Result<(), ErrorHandle> {
         if !self.game_loaded.load(Ordering::SeqCst) {
             return Err(ErrorHandle::new("Uma rom precisa ser carregada primeiro"));
         }
@@ -253,21 +1011,94 @@ impl RetroCore {
             ));
         }
 
+        self.serialization.ensure_can_serialize()?;
+
         let rom_name = self.rom_name.read()?.to_string();
 
-        let save_info = SaveInfo::new(
+        let buffer_size = unsafe { self.raw.retro_serialize_size() };
+
+        let mut save_info = SaveInfo::new(
             &self.paths.save,
             &self.system.info.library_name,
             &rom_name,
             slot,
-            unsafe { self.raw.retro_serialize_size() },
+            buffer_size,
         )?;
+        save_info.variable_size = self.serialization.is_variable_size();
 
-        RomTools::load_save_state(save_info, |data, size| unsafe {
-            self.raw
-                .retro_unserialize(data.as_mut_ptr() as *mut c_void, size)
+        // o tamanho de um núcleo CORE_VARIABLE_SIZE não indica se o state é de outra versão
+        let current = self.save_state_header(if save_info.variable_size {
+            0
+        } else {
+            buffer_size
         })?;
+        let previous = self.serialize();
+
+        let header = RomTools::load_save_state(
+            save_info,
+            &current,
+            |found| self.serialization.check_state(found, &current),
+            |data, size| unsafe {
+                self.raw
+                    .retro_unserialize(data.as_mut_ptr() as *mut c_void, size)
+            },
+        )?;
+
+        if let Some(header) = header {
+            self.playtime.set(Duration::from_secs(header.playtime))?;
+        }
+
+        if let Some(previous) = previous {
+            self.save_states.set_load_backup(previous)?;
+        }
 
         Ok(())
     }
+
+    #[doc = "volta o slot sobrescrito pelo último save ao que era antes, retorna o slot restaurado"]
+    pub fn undo_save_state(&self) -> Result<Option<SaveSlot>, ErrorHandle> {
+        self.ensure_game_loaded()?;
+        self.save_states.undo_save()
+    }
+
+    #[doc = "volta o jogo ao ponto em que estava antes do último load, retorna false se não há o que desfazer"]
+    pub fn undo_load_state(&self) -> Result<bool, ErrorHandle> {
+        self.ensure_game_loaded()?;
+
+        let mut state = match self.save_states.take_load_backup()? {
+            Some(state) => state,
+            None => return Ok(false),
+        };
+
+        if !self.unserialize(&mut state) {
+            return Err(ErrorHandle::new(
+                "O núcleo não conseguiu restaurar o estado anterior ao load",
+            ));
+        }
+
+        Ok(true)
+    }
+
+    #[doc = "cabeçalho com o núcleo, a rom e o tempo de jogo atuais"]
+    fn save_state_header(&self, state_size: usize) -> Result<SaveStateHeader, ErrorHandle> {
+        Ok(SaveStateHeader {
+            format_version: SAVE_STATE_FORMAT_VERSION,
+            library_name: self.system.info.library_name.to_string(),
+            library_version: self.system.info.library_version.to_string(),
+            rom_crc32: self.rom_crc32.load(Ordering::SeqCst),
+            timestamp: Self::now_secs(),
+            playtime: self.playtime.elapsed()?.as_secs(),
+            compressed: self.settings.compress_save_states,
+            state_size: state_size as u64,
+            platform: current_platform(),
+            big_endian: cfg!(target_endian = "big"),
+        })
+    }
+
+    fn now_secs() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default()
+    }
 }
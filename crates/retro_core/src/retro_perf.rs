@@ -1,14 +1,15 @@
 use crate::libretro_sys::binding_libretro::{
-    retro_perf_counter, retro_perf_tick_t, retro_time_t, RETRO_SIMD_AVX, RETRO_SIMD_AVX2,
-    RETRO_SIMD_MMX, RETRO_SIMD_SSE, RETRO_SIMD_SSE2, RETRO_SIMD_SSE3, RETRO_SIMD_SSE4,
-    RETRO_SIMD_SSE42,
+    RETRO_SIMD_AVX, RETRO_SIMD_AVX2, RETRO_SIMD_MMX, RETRO_SIMD_SSE, RETRO_SIMD_SSE2,
+    RETRO_SIMD_SSE3, RETRO_SIMD_SSE4, RETRO_SIMD_SSE42, retro_perf_counter, retro_perf_tick_t,
+    retro_time_t,
 };
 
+use generics::logger::{self, LogTarget};
 use raw_cpuid::CpuId;
 use std::ptr;
 use std::sync::{
-    atomic::{AtomicPtr, Ordering},
     OnceLock,
+    atomic::{AtomicPtr, Ordering},
 };
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
@@ -73,9 +74,12 @@ pub extern "C" fn core_perf_log() {
     let counter_ptr = LAST_COUNTER.load(Ordering::Acquire);
 
     if let Some(counter) = unsafe { counter_ptr.as_ref() } {
-        println!(
-            "[perf] ident={:?} total={} ticks",
-            counter.ident, counter.total
+        logger::info(
+            LogTarget::Core,
+            format!(
+                "[perf] ident={:?} total={} ticks",
+                counter.ident, counter.total
+            ),
         );
     }
 }
@@ -6,8 +6,9 @@ use generics::{
     error_handle::ErrorHandle,
 };
 use libretro_sys::binding_libretro::{
-    LibretroRaw, retro_controller_description, retro_controller_info, retro_subsystem_info,
-    retro_subsystem_memory_info, retro_subsystem_rom_info, retro_system_info,
+    LibretroRaw, RETRO_DEVICE_MASK, retro_controller_description, retro_controller_info,
+    retro_input_descriptor, retro_subsystem_info, retro_subsystem_memory_info,
+    retro_subsystem_rom_info, retro_system_info,
 };
 use std::sync::{Arc, RwLock, atomic::AtomicU8};
 
@@ -20,19 +21,37 @@ pub struct SysInfo {
     pub block_extract: Arc<bool>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct MemoryInfo {
     pub extension: Arc<String>,
     pub type_: Arc<u32>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct ControllerDescription {
+    pub port: Arc<u32>,
     pub desc: Arc<String>,
     pub id: Arc<u32>,
 }
 
-#[derive(Default, Debug)]
+/// O que cada botão faz no jogo atual, ex: "B" -> "Jump".
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct InputDescriptor {
+    pub port: u32,
+    pub device: u32,
+    pub index: u32,
+    pub id: u32,
+    pub description: String,
+}
+
+impl InputDescriptor {
+    // os descritores usam apenas o tipo base, mas o device conectado pode ser uma subclasse
+    pub fn matches(&self, port: u32, device: u32) -> bool {
+        self.port == port && self.device == device & RETRO_DEVICE_MASK
+    }
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct SubSystemRomInfo {
     pub desc: Arc<String>,
     pub valid_extensions: Arc<String>,
@@ -51,11 +70,28 @@ pub struct SubSystemInfo {
     pub roms: RwLock<Vec<SubSystemRomInfo>>,
 }
 
+impl Clone for SubSystemInfo {
+    fn clone(&self) -> Self {
+        let roms = match self.roms.read() {
+            Ok(roms) => roms.clone(),
+            Err(e) => e.into_inner().clone(),
+        };
+
+        Self {
+            id: self.id.clone(),
+            desc: self.desc.clone(),
+            ident: self.ident.clone(),
+            roms: RwLock::new(roms),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct System {
     pub info: SysInfo,
     pub ports: Arc<RwLock<Vec<ControllerDescription>>>,
     pub subsystem: Arc<RwLock<Vec<SubSystemInfo>>>,
+    pub input_descriptors: Arc<RwLock<Vec<InputDescriptor>>>,
     pub performance_level: Arc<AtomicU8>,
 }
 
@@ -75,6 +111,7 @@ impl System {
             System {
                 ports: Arc::new(RwLock::new(Vec::new())),
                 subsystem: Arc::new(RwLock::new(Vec::new())),
+                input_descriptors: Arc::new(RwLock::new(Vec::new())),
                 performance_level: Arc::new(AtomicU8::new(0)),
                 info: SysInfo {
                     library_name: Arc::new(get_str_from_ptr(sys_info.library_name)),
@@ -140,7 +177,8 @@ impl System {
     ) -> Result<(), ErrorHandle> {
         self.ports.write()?.clear();
 
-        for raw_ctr_info in raw_ctr_infos {
+        // cada entrada do array descreve uma porta, na mesma ordem das portas do núcleo
+        for (port, raw_ctr_info) in raw_ctr_infos.into_iter().enumerate() {
             if raw_ctr_info.types.is_null() {
                 break;
             }
@@ -156,6 +194,7 @@ impl System {
                 }
 
                 let controller_description = ControllerDescription {
+                    port: Arc::new(port as u32),
                     desc: Arc::new(get_str_from_ptr(ctr_type.desc)),
                     id: Arc::new(ctr_type.id),
                 };
@@ -165,4 +204,34 @@ impl System {
         }
         Ok(())
     }
+
+    // a lista termina no primeiro descritor sem descrição
+    pub fn get_input_descriptors(
+        &self,
+        raw_descriptors: *const retro_input_descriptor,
+    ) -> Result<(), ErrorHandle> {
+        let mut descriptors = Vec::new();
+        let mut raw_descriptor = raw_descriptors;
+
+        loop {
+            let descriptor = unsafe { &*raw_descriptor };
+
+            if descriptor.description.is_null() {
+                break;
+            }
+
+            descriptors.push(InputDescriptor {
+                port: descriptor.port,
+                device: descriptor.device,
+                index: descriptor.index,
+                id: descriptor.id,
+                description: get_str_from_ptr(descriptor.description),
+            });
+
+            raw_descriptor = unsafe { raw_descriptor.add(1) };
+        }
+
+        *self.input_descriptors.write()? = descriptors;
+        Ok(())
+    }
 }
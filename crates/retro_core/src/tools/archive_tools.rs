@@ -0,0 +1,281 @@
+use crate::tools::validation::InputValidator;
+use generics::error_handle::ErrorHandle;
+use sevenz_rust::{Password, SevenZReader};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// A content path pointing to a `.zip` or `.7z` archive, optionally with the
+/// file inside it selected using the `archive.zip#inner.sfc` syntax
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivePath {
+    pub archive: PathBuf,
+    pub inner: Option<String>,
+}
+
+/// Access to roms compressed inside archives
+pub struct ArchiveTools;
+
+impl ArchiveTools {
+    pub fn is_archive(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip") || ext.eq_ignore_ascii_case("7z"))
+    }
+
+    /// Returns `None` when the path is not an archive
+    pub fn parse_path(path: &str) -> Option<ArchivePath> {
+        if let Some((archive, inner)) = path.split_once('#')
+            && Self::is_archive(Path::new(archive))
+            && !inner.is_empty()
+        {
+            return Some(ArchivePath {
+                archive: PathBuf::from(archive),
+                inner: Some(inner.to_string()),
+            });
+        }
+
+        Self::is_archive(Path::new(path)).then(|| ArchivePath {
+            archive: PathBuf::from(path),
+            inner: None,
+        })
+    }
+
+    /// Names of every file inside the archive, directories are left out
+    pub fn list_files(archive: &Path) -> Result<Vec<String>, ErrorHandle> {
+        if Self::is_7z(archive) {
+            let reader = SevenZReader::open(archive, Password::empty())
+                .map_err(|e| Self::archive_error(archive, e))?;
+
+            return Ok(reader
+                .archive()
+                .files
+                .iter()
+                .filter(|entry| !entry.is_directory())
+                .map(|entry| entry.name().to_string())
+                .collect());
+        }
+
+        let mut zip = Self::open_zip(archive)?;
+        let mut names = Vec::new();
+
+        for index in 0..zip.len() {
+            let file = zip
+                .by_index(index)
+                .map_err(|e| Self::archive_error(archive, e))?;
+
+            if !file.is_dir() {
+                names.push(file.name().to_string());
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Find the only file inside the archive the core can run
+    pub fn find_single_rom(
+        archive: &Path,
+        valid_extensions: &String,
+    ) -> Result<String, ErrorHandle> {
+        let mut roms: Vec<String> = Self::list_files(archive)?
+            .into_iter()
+            .filter(|name| {
+                InputValidator::validate_rom_extension(Path::new(name), valid_extensions).is_ok()
+            })
+            .collect();
+
+        match roms.len() {
+            0 => Err(ErrorHandle::new(&format!(
+                "No compatible rom found inside {}. Valid extensions: {}",
+                archive.display(),
+                valid_extensions
+            ))),
+            1 => Ok(roms.remove(0)),
+            _ => Err(ErrorHandle::new(&format!(
+                "{} has more than one rom, select one using {}#<file>: {}",
+                archive.display(),
+                archive.display(),
+                roms.join(", ")
+            ))),
+        }
+    }
+
+    /// Decompress a single file into memory, refusing files bigger than `max_size` bytes
+    pub fn read_file(archive: &Path, name: &str, max_size: u64) -> Result<Vec<u8>, ErrorHandle> {
+        let too_big = |size: u64| {
+            ErrorHandle::new(&format!(
+                "{} inside {} is too large ({} MB)",
+                name,
+                archive.display(),
+                size / (1024 * 1024)
+            ))
+        };
+
+        if Self::is_7z(archive) {
+            let mut reader = SevenZReader::open(archive, Password::empty())
+                .map_err(|e| Self::archive_error(archive, e))?;
+
+            let mut data = None;
+
+            reader
+                .for_each_entries(|entry, entry_reader| {
+                    if entry.name() != name || entry.size() > max_size {
+                        // o stream precisa ser consumido para o leitor seguir para a próxima entrada
+                        io::copy(entry_reader, &mut io::sink())?;
+                        return Ok(true);
+                    }
+
+                    let mut buf = Vec::with_capacity(entry.size() as usize);
+                    entry_reader.read_to_end(&mut buf)?;
+                    data = Some(buf);
+
+                    Ok(false)
+                })
+                .map_err(|e| Self::archive_error(archive, e))?;
+
+            return match data {
+                Some(data) => Ok(data),
+                None => match Self::find_7z_size(archive, name)? {
+                    Some(size) => Err(too_big(size)),
+                    None => Err(Self::not_found(archive, name)),
+                },
+            };
+        }
+
+        let mut zip = Self::open_zip(archive)?;
+        let mut file = zip
+            .by_name(name)
+            .map_err(|_| Self::not_found(archive, name))?;
+
+        if file.size() > max_size {
+            return Err(too_big(file.size()));
+        }
+
+        let mut data = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut data)?;
+
+        Ok(data)
+    }
+
+    /// Decompress a single file into `out_dir`, keeping only its file name
+    pub fn extract_file(
+        archive: &Path,
+        name: &str,
+        out_dir: &Path,
+        max_size: u64,
+    ) -> Result<PathBuf, ErrorHandle> {
+        let file_name = Path::new(name).file_name().ok_or_else(|| {
+            ErrorHandle::new(&format!("Invalid file name inside archive: {}", name))
+        })?;
+
+        let data = Self::read_file(archive, name, max_size)?;
+
+        fs::create_dir_all(out_dir)?;
+        let out_path = out_dir.join(file_name);
+        fs::write(&out_path, data)?;
+
+        Ok(out_path)
+    }
+
+    fn is_7z(archive: &Path) -> bool {
+        archive
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("7z"))
+    }
+
+    fn open_zip(archive: &Path) -> Result<ZipArchive<File>, ErrorHandle> {
+        ZipArchive::new(File::open(archive)?).map_err(|e| Self::archive_error(archive, e))
+    }
+
+    fn find_7z_size(archive: &Path, name: &str) -> Result<Option<u64>, ErrorHandle> {
+        let reader = SevenZReader::open(archive, Password::empty())
+            .map_err(|e| Self::archive_error(archive, e))?;
+
+        Ok(reader
+            .archive()
+            .files
+            .iter()
+            .find(|entry| entry.name() == name)
+            .map(|entry| entry.size()))
+    }
+
+    fn archive_error(archive: &Path, error: impl std::fmt::Display) -> ErrorHandle {
+        ErrorHandle::new(&format!(
+            "Failed to read archive {}: {}",
+            archive.display(),
+            error
+        ))
+    }
+
+    fn not_found(archive: &Path, name: &str) -> ErrorHandle {
+        ErrorHandle::new(&format!("{} not found inside {}", name, archive.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    fn create_zip(dir: &Path, files: &[(&str, &[u8])]) -> PathBuf {
+        let path = dir.join("games.zip");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+
+        for (name, data) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+
+        zip.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            ArchiveTools::parse_path("/roms/games.zip#sub/game.sfc"),
+            Some(ArchivePath {
+                archive: PathBuf::from("/roms/games.zip"),
+                inner: Some("sub/game.sfc".to_string()),
+            })
+        );
+        assert_eq!(
+            ArchiveTools::parse_path("/roms/games.7Z"),
+            Some(ArchivePath {
+                archive: PathBuf::from("/roms/games.7Z"),
+                inner: None,
+            })
+        );
+        assert_eq!(ArchiveTools::parse_path("/roms/game #1.sfc"), None);
+    }
+
+    #[test]
+    fn test_find_and_read_rom_inside_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let valid_extensions = "sfc|smc".to_string();
+
+        let single = create_zip(
+            temp_dir.path(),
+            &[("readme.txt", b"hi"), ("game.sfc", b"rom")],
+        );
+        let rom = ArchiveTools::find_single_rom(&single, &valid_extensions).unwrap();
+        assert_eq!(rom, "game.sfc");
+        assert_eq!(
+            ArchiveTools::read_file(&single, &rom, 1024).unwrap(),
+            b"rom"
+        );
+        assert!(ArchiveTools::read_file(&single, &rom, 2).is_err());
+        assert!(ArchiveTools::read_file(&single, "other.sfc", 1024).is_err());
+
+        let out_dir = temp_dir.path().join("out");
+        let extracted = ArchiveTools::extract_file(&single, &rom, &out_dir, 1024).unwrap();
+        assert_eq!(extracted, out_dir.join("game.sfc"));
+        assert_eq!(fs::read(extracted).unwrap(), b"rom");
+
+        let multiple = create_zip(temp_dir.path(), &[("a.sfc", b"a"), ("b.smc", b"b")]);
+        assert!(ArchiveTools::find_single_rom(&multiple, &valid_extensions).is_err());
+    }
+}
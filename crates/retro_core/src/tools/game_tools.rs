@@ -1,7 +1,11 @@
-use crate::system::SysInfo;
+use crate::managers::save_state_manager::SaveSlot;
+use crate::system::{SubSystemInfo, SysInfo};
+use crate::tools::archive_tools::{ArchivePath, ArchiveTools};
+use crate::tools::save_state_tools::{SaveStateFile, SaveStateHeader, SaveStateTools};
 use crate::tools::validation::InputValidator;
-use generics::constants::SAVE_EXTENSION_FILE;
+use generics::constants::{SAVE_EXTENSION_FILE, SAVE_RAM_EXTENSION_FILE};
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 use libretro_sys::binding_libretro::{LibretroRaw, retro_game_info};
 use std::fs;
 use std::sync::Arc;
@@ -28,12 +32,14 @@ pub struct SaveInfo<'a> {
     pub save_dir: &'a str,
     pub library_name: &'a str,
     pub rom_name: &'a str,
-    pub slot: usize,
+    pub slot: SaveSlot,
     pub buffer_size: usize,
+    /// The core reported `CORE_VARIABLE_SIZE`, so `buffer_size` is only the current size
+    pub variable_size: bool,
 }
 
 /// Create a rom info wrapper
-struct RomInfo {
+pub(crate) struct RomInfo {
     data: Vec<u8>,
     meta: CString,
     path: CString,
@@ -61,22 +67,24 @@ impl<'a> SaveInfo<'a> {
         save_dir: &'a str,
         library_name: &'a str,
         rom_name: &'a str,
-        slot: usize,
+        slot: SaveSlot,
         buffer_size: usize,
     ) -> Result<Self, ErrorHandle> {
-        if slot > MAX_SAVE_SLOTS {
-            return Err(ErrorHandle::new(&format!(
-                "Invalid slot number: {}. Maximum allowed slot number is {}",
-                slot, MAX_SAVE_SLOTS
-            )));
+        if let SaveSlot::Index(slot) = slot {
+            if slot > MAX_SAVE_SLOTS {
+                return Err(ErrorHandle::new(&format!(
+                    "Invalid slot number: {}. Maximum allowed slot number is {}",
+                    slot, MAX_SAVE_SLOTS
+                )));
+            }
+
+            // Validate slot number
+            InputValidator::validate_save_slot(slot)?;
         }
 
         // Validate save directory
         InputValidator::validate_directory_path(save_dir)?;
 
-        // Validate slot number
-        InputValidator::validate_save_slot(slot)?;
-
         // Validate buffer size (max 100MB)
         InputValidator::validate_buffer_size(
             buffer_size,
@@ -106,6 +114,7 @@ impl<'a> SaveInfo<'a> {
             rom_name,
             slot,
             buffer_size,
+            variable_size: false,
         })
     }
 }
@@ -119,15 +128,10 @@ impl RomTools {
         libretro_raw: &Arc<LibretroRaw>,
         sys_info: &SysInfo,
         path: &str,
+        temps_dir: &str,
     ) -> Result<bool, ErrorHandle> {
-        // Validate and canonicalize the ROM path
-        let validated_path = InputValidator::validate_file_path(path)?;
-
-        // Validate ROM extension
-        InputValidator::validate_rom_extension(&validated_path, &sys_info.valid_extensions)?;
-
         // Create validated ROM info
-        let rom_info = Self::create_game_info(&validated_path, sys_info)?;
+        let rom_info = Self::create_validated_game_info(path, sys_info, temps_dir)?;
         let native_core_info = rom_info.to_core_native();
 
         // Load the game using the validated info
@@ -136,13 +140,160 @@ impl RomTools {
         if !loaded {
             return Err(ErrorHandle::new(&format!(
                 "Core rejected ROM file: {}",
-                validated_path.display()
+                path
+            )));
+        }
+
+        Ok(loaded)
+    }
+
+    /// Load several contents at once using one of the core subsystems.
+    /// An empty path leaves an optional content out.
+    pub fn try_load_game_special(
+        libretro_raw: &Arc<LibretroRaw>,
+        sys_info: &SysInfo,
+        subsystem: &SubSystemInfo,
+        paths: &[String],
+        temps_dir: &str,
+    ) -> Result<bool, ErrorHandle> {
+        let roms = subsystem.roms.read()?;
+
+        if paths.len() > roms.len() {
+            return Err(ErrorHandle::new(&format!(
+                "Subsystem '{}' accepts {} contents but {} were given",
+                subsystem.ident,
+                roms.len(),
+                paths.len()
+            )));
+        }
+
+        let mut rom_infos = Vec::with_capacity(roms.len());
+
+        for (index, rom) in roms.iter().enumerate() {
+            let path = paths.get(index).map(String::as_str).unwrap_or_default();
+
+            if path.is_empty() {
+                if *rom.required {
+                    return Err(ErrorHandle::new(&format!(
+                        "Subsystem '{}' requires the content '{}'",
+                        subsystem.ident, rom.desc
+                    )));
+                }
+
+                rom_infos.push(None);
+                continue;
+            }
+
+            // cada conteúdo do subsystem possui suas próprias regras de carregamento
+            let rom_sys_info = SysInfo {
+                valid_extensions: rom.valid_extensions.clone(),
+                need_full_path: rom.need_full_path.clone(),
+                block_extract: rom.block_extract.clone(),
+                ..sys_info.clone()
+            };
+
+            rom_infos.push(Some(Self::create_validated_game_info(
+                path,
+                &rom_sys_info,
+                temps_dir,
+            )?));
+        }
+
+        let native_infos: Vec<retro_game_info> = rom_infos
+            .iter()
+            .map(|rom_info| match rom_info {
+                Some(rom_info) => rom_info.to_core_native(),
+                None => retro_game_info {
+                    path: null(),
+                    data: null(),
+                    size: 0,
+                    meta: null(),
+                },
+            })
+            .collect();
+
+        let loaded = unsafe {
+            libretro_raw.retro_load_game_special(
+                *subsystem.id,
+                native_infos.as_ptr(),
+                native_infos.len(),
+            )
+        };
+
+        if !loaded {
+            return Err(ErrorHandle::new(&format!(
+                "Core rejected the contents of subsystem '{}'",
+                subsystem.ident
             )));
         }
 
         Ok(loaded)
     }
 
+    /// Validate the path and extension of a content file and read it as the core expects.
+    /// Roms inside `.zip` and `.7z` archives are decompressed into memory, or into
+    /// `temps_dir` when the core needs a real file.
+    pub(crate) fn create_validated_game_info(
+        path: &str,
+        sys_info: &SysInfo,
+        temps_dir: &str,
+    ) -> Result<RomInfo, ErrorHandle> {
+        let path = match ArchiveTools::parse_path(path) {
+            Some(archive) if !*sys_info.block_extract => {
+                return Self::create_archive_game_info(archive, sys_info, temps_dir);
+            }
+            // o núcleo lê o arquivo compactado sozinho, então ele é enviado sem alterações
+            Some(archive) => archive.archive.to_string_lossy().to_string(),
+            None => path.to_string(),
+        };
+
+        // Validate and canonicalize the ROM path
+        let validated_path = InputValidator::validate_file_path(&path)?;
+
+        // Validate ROM extension
+        InputValidator::validate_rom_extension(&validated_path, &sys_info.valid_extensions)?;
+
+        Self::create_game_info(&validated_path, sys_info)
+    }
+
+    fn create_archive_game_info(
+        archive: ArchivePath,
+        sys_info: &SysInfo,
+        temps_dir: &str,
+    ) -> Result<RomInfo, ErrorHandle> {
+        let archive_path = InputValidator::validate_file_path(&archive.archive.to_string_lossy())?;
+
+        let inner = match archive.inner {
+            Some(inner) => inner,
+            None => ArchiveTools::find_single_rom(&archive_path, &sys_info.valid_extensions)?,
+        };
+
+        InputValidator::validate_rom_extension(Path::new(&inner), &sys_info.valid_extensions)?;
+
+        let max_size = MAX_ROM_SIZE_MB * 1024 * 1024;
+
+        if *sys_info.need_full_path {
+            let archive_name = Self::get_rom_name(&archive_path)?;
+            let out_dir = Path::new(temps_dir).join(archive_name);
+
+            let extracted = ArchiveTools::extract_file(&archive_path, &inner, &out_dir, max_size)?;
+
+            return Self::create_game_info(&extracted, sys_info);
+        }
+
+        let data = ArchiveTools::read_file(&archive_path, &inner, max_size)?;
+
+        // o núcleo recebe o mesmo caminho usado pelo RetroArch para arquivos compactados
+        let path = format!("{}#{}", archive_path.display(), inner);
+
+        Ok(RomInfo {
+            size: data.len(),
+            data,
+            meta: CString::new("")?,
+            path: InputValidator::create_safe_c_string(&path, "Cannot send ROM path to core")?,
+        })
+    }
+
     /// Create retro_game_info with proper validation and memory management
     fn create_game_info(path: &Path, sys_info: &SysInfo) -> Result<RomInfo, ErrorHandle> {
         let mut buf: Vec<u8> = Vec::new();
@@ -165,9 +316,12 @@ impl RomTools {
             // Additional safety check before reading large files into memory
             if file_size > 100 * 1024 * 1024 {
                 // For files > 100MB, confirm this is intentional
-                println!(
-                    "Warning: Loading large ROM file ({} MB) into memory",
-                    file_size / (1024 * 1024)
+                logger::warn(
+                    LogTarget::Frontend,
+                    format!(
+                        "Loading large ROM file ({} MB) into memory",
+                        file_size / (1024 * 1024)
+                    ),
                 );
             }
 
@@ -202,8 +356,16 @@ impl RomTools {
         Ok(game_info)
     }
 
-    /// Safely extract ROM name with validation
+    /// Safely extract ROM name with validation.
+    /// For `archive.zip#inner.sfc` paths the name comes from the inner file
     pub fn get_rom_name(path: &Path) -> Result<String, ErrorHandle> {
+        let inner_path = path
+            .to_str()
+            .and_then(ArchiveTools::parse_path)
+            .and_then(|archive| archive.inner)
+            .map(PathBuf::from);
+        let path = inner_path.as_deref().unwrap_or(path);
+
         let file_name = path
             .file_name()
             .ok_or_else(|| ErrorHandle::new("Cannot extract filename from path"))?
@@ -238,8 +400,47 @@ impl RomTools {
         Ok(sanitized_name)
     }
 
+    /// CRC32 of the content. Roms inside archives are hashed by the inner file,
+    /// which is the value listed by the game databases
+    pub fn get_rom_crc32(path: &str, valid_extensions: &String) -> Result<u32, ErrorHandle> {
+        let max_size = MAX_ROM_SIZE_MB * 1024 * 1024;
+
+        if let Some(archive) = ArchiveTools::parse_path(path) {
+            let inner = match archive.inner {
+                Some(inner) => inner,
+                None => ArchiveTools::find_single_rom(&archive.archive, valid_extensions)?,
+            };
+
+            let data = ArchiveTools::read_file(&archive.archive, &inner, max_size)?;
+            return Ok(crc32fast::hash(&data));
+        }
+
+        let path = InputValidator::validate_file_path(path)?;
+        InputValidator::validate_file_size(&path, MAX_ROM_SIZE_MB)?;
+
+        let mut file = File::open(&path)
+            .map_err(|e| ErrorHandle::new(&format!("Failed to open ROM file: {}", e)))?;
+
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buf = vec![0u8; 64 * 1024];
+
+        loop {
+            let read = file
+                .read(&mut buf)
+                .map_err(|e| ErrorHandle::new(&format!("Failed to read ROM file: {}", e)))?;
+
+            if read == 0 {
+                break;
+            }
+
+            hasher.update(&buf[..read]);
+        }
+
+        Ok(hasher.finalize())
+    }
+
     /// Sanitize filename for safe filesystem operations
-    fn sanitize_filename(name: &str) -> String {
+    pub(crate) fn sanitize_filename(name: &str) -> String {
         name.chars()
             .map(|c| {
                 match c {
@@ -254,8 +455,14 @@ impl RomTools {
             .collect()
     }
 
-    /// Safely create save state with comprehensive validation
-    pub fn create_save_state<CA>(save_info: SaveInfo, get_data: CA) -> Result<PathBuf, ErrorHandle>
+    /// Safely create save state with comprehensive validation.
+    /// The serialized data is written after `header`, see [`SaveStateTools`]
+    pub fn create_save_state<CA>(
+        save_info: SaveInfo,
+        header: &SaveStateHeader,
+        thumbnail: Option<&[u8]>,
+        get_data: CA,
+    ) -> Result<PathBuf, ErrorHandle>
     where
         CA: FnOnce(&mut Vec<u8>, usize) -> bool,
     {
@@ -285,49 +492,64 @@ impl RomTools {
         // Get validated save path
         let save_path = Self::get_validated_save_path(&save_info)?;
 
+        let file = SaveStateTools::encode(header, &data, thumbnail)?;
+
+        Self::write_file_atomically(&save_path, &file)?;
+
+        Ok(save_path)
+    }
+
+    /// Write data to a temporary file first and then rename it over the destination,
+    /// so a crash in the middle of the write never leaves a truncated file behind
+    pub fn write_file_atomically(path: &Path, data: &[u8]) -> Result<(), ErrorHandle> {
         // Ensure parent directory exists
-        if let Some(parent) = save_path.parent() {
+        if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|e| {
                 ErrorHandle::new(&format!("Failed to create save directory: {}", e))
             })?;
         }
 
         // Write to temporary file first, then rename (atomic operation)
-        let temp_path = save_path.with_extension("tmp");
+        let temp_path = path.with_extension("tmp");
 
-        fs::write(&temp_path, &data).map_err(|e| {
-            ErrorHandle::new(&format!(
-                "Failed to write save state to temporary file: {}",
-                e
-            ))
-        })?;
+        fs::write(&temp_path, data)
+            .map_err(|e| ErrorHandle::new(&format!("Failed to write temporary file: {}", e)))?;
 
         // Atomic rename
-        fs::rename(&temp_path, &save_path).map_err(|e| {
+        fs::rename(&temp_path, path).map_err(|e| {
             // Clean up temp file if rename fails
             let _ = fs::remove_file(&temp_path);
-            ErrorHandle::new(&format!("Failed to finalize save state file: {}", e))
+            ErrorHandle::new(&format!("Failed to finalize file: {}", e))
         })?;
 
         // Verify the file was written correctly
-        let written_size = fs::metadata(&save_path)
+        let written_size = fs::metadata(path)
             .map_err(|e| ErrorHandle::new(&format!("Failed to verify saved file: {}", e)))?
             .len();
 
         if written_size != data.len() as u64 {
             return Err(ErrorHandle::new(&format!(
-                "Save state file size mismatch: expected {}, got {}",
+                "File size mismatch: expected {}, got {}",
                 data.len(),
                 written_size
             )));
         }
 
-        Ok(save_path)
+        Ok(())
     }
 
-    /// Safely load save state with validation
-    pub fn load_save_state<CA>(save_info: SaveInfo, send_to_core: CA) -> Result<(), ErrorHandle>
+    /// Safely load save state with validation.
+    /// Differences between the state header and `current` are logged as warnings, files
+    /// without a header are sent to the core as they are. `check` can still refuse the
+    /// state before it reaches the core. Returns the header of the file
+    pub fn load_save_state<CK, CA>(
+        save_info: SaveInfo,
+        current: &SaveStateHeader,
+        check: CK,
+        send_to_core: CA,
+    ) -> Result<Option<SaveStateHeader>, ErrorHandle>
     where
+        CK: FnOnce(Option<&SaveStateHeader>) -> Result<(), ErrorHandle>,
         CA: FnOnce(&mut Vec<u8>, usize) -> bool,
     {
         let save_path = Self::get_validated_save_path(&save_info)?;
@@ -343,21 +565,58 @@ impl RomTools {
         let file_size = InputValidator::validate_file_size(&save_path, MAX_SAVE_STATE_SIZE_MB)?;
 
         // Read and validate the save state data
-        let mut data = fs::read(&save_path)
+        let file = fs::read(&save_path)
             .map_err(|e| ErrorHandle::new(&format!("Failed to read save state file: {}", e)))?;
 
-        let actual_size = data.len();
-
         // Validate size consistency
-        if actual_size != file_size as usize {
+        if file.len() != file_size as usize {
             return Err(ErrorHandle::new(&format!(
                 "Save state file size inconsistency: metadata says {}, actual {}",
-                file_size, actual_size
+                file_size,
+                file.len()
             )));
         }
 
+        let max_state_size = if save_info.variable_size {
+            MAX_SAVE_STATE_SIZE_MB as usize * 1024 * 1024
+        } else {
+            save_info.buffer_size
+        };
+
+        let SaveStateFile {
+            header, mut data, ..
+        } = SaveStateTools::decode(&file, max_state_size)?;
+
+        check(header.as_ref())?;
+
+        match &header {
+            Some(header) => {
+                let mismatches = SaveStateTools::mismatches(header, current);
+
+                if !mismatches.is_empty() {
+                    logger::warn(
+                        LogTarget::Frontend,
+                        format!(
+                            "Save state {} may not be compatible: {}",
+                            save_path.display(),
+                            mismatches.join("; ")
+                        ),
+                    );
+                }
+            }
+            None => logger::debug(
+                LogTarget::Frontend,
+                format!(
+                    "Save state {} has no header, loading it as raw core data",
+                    save_path.display()
+                ),
+            ),
+        }
+
+        let actual_size = data.len();
+
         // Check if save state is compatible with current core buffer size
-        if actual_size > save_info.buffer_size {
+        if actual_size > save_info.buffer_size && !save_info.variable_size {
             return Err(ErrorHandle::new(&format!(
                 "Save state file size {} exceeds core buffer size {}. Incompatible save state.",
                 actual_size, save_info.buffer_size
@@ -365,7 +624,7 @@ impl RomTools {
         }
 
         // Pad with zeros if necessary (some cores might expect exact buffer size)
-        if actual_size < save_info.buffer_size {
+        if actual_size < save_info.buffer_size && !save_info.variable_size {
             data.resize(save_info.buffer_size, 0);
         }
 
@@ -376,16 +635,35 @@ impl RomTools {
             return Err(ErrorHandle::new("Core failed to deserialize save state"));
         }
 
-        Ok(())
+        Ok(header)
     }
 
     /// Get validated save path with proper directory structure
     fn get_validated_save_path(save_info: &SaveInfo) -> Result<PathBuf, ErrorHandle> {
-        let base_path = InputValidator::validate_directory_path(save_info.save_dir)?;
+        let mut path = Self::get_save_state_dir(
+            save_info.save_dir,
+            save_info.library_name,
+            save_info.rom_name,
+        )?;
+
+        // Create the filename with validation
+        let file_name = format!("{}.{}", save_info.slot.file_stem(), SAVE_EXTENSION_FILE);
+        path.push(file_name);
+
+        Ok(path)
+    }
+
+    /// Directory holding every save state slot of a rom
+    pub fn get_save_state_dir(
+        save_dir: &str,
+        library_name: &str,
+        rom_name: &str,
+    ) -> Result<PathBuf, ErrorHandle> {
+        let base_path = InputValidator::validate_directory_path(save_dir)?;
 
         // Create sanitized subdirectory structure
-        let library_subdir = Self::sanitize_filename(save_info.library_name);
-        let rom_subdir = Self::sanitize_filename(save_info.rom_name);
+        let library_subdir = Self::sanitize_filename(library_name);
+        let rom_subdir = Self::sanitize_filename(rom_name);
 
         let mut path = base_path;
         path.push(library_subdir);
@@ -397,9 +675,32 @@ impl RomTools {
             return Err(ErrorHandle::new("Save path is too long"));
         }
 
-        // Create the filename with validation
-        let file_name = format!("{:02}.{}", save_info.slot, SAVE_EXTENSION_FILE);
-        path.push(file_name);
+        Ok(path)
+    }
+
+    /// Get the battery-backed SRAM file path, kept next to the save state slots of the rom
+    pub fn get_save_ram_path(
+        save_dir: &str,
+        library_name: &str,
+        rom_name: &str,
+    ) -> Result<PathBuf, ErrorHandle> {
+        if library_name.is_empty() || rom_name.is_empty() {
+            return Err(ErrorHandle::new(
+                "Library name and ROM name cannot be empty",
+            ));
+        }
+
+        let mut path = InputValidator::validate_directory_path(save_dir)?;
+
+        let rom_name = Self::sanitize_filename(rom_name);
+        path.push(Self::sanitize_filename(library_name));
+        path.push(&rom_name);
+
+        if path.to_string_lossy().len() > 1024 {
+            return Err(ErrorHandle::new("Save path is too long"));
+        }
+
+        path.push(format!("{}.{}", rom_name, SAVE_RAM_EXTENSION_FILE));
 
         Ok(path)
     }
@@ -424,7 +725,7 @@ impl RomTools {
         // Check file permissions (readable)
         let permissions = metadata.permissions();
         if permissions.readonly() {
-            println!("Warning: ROM file is read-only");
+            logger::warn(LogTarget::Frontend, "ROM file is read-only");
         }
 
         // Basic size sanity check
@@ -453,15 +754,18 @@ mod tests {
 
     #[test]
     fn test_safe_save_info_creation() {
-        let result = SaveInfo::new("/tmp", "test_core", "test_rom", 5, 1024);
+        let result = SaveInfo::new("/tmp", "test_core", "test_rom", SaveSlot::Index(5), 1024);
         assert!(result.is_ok());
 
         // Test invalid slot
-        let result = SaveInfo::new("/tmp", "test_core", "test_rom", 100, 1024);
+        let result = SaveInfo::new("/tmp", "test_core", "test_rom", SaveSlot::Index(100), 1024);
         assert!(result.is_err());
 
+        let result = SaveInfo::new("/tmp", "test_core", "test_rom", SaveSlot::Auto, 1024);
+        assert!(result.is_ok());
+
         // Test empty names
-        let result = SaveInfo::new("/tmp", "", "test_rom", 5, 1024);
+        let result = SaveInfo::new("/tmp", "", "test_rom", SaveSlot::Index(5), 1024);
         assert!(result.is_err());
     }
 
@@ -484,6 +788,30 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_write_file_atomically() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("nested").join("game.srm");
+
+        RomTools::write_file_atomically(&file_path, b"first").unwrap();
+        RomTools::write_file_atomically(&file_path, b"second").unwrap();
+
+        assert_eq!(fs::read(&file_path).unwrap(), b"second");
+        assert!(!file_path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_get_save_ram_path() {
+        // the default temp dir prefix starts with a dot, which the path validation rejects
+        let temp_dir = tempfile::Builder::new().prefix("tinic").tempdir().unwrap();
+        let save_dir = temp_dir.path().to_str().unwrap();
+
+        let path = RomTools::get_save_ram_path(save_dir, "Mesen", "my:game").unwrap();
+        assert!(path.ends_with("Mesen/my_game/my_game.srm"));
+
+        assert!(RomTools::get_save_ram_path(save_dir, "", "game").is_err());
+    }
+
     #[test]
     fn test_validate_rom_integrity() {
         let temp_dir = TempDir::new().unwrap();
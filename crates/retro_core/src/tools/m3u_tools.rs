@@ -0,0 +1,106 @@
+use generics::error_handle::ErrorHandle;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A disc listed in a `.m3u` playlist
+#[derive(Debug, Clone, PartialEq)]
+pub struct M3uEntry {
+    pub path: PathBuf,
+    pub label: Option<String>,
+}
+
+/// Multi-disc playlist parsing
+pub struct M3uTools;
+
+impl M3uTools {
+    pub fn is_m3u(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("m3u"))
+    }
+
+    /// Read a playlist, resolving relative entries against the playlist directory
+    pub fn parse_file(path: &Path) -> Result<Vec<M3uEntry>, ErrorHandle> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            ErrorHandle::new(&format!(
+                "Failed to read m3u playlist {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let entries = Self::parse(&content, base_dir);
+
+        if entries.is_empty() {
+            return Err(ErrorHandle::new(&format!(
+                "The m3u playlist {} has no entries",
+                path.display()
+            )));
+        }
+
+        Ok(entries)
+    }
+
+    /// Parse the playlist content. Lines starting with `#` are comments and an entry
+    /// may carry a label using the RetroArch syntax `disc.cue|Label`
+    pub fn parse(content: &str, base_dir: &Path) -> Vec<M3uEntry> {
+        content
+            .lines()
+            .map(|line| line.trim().trim_start_matches('\u{feff}'))
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (file, label) = match line.split_once('|') {
+                    Some((file, label)) if !label.trim().is_empty() => {
+                        (file.trim(), Some(label.trim().to_string()))
+                    }
+                    Some((file, _)) => (file.trim(), None),
+                    None => (line, None),
+                };
+
+                let file = Path::new(file);
+                let path = if file.is_absolute() {
+                    file.to_path_buf()
+                } else {
+                    base_dir.join(file)
+                };
+
+                M3uEntry { path, label }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_m3u() {
+        let content = "#EXTM3U\n\nGame (Disc 1).cue\n# comment\nGame (Disc 2).cue|Disc Two\n/abs/Game (Disc 3).cue|\n";
+        let entries = M3uTools::parse(content, Path::new("/roms/psx"));
+
+        assert_eq!(
+            entries,
+            vec![
+                M3uEntry {
+                    path: PathBuf::from("/roms/psx/Game (Disc 1).cue"),
+                    label: None,
+                },
+                M3uEntry {
+                    path: PathBuf::from("/roms/psx/Game (Disc 2).cue"),
+                    label: Some("Disc Two".to_string()),
+                },
+                M3uEntry {
+                    path: PathBuf::from("/abs/Game (Disc 3).cue"),
+                    label: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_m3u() {
+        assert!(M3uTools::is_m3u(Path::new("game.M3U")));
+        assert!(!M3uTools::is_m3u(Path::new("game.cue")));
+    }
+}
@@ -1,4 +1,7 @@
+pub mod archive_tools;
 pub mod ffi_tools;
 pub mod game_tools;
+pub mod m3u_tools;
 pub mod mutex_tools;
+pub mod save_state_tools;
 pub mod validation;
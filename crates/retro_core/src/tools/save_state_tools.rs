@@ -0,0 +1,427 @@
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use generics::error_handle::ErrorHandle;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// First bytes of every save state written by Tinic. Files without it are raw
+/// `retro_serialize` buffers created by older versions
+pub const SAVE_STATE_MAGIC: &[u8; 8] = b"TINICSST";
+
+pub const SAVE_STATE_FORMAT_VERSION: u16 = 2;
+
+const FLAG_COMPRESSED: u8 = 1;
+const FLAG_THUMBNAIL: u8 = 1 << 1;
+const FLAG_BIG_ENDIAN: u8 = 1 << 2;
+
+/// Metadata stored in front of the serialized data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveStateHeader {
+    pub format_version: u16,
+    pub library_name: String,
+    pub library_version: String,
+    pub rom_crc32: u32,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    /// Seconds of play when the state was created
+    pub playtime: u64,
+    pub compressed: bool,
+    /// Size of the `retro_serialize` buffer, before compression
+    pub state_size: u64,
+    /// `<arch>-<os>` of the machine that created the state, empty for version 1 files
+    pub platform: String,
+    pub big_endian: bool,
+}
+
+/// Platform written in the header of new save states
+pub fn current_platform() -> String {
+    format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)
+}
+
+/// A decoded save state file. `header` is `None` for headerless files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveStateFile {
+    pub header: Option<SaveStateHeader>,
+    /// PNG image of the screen when the state was created
+    pub thumbnail: Option<Vec<u8>>,
+    pub data: Vec<u8>,
+}
+
+/// Reading and writing of the save state container.
+///
+/// Layout, integers in little endian:
+/// `magic | version u16 | flags u8 | timestamp u64 | playtime u64 | rom crc32 u32 |
+/// library_name | library_version | platform | state_size u64 | state crc32 u32 |
+/// thumbnail (u32 len + png) | payload (u64 len + data)`.
+/// Strings are stored as a u16 length followed by UTF-8 bytes. Version 1 has no platform
+pub struct SaveStateTools;
+
+impl SaveStateTools {
+    pub fn encode(
+        header: &SaveStateHeader,
+        data: &[u8],
+        thumbnail: Option<&[u8]>,
+    ) -> Result<Vec<u8>, ErrorHandle> {
+        let payload = if header.compressed {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
+            encoder
+                .write_all(data)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| ErrorHandle::new(&format!("Failed to compress save state: {}", e)))?
+        } else {
+            data.to_vec()
+        };
+
+        let thumbnail = thumbnail.unwrap_or_default();
+        let mut flags = 0;
+
+        if header.compressed {
+            flags |= FLAG_COMPRESSED;
+        }
+
+        if !thumbnail.is_empty() {
+            flags |= FLAG_THUMBNAIL;
+        }
+
+        if header.big_endian {
+            flags |= FLAG_BIG_ENDIAN;
+        }
+
+        let mut out = Vec::with_capacity(payload.len() + thumbnail.len() + 128);
+        out.extend_from_slice(SAVE_STATE_MAGIC);
+        out.extend_from_slice(&SAVE_STATE_FORMAT_VERSION.to_le_bytes());
+        out.push(flags);
+        out.extend_from_slice(&header.timestamp.to_le_bytes());
+        out.extend_from_slice(&header.playtime.to_le_bytes());
+        out.extend_from_slice(&header.rom_crc32.to_le_bytes());
+        Self::write_string(&mut out, &header.library_name)?;
+        Self::write_string(&mut out, &header.library_version)?;
+        Self::write_string(&mut out, &header.platform)?;
+        out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        out.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+
+        let thumbnail_len = u32::try_from(thumbnail.len())
+            .map_err(|_| ErrorHandle::new("Save state thumbnail is too large"))?;
+        out.extend_from_slice(&thumbnail_len.to_le_bytes());
+        out.extend_from_slice(thumbnail);
+
+        out.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        out.extend_from_slice(&payload);
+
+        Ok(out)
+    }
+
+    /// Decode a save state file, `max_state_size` limits the decompressed data
+    pub fn decode(file: &[u8], max_state_size: usize) -> Result<SaveStateFile, ErrorHandle> {
+        if !Self::has_header(file) {
+            return Ok(SaveStateFile {
+                header: None,
+                thumbnail: None,
+                data: file.to_vec(),
+            });
+        }
+
+        let mut reader = ByteReader::new(&file[SAVE_STATE_MAGIC.len()..]);
+        let (header, flags) = Self::read_header(&mut reader)?;
+        let expected_crc = reader.u32()?;
+
+        let thumbnail_len = reader.u32()? as usize;
+        let thumbnail = reader.bytes(thumbnail_len)?;
+        let thumbnail = (flags & FLAG_THUMBNAIL != 0).then(|| thumbnail.to_vec());
+
+        let payload_len = reader.u64()? as usize;
+        let payload = reader.bytes(payload_len)?;
+
+        if header.state_size as usize > max_state_size {
+            return Err(ErrorHandle::new(&format!(
+                "Save state size {} exceeds the maximum of {} bytes",
+                header.state_size, max_state_size
+            )));
+        }
+
+        let data = if header.compressed {
+            let mut data = Vec::with_capacity(header.state_size as usize);
+            ZlibDecoder::new(payload)
+                .take(header.state_size + 1)
+                .read_to_end(&mut data)
+                .map_err(|e| {
+                    ErrorHandle::new(&format!("Failed to decompress save state: {}", e))
+                })?;
+            data
+        } else {
+            payload.to_vec()
+        };
+
+        if data.len() as u64 != header.state_size || crc32fast::hash(&data) != expected_crc {
+            return Err(ErrorHandle::new("Save state data is corrupted"));
+        }
+
+        Ok(SaveStateFile {
+            header: Some(header),
+            thumbnail,
+            data,
+        })
+    }
+
+    /// Read only the header of a save state file, without loading the state data
+    pub fn read_file_header(path: &Path) -> Result<Option<SaveStateHeader>, ErrorHandle> {
+        let file = File::open(path)
+            .map_err(|e| ErrorHandle::new(&format!("Failed to open save state file: {}", e)))?;
+
+        // 64KB cobre o cabeçalho mesmo com nomes no tamanho máximo
+        let mut head = Vec::new();
+        file.take(SAVE_STATE_MAGIC.len() as u64 + 64 * 1024)
+            .read_to_end(&mut head)
+            .map_err(|e| ErrorHandle::new(&format!("Failed to read save state file: {}", e)))?;
+
+        if !Self::has_header(&head) {
+            return Ok(None);
+        }
+
+        let mut reader = ByteReader::new(&head[SAVE_STATE_MAGIC.len()..]);
+        Ok(Some(Self::read_header(&mut reader)?.0))
+    }
+
+    /// Describe every field of `found` that does not match the running core and rom.
+    /// A zero CRC means the rom could not be read and a zero size means the core size
+    /// varies, neither is compared
+    pub fn mismatches(found: &SaveStateHeader, current: &SaveStateHeader) -> Vec<String> {
+        let mut mismatches = Vec::new();
+
+        if found.library_name != current.library_name {
+            mismatches.push(format!(
+                "created by core '{}', running '{}'",
+                found.library_name, current.library_name
+            ));
+        }
+
+        if found.library_version != current.library_version {
+            mismatches.push(format!(
+                "created by core version '{}', running '{}'",
+                found.library_version, current.library_version
+            ));
+        }
+
+        if found.rom_crc32 != 0 && current.rom_crc32 != 0 && found.rom_crc32 != current.rom_crc32 {
+            mismatches.push(format!(
+                "created with rom CRC {:08X}, running {:08X}",
+                found.rom_crc32, current.rom_crc32
+            ));
+        }
+
+        if current.state_size != 0 && found.state_size != current.state_size {
+            mismatches.push(format!(
+                "state has {} bytes, core expects {}",
+                found.state_size, current.state_size
+            ));
+        }
+
+        mismatches
+    }
+
+    fn has_header(file: &[u8]) -> bool {
+        file.starts_with(SAVE_STATE_MAGIC)
+    }
+
+    fn read_header(reader: &mut ByteReader) -> Result<(SaveStateHeader, u8), ErrorHandle> {
+        let format_version = reader.u16()?;
+
+        if format_version > SAVE_STATE_FORMAT_VERSION {
+            return Err(ErrorHandle::new(&format!(
+                "Save state format version {} is newer than the supported version {}",
+                format_version, SAVE_STATE_FORMAT_VERSION
+            )));
+        }
+
+        let flags = reader.u8()?;
+
+        let header = SaveStateHeader {
+            format_version,
+            timestamp: reader.u64()?,
+            playtime: reader.u64()?,
+            rom_crc32: reader.u32()?,
+            library_name: reader.string()?,
+            library_version: reader.string()?,
+            platform: if format_version >= 2 {
+                reader.string()?
+            } else {
+                String::new()
+            },
+            state_size: reader.u64()?,
+            compressed: flags & FLAG_COMPRESSED != 0,
+            big_endian: flags & FLAG_BIG_ENDIAN != 0,
+        };
+
+        Ok((header, flags))
+    }
+
+    fn write_string(out: &mut Vec<u8>, value: &str) -> Result<(), ErrorHandle> {
+        let len = u16::try_from(value.len())
+            .map_err(|_| ErrorHandle::new("Save state header string is too long"))?;
+
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(value.as_bytes());
+
+        Ok(())
+    }
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], ErrorHandle> {
+        if len > self.data.len() {
+            return Err(ErrorHandle::new("Save state file is truncated"));
+        }
+
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ErrorHandle> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, ErrorHandle> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ErrorHandle> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, ErrorHandle> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, ErrorHandle> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn string(&mut self) -> Result<String, ErrorHandle> {
+        let len = self.u16()? as usize;
+
+        String::from_utf8(self.bytes(len)?.to_vec())
+            .map_err(|_| ErrorHandle::new("Save state header has an invalid string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(compressed: bool) -> SaveStateHeader {
+        SaveStateHeader {
+            format_version: SAVE_STATE_FORMAT_VERSION,
+            library_name: "Mesen".to_string(),
+            library_version: "0.9.9".to_string(),
+            rom_crc32: 0xDEADBEEF,
+            timestamp: 1_700_000_000,
+            playtime: 3600,
+            compressed,
+            state_size: 4096,
+            platform: current_platform(),
+            big_endian: false,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data: Vec<u8> = (0..4096).map(|i| (i % 7) as u8).collect();
+
+        for compressed in [false, true] {
+            let file = SaveStateTools::encode(&header(compressed), &data, Some(b"png")).unwrap();
+            let decoded = SaveStateTools::decode(&file, data.len()).unwrap();
+
+            assert_eq!(decoded.header, Some(header(compressed)));
+            assert_eq!(decoded.thumbnail.as_deref(), Some(b"png".as_slice()));
+            assert_eq!(decoded.data, data);
+
+            if compressed {
+                assert!(file.len() < data.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_version_1_files_have_no_platform() {
+        let data = [7u8; 4];
+        let mut file = SAVE_STATE_MAGIC.to_vec();
+        file.extend_from_slice(&1u16.to_le_bytes());
+        file.push(0);
+        file.extend_from_slice(&10u64.to_le_bytes());
+        file.extend_from_slice(&20u64.to_le_bytes());
+        file.extend_from_slice(&0xABCDu32.to_le_bytes());
+        for text in ["Mesen", "0.9.9"] {
+            file.extend_from_slice(&(text.len() as u16).to_le_bytes());
+            file.extend_from_slice(text.as_bytes());
+        }
+        file.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        file.extend_from_slice(&crc32fast::hash(&data).to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes());
+        file.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        file.extend_from_slice(&data);
+
+        let decoded = SaveStateTools::decode(&file, data.len()).unwrap();
+        let header = decoded.header.unwrap();
+
+        assert_eq!(header.format_version, 1);
+        assert_eq!(header.library_version, "0.9.9");
+        assert!(header.platform.is_empty());
+        assert_eq!(decoded.data, data);
+    }
+
+    #[test]
+    fn test_headerless_files_are_raw_states() {
+        let decoded = SaveStateTools::decode(&[1, 2, 3], 3).unwrap();
+
+        assert_eq!(decoded.header, None);
+        assert_eq!(decoded.data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_corrupted_and_oversized_states_are_rejected() {
+        let data = vec![9u8; 4096];
+        let mut file = SaveStateTools::encode(&header(false), &data, None).unwrap();
+
+        assert!(SaveStateTools::decode(&file, 1024).is_err());
+
+        let last = file.len() - 1;
+        file[last] = 0;
+        assert!(SaveStateTools::decode(&file, data.len()).is_err());
+
+        file.truncate(40);
+        assert!(SaveStateTools::decode(&file, data.len()).is_err());
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let current = header(false);
+        assert!(SaveStateTools::mismatches(&current, &current).is_empty());
+
+        let found = SaveStateHeader {
+            library_version: "1.0".to_string(),
+            rom_crc32: 0x1234,
+            ..current.clone()
+        };
+        assert_eq!(SaveStateTools::mismatches(&found, &current).len(), 2);
+
+        let unknown_rom = SaveStateHeader {
+            rom_crc32: 0,
+            ..current.clone()
+        };
+        assert!(SaveStateTools::mismatches(&unknown_rom, &current).is_empty());
+    }
+}
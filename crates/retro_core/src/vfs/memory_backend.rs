@@ -0,0 +1,237 @@
+use crate::vfs::{VfsBackend, VfsDirEntry, VfsFile, VfsOpenMode, VfsStat};
+use generics::error_handle::ErrorHandle;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::SeekFrom;
+use std::sync::{Arc, RwLock};
+
+type SharedBuffer = Arc<RwLock<Vec<u8>>>;
+
+/// Serves in-memory buffers, such as files extracted from an archive, under a path prefix.
+///
+/// Directories are implicit: a directory exists while some file lives inside it.
+pub struct MemoryVfsBackend {
+    prefix: String,
+    files: RwLock<BTreeMap<String, SharedBuffer>>,
+}
+
+impl MemoryVfsBackend {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            files: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Add a file at `<prefix>/<name>` and return the path the core must use to open it
+    pub fn insert(&self, name: &str, data: Vec<u8>) -> Result<String, ErrorHandle> {
+        let path = format!("{}/{}", self.prefix, name.trim_start_matches('/'));
+
+        self.files
+            .write()?
+            .insert(path.clone(), Arc::new(RwLock::new(data)));
+
+        Ok(path)
+    }
+
+    pub fn clear(&self) -> Result<(), ErrorHandle> {
+        self.files.write()?.clear();
+        Ok(())
+    }
+
+    fn normalize(path: &str) -> &str {
+        path.trim_end_matches('/')
+    }
+
+    fn not_found(path: &str) -> ErrorHandle {
+        ErrorHandle::new(&format!("File not found in memory VFS: {}", path))
+    }
+}
+
+impl VfsBackend for MemoryVfsBackend {
+    fn handles(&self, path: &str) -> bool {
+        path == self.prefix || path.starts_with(&format!("{}/", self.prefix))
+    }
+
+    fn open(&self, path: &str, mode: VfsOpenMode) -> Result<Box<dyn VfsFile>, ErrorHandle> {
+        let path = Self::normalize(path);
+        let mut files = self.files.write()?;
+
+        let buffer = match files.get(path) {
+            Some(buffer) => buffer.clone(),
+            None if mode.write && !mode.update_existing => {
+                let buffer: SharedBuffer = Arc::default();
+                files.insert(path.to_string(), buffer.clone());
+                buffer
+            }
+            None => return Err(Self::not_found(path)),
+        };
+
+        if mode.write && !mode.update_existing {
+            buffer.write()?.clear();
+        }
+
+        Ok(Box::new(MemoryFile {
+            buffer,
+            position: 0,
+            writable: mode.write,
+        }))
+    }
+
+    fn remove(&self, path: &str) -> Result<(), ErrorHandle> {
+        let path = Self::normalize(path);
+
+        self.files
+            .write()?
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| Self::not_found(path))
+    }
+
+    fn rename(&self, old_path: &str, new_path: &str) -> Result<(), ErrorHandle> {
+        let (old_path, new_path) = (Self::normalize(old_path), Self::normalize(new_path));
+
+        if !self.handles(new_path) {
+            return Err(ErrorHandle::new("Cannot move a file out of the memory VFS"));
+        }
+
+        let mut files = self.files.write()?;
+        let buffer = files
+            .remove(old_path)
+            .ok_or_else(|| Self::not_found(old_path))?;
+        files.insert(new_path.to_string(), buffer);
+
+        Ok(())
+    }
+
+    fn stat(&self, path: &str) -> Option<VfsStat> {
+        let path = Self::normalize(path);
+        let files = self.files.read().ok()?;
+
+        if let Some(buffer) = files.get(path) {
+            return Some(VfsStat {
+                size: buffer.read().ok()?.len() as u64,
+                ..VfsStat::default()
+            });
+        }
+
+        let dir_prefix = format!("{}/", path);
+
+        (path == self.prefix || files.keys().any(|key| key.starts_with(&dir_prefix))).then_some(
+            VfsStat {
+                is_directory: true,
+                ..VfsStat::default()
+            },
+        )
+    }
+
+    fn mkdir(&self, _path: &str) -> Result<(), ErrorHandle> {
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &str, include_hidden: bool) -> Result<Vec<VfsDirEntry>, ErrorHandle> {
+        let dir_prefix = format!("{}/", Self::normalize(path));
+        let files = self.files.read()?;
+
+        let mut seen = BTreeSet::new();
+        let mut entries = Vec::new();
+
+        for key in files.keys() {
+            let relative = match key.strip_prefix(&dir_prefix) {
+                Some(relative) => relative,
+                None => continue,
+            };
+
+            let (name, is_directory) = match relative.split_once('/') {
+                Some((dir, _)) => (dir, true),
+                None => (relative, false),
+            };
+
+            if (!include_hidden && name.starts_with('.')) || !seen.insert(name) {
+                continue;
+            }
+
+            entries.push(VfsDirEntry {
+                name: name.to_string(),
+                is_directory,
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+struct MemoryFile {
+    buffer: SharedBuffer,
+    position: u64,
+    writable: bool,
+}
+
+impl VfsFile for MemoryFile {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ErrorHandle> {
+        let data = self.buffer.read()?;
+        let start = (self.position as usize).min(data.len());
+        let count = buf.len().min(data.len() - start);
+
+        buf[..count].copy_from_slice(&data[start..start + count]);
+        self.position += count as u64;
+
+        Ok(count)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorHandle> {
+        if !self.writable {
+            return Err(ErrorHandle::new("File was opened as read only"));
+        }
+
+        let mut data = self.buffer.write()?;
+        let start = self.position as usize;
+        let end = start + buf.len();
+
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+
+        data[start..end].copy_from_slice(buf);
+        self.position = end as u64;
+
+        Ok(buf.len())
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, ErrorHandle> {
+        let size = self.buffer.read()?.len() as i64;
+
+        let position = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+            SeekFrom::End(offset) => size + offset,
+        };
+
+        if position < 0 {
+            return Err(ErrorHandle::new("Invalid seek to a negative position"));
+        }
+
+        self.position = position as u64;
+        Ok(self.position)
+    }
+
+    fn size(&mut self) -> Result<u64, ErrorHandle> {
+        Ok(self.buffer.read()?.len() as u64)
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<(), ErrorHandle> {
+        if !self.writable {
+            return Err(ErrorHandle::new("File was opened as read only"));
+        }
+
+        self.buffer.write()?.resize(len as usize, 0);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorHandle> {
+        Ok(())
+    }
+}
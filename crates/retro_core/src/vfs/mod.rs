@@ -0,0 +1,100 @@
+mod memory_backend;
+mod std_fs_backend;
+pub(crate) mod vfs_interface;
+
+pub use memory_backend::MemoryVfsBackend;
+pub use std_fs_backend::StdFsBackend;
+
+use generics::error_handle::ErrorHandle;
+use libretro_sys::binding_libretro::{
+    RETRO_VFS_FILE_ACCESS_READ, RETRO_VFS_FILE_ACCESS_UPDATE_EXISTING, RETRO_VFS_FILE_ACCESS_WRITE,
+};
+use std::ffi::c_uint;
+use std::io::SeekFrom;
+use std::sync::{Arc, LazyLock, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VfsOpenMode {
+    pub read: bool,
+    pub write: bool,
+    /// Keep the current content when opening for write instead of truncating it
+    pub update_existing: bool,
+}
+
+impl VfsOpenMode {
+    pub fn from_raw(mode: c_uint) -> Self {
+        Self {
+            read: mode & RETRO_VFS_FILE_ACCESS_READ != 0,
+            write: mode & RETRO_VFS_FILE_ACCESS_WRITE != 0,
+            update_existing: mode & RETRO_VFS_FILE_ACCESS_UPDATE_EXISTING != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VfsStat {
+    pub is_directory: bool,
+    pub is_character_special: bool,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VfsDirEntry {
+    pub name: String,
+    pub is_directory: bool,
+}
+
+/// An open file served through the libretro VFS interface
+pub trait VfsFile: Send {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ErrorHandle>;
+    fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorHandle>;
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, ErrorHandle>;
+    fn size(&mut self) -> Result<u64, ErrorHandle>;
+    fn truncate(&mut self, len: u64) -> Result<(), ErrorHandle>;
+    fn flush(&mut self) -> Result<(), ErrorHandle>;
+}
+
+/// Source of the files and directories the cores see through the VFS interface.
+///
+/// Every path asked by a core goes to the last registered backend that `handles` it,
+/// and falls back to [`StdFsBackend`] when none does.
+pub trait VfsBackend: Send + Sync {
+    fn handles(&self, path: &str) -> bool;
+    fn open(&self, path: &str, mode: VfsOpenMode) -> Result<Box<dyn VfsFile>, ErrorHandle>;
+    fn remove(&self, path: &str) -> Result<(), ErrorHandle>;
+    fn rename(&self, old_path: &str, new_path: &str) -> Result<(), ErrorHandle>;
+    /// Returns `None` when the path does not exist
+    fn stat(&self, path: &str) -> Option<VfsStat>;
+    fn mkdir(&self, path: &str) -> Result<(), ErrorHandle>;
+    fn read_dir(&self, path: &str, include_hidden: bool) -> Result<Vec<VfsDirEntry>, ErrorHandle>;
+}
+
+static BACKENDS: RwLock<Vec<Arc<dyn VfsBackend>>> = RwLock::new(Vec::new());
+static STD_FS_BACKEND: LazyLock<Arc<dyn VfsBackend>> = LazyLock::new(|| Arc::new(StdFsBackend));
+
+pub fn register_vfs_backend(backend: Arc<dyn VfsBackend>) -> Result<(), ErrorHandle> {
+    BACKENDS.write()?.push(backend);
+    Ok(())
+}
+
+pub fn unregister_vfs_backend(backend: &Arc<dyn VfsBackend>) -> Result<(), ErrorHandle> {
+    BACKENDS
+        .write()?
+        .retain(|registered| !Arc::ptr_eq(registered, backend));
+
+    Ok(())
+}
+
+pub(crate) fn backend_for(path: &str) -> Arc<dyn VfsBackend> {
+    BACKENDS
+        .read()
+        .ok()
+        .and_then(|backends| {
+            backends
+                .iter()
+                .rev()
+                .find(|backend| backend.handles(path))
+                .cloned()
+        })
+        .unwrap_or_else(|| STD_FS_BACKEND.clone())
+}
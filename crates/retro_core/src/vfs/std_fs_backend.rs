@@ -0,0 +1,107 @@
+use crate::vfs::{VfsBackend, VfsDirEntry, VfsFile, VfsOpenMode, VfsStat};
+use generics::error_handle::ErrorHandle;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/// Serves the VFS straight from the host file system
+pub struct StdFsBackend;
+
+impl VfsFile for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ErrorHandle> {
+        Ok(Read::read(self, buf)?)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorHandle> {
+        Ok(Write::write(self, buf)?)
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, ErrorHandle> {
+        Ok(Seek::seek(self, pos)?)
+    }
+
+    fn size(&mut self) -> Result<u64, ErrorHandle> {
+        Ok(self.metadata()?.len())
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<(), ErrorHandle> {
+        Ok(self.set_len(len)?)
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorHandle> {
+        Ok(Write::flush(self)?)
+    }
+}
+
+impl VfsBackend for StdFsBackend {
+    fn handles(&self, _path: &str) -> bool {
+        true
+    }
+
+    fn open(&self, path: &str, mode: VfsOpenMode) -> Result<Box<dyn VfsFile>, ErrorHandle> {
+        let mut options = OpenOptions::new();
+        options.read(mode.read).write(mode.write);
+
+        // mesmo comportamento do fopen: "wb" e "w+b" criam o arquivo do zero, "r+b" mantém o conteúdo
+        if mode.write && !mode.update_existing {
+            options.create(true).truncate(true);
+        }
+
+        Ok(Box::new(options.open(path)?))
+    }
+
+    fn remove(&self, path: &str) -> Result<(), ErrorHandle> {
+        if fs::metadata(path)?.is_dir() {
+            fs::remove_dir(path)?;
+        } else {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    fn rename(&self, old_path: &str, new_path: &str) -> Result<(), ErrorHandle> {
+        Ok(fs::rename(old_path, new_path)?)
+    }
+
+    fn stat(&self, path: &str) -> Option<VfsStat> {
+        let metadata = fs::metadata(path).ok()?;
+
+        #[cfg(unix)]
+        let is_character_special = {
+            use std::os::unix::fs::FileTypeExt;
+            metadata.file_type().is_char_device()
+        };
+        #[cfg(not(unix))]
+        let is_character_special = false;
+
+        Some(VfsStat {
+            is_directory: metadata.is_dir(),
+            is_character_special,
+            size: metadata.len(),
+        })
+    }
+
+    fn mkdir(&self, path: &str) -> Result<(), ErrorHandle> {
+        Ok(fs::create_dir(path)?)
+    }
+
+    fn read_dir(&self, path: &str, include_hidden: bool) -> Result<Vec<VfsDirEntry>, ErrorHandle> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if !include_hidden && name.starts_with('.') {
+                continue;
+            }
+
+            entries.push(VfsDirEntry {
+                name,
+                is_directory: entry.file_type()?.is_dir(),
+            });
+        }
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,504 @@
+use crate::vfs::{VfsDirEntry, VfsFile, VfsOpenMode, backend_for};
+use generics::logger::{self, LogTarget};
+use libretro_sys::binding_libretro::{
+    RETRO_VFS_SEEK_POSITION_CURRENT, RETRO_VFS_SEEK_POSITION_END, RETRO_VFS_SEEK_POSITION_START,
+    RETRO_VFS_STAT_IS_CHARACTER_SPECIAL, RETRO_VFS_STAT_IS_DIRECTORY, RETRO_VFS_STAT_IS_VALID,
+    retro_vfs_dir_handle, retro_vfs_file_handle, retro_vfs_interface, retro_vfs_interface_info,
+};
+use std::ffi::{CStr, CString, c_char, c_int, c_uint, c_void};
+use std::io::SeekFrom;
+use std::ptr::{self, null, null_mut};
+use std::slice;
+
+//
+// ─────────────────────────────────────────────────────────
+// INTERFACE
+// ─────────────────────────────────────────────────────────
+//
+
+const VFS_INTERFACE_VERSION: u32 = 3;
+
+static VFS_INTERFACE: retro_vfs_interface = retro_vfs_interface {
+    get_path: Some(vfs_get_path),
+    open: Some(vfs_open),
+    close: Some(vfs_close),
+    size: Some(vfs_size),
+    tell: Some(vfs_tell),
+    seek: Some(vfs_seek),
+    read: Some(vfs_read),
+    write: Some(vfs_write),
+    flush: Some(vfs_flush),
+    remove: Some(vfs_remove),
+    rename: Some(vfs_rename),
+    truncate: Some(vfs_truncate),
+    stat: Some(vfs_stat),
+    mkdir: Some(vfs_mkdir),
+    opendir: Some(vfs_opendir),
+    readdir: Some(vfs_readdir),
+    dirent_get_name: Some(vfs_dirent_get_name),
+    dirent_is_dir: Some(vfs_dirent_is_dir),
+    closedir: Some(vfs_closedir),
+};
+
+/// Give the VFS table to the core. Returns false when the core needs a newer version
+pub(crate) fn configure_vfs_interface(info: &mut retro_vfs_interface_info) -> bool {
+    if info.required_interface_version > VFS_INTERFACE_VERSION {
+        return false;
+    }
+
+    info.required_interface_version = VFS_INTERFACE_VERSION;
+    info.iface = ptr::addr_of!(VFS_INTERFACE) as *mut retro_vfs_interface;
+
+    true
+}
+
+struct FileHandle {
+    path: CString,
+    file: Box<dyn VfsFile>,
+}
+
+struct DirHandle {
+    entries: Vec<VfsDirEntry>,
+    // readdir precisa ser chamado antes da primeira entrada, por isso começa em None
+    current: Option<usize>,
+    current_name: CString,
+}
+
+fn path_from_ptr(path: *const c_char) -> Option<String> {
+    if path.is_null() {
+        return None;
+    }
+
+    Some(
+        unsafe { CStr::from_ptr(path) }
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+unsafe fn file_from_ptr<'a>(stream: *mut retro_vfs_file_handle) -> Option<&'a mut FileHandle> {
+    unsafe { (stream as *mut FileHandle).as_mut() }
+}
+
+unsafe fn dir_from_ptr<'a>(dirstream: *mut retro_vfs_dir_handle) -> Option<&'a mut DirHandle> {
+    unsafe { (dirstream as *mut DirHandle).as_mut() }
+}
+
+//
+// ─────────────────────────────────────────────────────────
+// FILES
+// ─────────────────────────────────────────────────────────
+//
+
+unsafe extern "C" fn vfs_get_path(stream: *mut retro_vfs_file_handle) -> *const c_char {
+    match unsafe { file_from_ptr(stream) } {
+        Some(handle) => handle.path.as_ptr(),
+        None => null(),
+    }
+}
+
+unsafe extern "C" fn vfs_open(
+    path: *const c_char,
+    mode: c_uint,
+    _hints: c_uint,
+) -> *mut retro_vfs_file_handle {
+    let path = match path_from_ptr(path) {
+        Some(path) => path,
+        None => return null_mut(),
+    };
+
+    let file = match backend_for(&path).open(&path, VfsOpenMode::from_raw(mode)) {
+        Ok(file) => file,
+        Err(e) => {
+            logger::debug(LogTarget::Env, format!("[VFS]: {e:?}"));
+
+            return null_mut();
+        }
+    };
+
+    let path = match CString::new(path) {
+        Ok(path) => path,
+        Err(_) => return null_mut(),
+    };
+
+    Box::into_raw(Box::new(FileHandle { path, file })) as *mut retro_vfs_file_handle
+}
+
+unsafe extern "C" fn vfs_close(stream: *mut retro_vfs_file_handle) -> c_int {
+    if stream.is_null() {
+        return -1;
+    }
+
+    let mut handle = unsafe { Box::from_raw(stream as *mut FileHandle) };
+
+    match handle.file.flush() {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_size(stream: *mut retro_vfs_file_handle) -> i64 {
+    unsafe { file_from_ptr(stream) }
+        .and_then(|handle| handle.file.size().ok())
+        .map_or(-1, |size| size as i64)
+}
+
+unsafe extern "C" fn vfs_truncate(stream: *mut retro_vfs_file_handle, length: i64) -> i64 {
+    if length < 0 {
+        return -1;
+    }
+
+    unsafe { file_from_ptr(stream) }
+        .and_then(|handle| handle.file.truncate(length as u64).ok())
+        .map_or(-1, |_| 0)
+}
+
+unsafe extern "C" fn vfs_tell(stream: *mut retro_vfs_file_handle) -> i64 {
+    unsafe { file_from_ptr(stream) }
+        .and_then(|handle| handle.file.seek(SeekFrom::Current(0)).ok())
+        .map_or(-1, |position| position as i64)
+}
+
+unsafe extern "C" fn vfs_seek(
+    stream: *mut retro_vfs_file_handle,
+    offset: i64,
+    seek_position: c_int,
+) -> i64 {
+    let pos = match seek_position as u32 {
+        RETRO_VFS_SEEK_POSITION_START if offset >= 0 => SeekFrom::Start(offset as u64),
+        RETRO_VFS_SEEK_POSITION_CURRENT => SeekFrom::Current(offset),
+        RETRO_VFS_SEEK_POSITION_END => SeekFrom::End(offset),
+        _ => return -1,
+    };
+
+    unsafe { file_from_ptr(stream) }
+        .and_then(|handle| handle.file.seek(pos).ok())
+        .map_or(-1, |position| position as i64)
+}
+
+unsafe extern "C" fn vfs_read(stream: *mut retro_vfs_file_handle, s: *mut c_void, len: u64) -> i64 {
+    let handle = match unsafe { file_from_ptr(stream) } {
+        Some(handle) if !s.is_null() => handle,
+        _ => return -1,
+    };
+
+    let buf = unsafe { slice::from_raw_parts_mut(s as *mut u8, len as usize) };
+    let mut total = 0;
+
+    // assim como o fread, só devolve menos bytes que o pedido no fim do arquivo
+    while total < buf.len() {
+        match handle.file.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(count) => total += count,
+            Err(_) => return -1,
+        }
+    }
+
+    total as i64
+}
+
+unsafe extern "C" fn vfs_write(
+    stream: *mut retro_vfs_file_handle,
+    s: *const c_void,
+    len: u64,
+) -> i64 {
+    let handle = match unsafe { file_from_ptr(stream) } {
+        Some(handle) if !s.is_null() => handle,
+        _ => return -1,
+    };
+
+    let buf = unsafe { slice::from_raw_parts(s as *const u8, len as usize) };
+    let mut total = 0;
+
+    while total < buf.len() {
+        match handle.file.write(&buf[total..]) {
+            Ok(0) => break,
+            Ok(count) => total += count,
+            Err(_) => return -1,
+        }
+    }
+
+    total as i64
+}
+
+unsafe extern "C" fn vfs_flush(stream: *mut retro_vfs_file_handle) -> c_int {
+    unsafe { file_from_ptr(stream) }
+        .and_then(|handle| handle.file.flush().ok())
+        .map_or(-1, |_| 0)
+}
+
+unsafe extern "C" fn vfs_remove(path: *const c_char) -> c_int {
+    path_from_ptr(path)
+        .and_then(|path| backend_for(&path).remove(&path).ok())
+        .map_or(-1, |_| 0)
+}
+
+unsafe extern "C" fn vfs_rename(old_path: *const c_char, new_path: *const c_char) -> c_int {
+    match (path_from_ptr(old_path), path_from_ptr(new_path)) {
+        (Some(old_path), Some(new_path)) => backend_for(&old_path)
+            .rename(&old_path, &new_path)
+            .map_or(-1, |_| 0),
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_stat(path: *const c_char, size: *mut i32) -> c_int {
+    let stat = match path_from_ptr(path).and_then(|path| backend_for(&path).stat(&path)) {
+        Some(stat) => stat,
+        None => return 0,
+    };
+
+    if !size.is_null() {
+        unsafe {
+            *size = stat.size.min(i32::MAX as u64) as i32;
+        }
+    }
+
+    let mut flags = RETRO_VFS_STAT_IS_VALID;
+
+    if stat.is_directory {
+        flags |= RETRO_VFS_STAT_IS_DIRECTORY;
+    }
+
+    if stat.is_character_special {
+        flags |= RETRO_VFS_STAT_IS_CHARACTER_SPECIAL;
+    }
+
+    flags as c_int
+}
+
+//
+// ─────────────────────────────────────────────────────────
+// DIRECTORIES
+// ─────────────────────────────────────────────────────────
+//
+
+unsafe extern "C" fn vfs_mkdir(dir: *const c_char) -> c_int {
+    let dir = match path_from_ptr(dir) {
+        Some(dir) => dir,
+        None => return -1,
+    };
+
+    let backend = backend_for(&dir);
+
+    // o libretro espera -2 quando o diretório já existe
+    if backend.stat(&dir).is_some() {
+        return -2;
+    }
+
+    backend.mkdir(&dir).map_or(-1, |_| 0)
+}
+
+unsafe extern "C" fn vfs_opendir(
+    dir: *const c_char,
+    include_hidden: bool,
+) -> *mut retro_vfs_dir_handle {
+    let entries = match path_from_ptr(dir)
+        .and_then(|dir| backend_for(&dir).read_dir(&dir, include_hidden).ok())
+    {
+        Some(entries) => entries,
+        None => return null_mut(),
+    };
+
+    Box::into_raw(Box::new(DirHandle {
+        entries,
+        current: None,
+        current_name: CString::default(),
+    })) as *mut retro_vfs_dir_handle
+}
+
+unsafe extern "C" fn vfs_readdir(dirstream: *mut retro_vfs_dir_handle) -> bool {
+    let handle = match unsafe { dir_from_ptr(dirstream) } {
+        Some(handle) => handle,
+        None => return false,
+    };
+
+    let next = handle.current.map_or(0, |index| index + 1);
+
+    match handle.entries.get(next) {
+        Some(entry) => {
+            handle.current = Some(next);
+            handle.current_name = CString::new(entry.name.as_str()).unwrap_or_default();
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe extern "C" fn vfs_dirent_get_name(dirstream: *mut retro_vfs_dir_handle) -> *const c_char {
+    match unsafe { dir_from_ptr(dirstream) } {
+        Some(handle) if handle.current.is_some() => handle.current_name.as_ptr(),
+        _ => null(),
+    }
+}
+
+unsafe extern "C" fn vfs_dirent_is_dir(dirstream: *mut retro_vfs_dir_handle) -> bool {
+    unsafe { dir_from_ptr(dirstream) }
+        .and_then(|handle| handle.entries.get(handle.current?))
+        .is_some_and(|entry| entry.is_directory)
+}
+
+unsafe extern "C" fn vfs_closedir(dirstream: *mut retro_vfs_dir_handle) -> c_int {
+    if dirstream.is_null() {
+        return -1;
+    }
+
+    drop(unsafe { Box::from_raw(dirstream as *mut DirHandle) });
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vfs::{MemoryVfsBackend, VfsBackend, register_vfs_backend, unregister_vfs_backend};
+    use libretro_sys::binding_libretro::{
+        RETRO_VFS_FILE_ACCESS_READ, RETRO_VFS_FILE_ACCESS_READ_WRITE,
+        RETRO_VFS_FILE_ACCESS_UPDATE_EXISTING, RETRO_VFS_FILE_ACCESS_WRITE,
+    };
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn c_path(path: &str) -> CString {
+        CString::new(path).unwrap()
+    }
+
+    unsafe fn read_to_vec(stream: *mut retro_vfs_file_handle) -> Vec<u8> {
+        unsafe {
+            let size = vfs_size(stream);
+            let mut data = vec![0u8; size as usize];
+
+            vfs_seek(stream, 0, RETRO_VFS_SEEK_POSITION_START as c_int);
+            assert_eq!(
+                vfs_read(stream, data.as_mut_ptr() as *mut c_void, size as u64),
+                size
+            );
+
+            data
+        }
+    }
+
+    unsafe fn list_dir(dir: &CString) -> Vec<(String, bool)> {
+        unsafe {
+            let dir_handle = vfs_opendir(dir.as_ptr(), false);
+            assert!(!dir_handle.is_null());
+
+            let mut entries = Vec::new();
+            while vfs_readdir(dir_handle) {
+                let name = CStr::from_ptr(vfs_dirent_get_name(dir_handle));
+                entries.push((
+                    name.to_string_lossy().to_string(),
+                    vfs_dirent_is_dir(dir_handle),
+                ));
+            }
+            assert_eq!(vfs_closedir(dir_handle), 0);
+
+            entries.sort();
+            entries
+        }
+    }
+
+    #[test]
+    fn test_configure_vfs_interface() {
+        let mut info = retro_vfs_interface_info {
+            required_interface_version: 3,
+            iface: null_mut(),
+        };
+        assert!(configure_vfs_interface(&mut info));
+        assert!(!info.iface.is_null());
+
+        info.required_interface_version = 4;
+        assert!(!configure_vfs_interface(&mut info));
+    }
+
+    #[test]
+    fn test_std_fs_files_and_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().to_str().unwrap();
+        let file = c_path(&format!("{base}/data/game.sav"));
+        let dir = c_path(&format!("{base}/data"));
+
+        unsafe {
+            assert_eq!(vfs_mkdir(dir.as_ptr()), 0);
+            assert_eq!(vfs_mkdir(dir.as_ptr()), -2);
+
+            assert!(vfs_open(file.as_ptr(), RETRO_VFS_FILE_ACCESS_READ, 0).is_null());
+
+            let stream = vfs_open(file.as_ptr(), RETRO_VFS_FILE_ACCESS_READ_WRITE, 0);
+            assert!(!stream.is_null());
+            assert_eq!(CStr::from_ptr(vfs_get_path(stream)), file.as_c_str());
+            assert_eq!(vfs_write(stream, b"tinic".as_ptr() as *const c_void, 5), 5);
+            assert_eq!(vfs_tell(stream), 5);
+            assert_eq!(read_to_vec(stream), b"tinic");
+            assert_eq!(vfs_close(stream), 0);
+
+            // sem truncar o conteúdo atual
+            let mode = RETRO_VFS_FILE_ACCESS_WRITE | RETRO_VFS_FILE_ACCESS_UPDATE_EXISTING;
+            let stream = vfs_open(file.as_ptr(), mode, 0);
+            assert_eq!(
+                vfs_seek(stream, -1, RETRO_VFS_SEEK_POSITION_END as c_int),
+                4
+            );
+            assert_eq!(vfs_write(stream, b"!".as_ptr() as *const c_void, 1), 1);
+            assert_eq!(vfs_truncate(stream, 3), 0);
+            assert_eq!(vfs_close(stream), 0);
+
+            let mut size = 0;
+            assert_eq!(
+                vfs_stat(file.as_ptr(), &mut size),
+                RETRO_VFS_STAT_IS_VALID as c_int
+            );
+            assert_eq!(size, 3);
+            assert_eq!(
+                vfs_stat(dir.as_ptr(), null_mut()),
+                (RETRO_VFS_STAT_IS_VALID | RETRO_VFS_STAT_IS_DIRECTORY) as c_int
+            );
+
+            assert_eq!(list_dir(&dir), vec![("game.sav".to_string(), false)]);
+
+            let renamed = c_path(&format!("{base}/data/game.bak"));
+            assert_eq!(vfs_rename(file.as_ptr(), renamed.as_ptr()), 0);
+            assert_eq!(vfs_remove(renamed.as_ptr()), 0);
+            assert_eq!(vfs_stat(renamed.as_ptr(), null_mut()), 0);
+        }
+    }
+
+    #[test]
+    fn test_memory_backend_is_used_for_its_prefix() {
+        let memory = Arc::new(MemoryVfsBackend::new("memory://test_vfs"));
+        let path = c_path(&memory.insert("roms/game.bin", vec![1, 2, 3]).unwrap());
+
+        let backend: Arc<dyn VfsBackend> = memory.clone();
+        register_vfs_backend(backend.clone()).unwrap();
+
+        unsafe {
+            let stream = vfs_open(path.as_ptr(), RETRO_VFS_FILE_ACCESS_READ, 0);
+            assert!(!stream.is_null());
+            assert_eq!(read_to_vec(stream), vec![1, 2, 3]);
+            assert_eq!(vfs_write(stream, [9u8].as_ptr() as *const c_void, 1), -1);
+            assert_eq!(vfs_close(stream), 0);
+
+            assert_eq!(
+                list_dir(&c_path("memory://test_vfs")),
+                vec![("roms".to_string(), true)]
+            );
+
+            let new_file = c_path("memory://test_vfs/roms/new.sav");
+            let stream = vfs_open(new_file.as_ptr(), RETRO_VFS_FILE_ACCESS_WRITE, 0);
+            assert_eq!(vfs_write(stream, [7u8, 7].as_ptr() as *const c_void, 2), 2);
+            assert_eq!(vfs_close(stream), 0);
+
+            let mut size = 0;
+            assert_eq!(
+                vfs_stat(new_file.as_ptr(), &mut size),
+                RETRO_VFS_STAT_IS_VALID as c_int
+            );
+            assert_eq!(size, 2);
+        }
+
+        unregister_vfs_backend(&backend).unwrap();
+
+        unsafe {
+            assert!(vfs_open(path.as_ptr(), RETRO_VFS_FILE_ACCESS_READ, 0).is_null());
+        }
+    }
+}
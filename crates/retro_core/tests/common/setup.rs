@@ -1,6 +1,7 @@
 use generics::{
     error_handle::ErrorHandle,
     retro_paths::RetroPaths,
+    retro_settings::RetroSettings,
     test_workdir::{create_test_work_dir_path, get_test_core_path},
 };
 use libretro_sys::binding_libretro::retro_rumble_effect;
@@ -8,7 +9,10 @@ use retro_core::{
     RetroAudioEnvCallbacks, RetroControllerEnvCallbacks, RetroCore, RetroCoreIns,
     RetroEnvCallbacks, RetroVideoEnvCallbacks, av_info::AvInfo, graphic_api::GraphicApi,
 };
-use std::{ptr, sync::Arc};
+use std::{
+    ptr,
+    sync::{Arc, Mutex, MutexGuard},
+};
 
 pub fn get_callbacks() -> RetroEnvCallbacks {
     RetroEnvCallbacks {
@@ -65,11 +69,12 @@ impl RetroAudioEnvCallbacks for Audio {
     fn audio_sample_batch_callback(
         &self,
         _data: *const i16,
-        _frames: usize,
+        frames: usize,
         _retro_av: Arc<AvInfo>,
     ) -> Result<usize, ErrorHandle> {
-        println!("audio_sample_batch_callback -> {_frames}");
-        Ok(0)
+        println!("audio_sample_batch_callback -> {frames}");
+        // consome todos os frames, senão alguns núcleos ficam reenviando o mesmo lote em retro_run
+        Ok(frames)
     }
 }
 
@@ -106,7 +111,24 @@ impl RetroControllerEnvCallbacks for Controller {
     }
 }
 
+// o mesmo .so é compartilhado por todos os testes do processo, então apenas
+// um núcleo pode estar ativo por vez
+static CORE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn lock_core_test() -> MutexGuard<'static, ()> {
+    CORE_TEST_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 pub fn get_core_test(project_name: &str) -> Result<RetroCoreIns, ErrorHandle> {
+    get_core_test_with_settings(project_name, RetroSettings::default())
+}
+
+pub fn get_core_test_with_settings(
+    project_name: &str,
+    settings: RetroSettings,
+) -> Result<RetroCoreIns, ErrorHandle> {
     let test_dir = create_test_work_dir_path(project_name)
         .display()
         .to_string();
@@ -114,6 +136,7 @@ pub fn get_core_test(project_name: &str) -> Result<RetroCoreIns, ErrorHandle> {
     RetroCore::new(
         &get_test_core_path(),
         RetroPaths::from_base(&test_dir)?,
+        settings,
         get_callbacks(),
         GraphicApi::default(),
     )
@@ -1,16 +1,30 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
 use std::sync::atomic::Ordering;
 
-use crate::common::setup::get_core_test;
+use crate::common::setup::{get_core_test, get_core_test_with_settings, lock_core_test};
 use generics::{
     error_handle::ErrorHandle,
-    test_workdir::{get_test_rom_path, remove_test_work_dir_path},
+    retro_settings::RetroSettings,
+    test_workdir::{create_test_work_dir_path, get_test_rom_path, remove_test_work_dir_path},
 };
-use libretro_sys::binding_libretro::{retro_hw_context_type, retro_pixel_format};
+use libretro_sys::binding_libretro::{
+    RETRO_DEVICE_ID_JOYPAD_START, RETRO_DEVICE_JOYPAD, RETRO_DEVICE_MOUSE,
+    RETRO_SERIALIZATION_QUIRK_MUST_INITIALIZE, retro_hw_context_type, retro_pixel_format,
+};
+use retro_core::cheat_finder_manager::SearchFilter;
+use retro_core::memory_manager::{MemoryAddress, MemoryRegion, ValueWidth};
+use retro_core::option_manager::OptionOverride;
+use retro_core::save_state_manager::SaveSlot;
+use retro_core::save_state_tools::{SAVE_STATE_MAGIC, SaveStateTools};
+use zip::{ZipWriter, write::SimpleFileOptions};
 
 mod common;
 
 #[test]
 fn test_core_initial_state_and_after_load() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
     let dir = "retro_core.test_core_initial_state_and_after_load";
     let core = get_core_test(dir)?;
 
@@ -111,3 +125,495 @@ fn test_core_initial_state_and_after_load() -> Result<(), ErrorHandle> {
     remove_test_work_dir_path(dir)?;
     Ok(())
 }
+
+#[test]
+fn test_rewind_steps_back_through_snapshots() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_rewind_steps_back_through_snapshots";
+    let core = get_core_test(dir)?;
+
+    core.load_game(&get_test_rom_path().display().to_string())?;
+    core.rewind.set_enabled(true)?;
+    core.rewind.set_interval(1);
+
+    for _ in 0..5 {
+        core.run()?;
+        core.rewind_capture()?;
+    }
+
+    assert_eq!(core.rewind.available_snapshots()?, 4);
+    assert!(core.rewind_step_back()?);
+    assert_eq!(core.rewind.available_snapshots()?, 3);
+
+    assert!(core.rewind_frame()?);
+    assert_eq!(core.rewind.available_snapshots()?, 2);
+    assert!(core.av_info.output.audio.load(Ordering::SeqCst));
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_run_ahead_restores_output_after_hidden_frames() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_run_ahead_restores_output_after_hidden_frames";
+    let core = get_core_test(dir)?;
+
+    core.load_game(&get_test_rom_path().display().to_string())?;
+    core.run_ahead.set_frames(2);
+
+    for _ in 0..3 {
+        core.run_with_run_ahead()?;
+    }
+
+    assert!(core.run_ahead.is_active());
+    assert!(core.av_info.output.video.load(Ordering::SeqCst));
+    assert!(core.av_info.output.audio.load(Ordering::SeqCst));
+    assert!(!core.av_info.output.fast_savestates.load(Ordering::SeqCst));
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_load_game_from_zip_archive() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_load_game_from_zip_archive";
+    let core = get_core_test(dir)?;
+
+    let archive_path = create_test_work_dir_path(dir).join("240pTestSuite.zip");
+    let mut zip = ZipWriter::new(File::create(&archive_path)?);
+    zip.start_file("240pTestSuite.nes", SimpleFileOptions::default())
+        .map_err(|e| ErrorHandle::new(&e.to_string()))?;
+    zip.write_all(&fs::read(get_test_rom_path())?)?;
+    zip.finish().map_err(|e| ErrorHandle::new(&e.to_string()))?;
+
+    core.load_game(&format!("{}#240pTestSuite.nes", archive_path.display()))?;
+    assert!(core.game_loaded.load(Ordering::SeqCst));
+    assert_eq!(*core.rom_name.read()?, "240pTestSuite");
+    core.run()?;
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_failed_load_preparation_leaves_no_game_loaded() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_failed_load_preparation_leaves_no_game_loaded";
+    let core = get_core_test(dir)?;
+    let rom = get_test_rom_path().display().to_string();
+
+    // um diretório no lugar do .cht faz a preparação do jogo falhar depois do retro_load_game
+    let cht_path = Path::new(core.paths.cheats.as_str())
+        .join("Mesen")
+        .join("240pTestSuite.cht");
+    fs::create_dir_all(&cht_path)?;
+
+    assert!(core.load_game(&rom).is_err());
+    assert!(!core.game_loaded.load(Ordering::SeqCst));
+
+    fs::remove_dir(&cht_path)?;
+    core.load_game(&rom)?;
+    assert!(core.game_loaded.load(Ordering::SeqCst));
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_load_game_special_rejects_unknown_subsystem() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_load_game_special_rejects_unknown_subsystem";
+    let core = get_core_test(dir)?;
+
+    let rom = get_test_rom_path().display().to_string();
+
+    assert!(
+        core.load_game_special("not_a_subsystem", vec![rom])
+            .is_err()
+    );
+    assert!(
+        core.load_game_special("not_a_subsystem", Vec::new())
+            .is_err()
+    );
+    assert!(!core.game_loaded.load(Ordering::SeqCst));
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_same_core_library_is_not_loaded_twice() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_same_core_library_is_not_loaded_twice";
+    let core = get_core_test(dir)?;
+
+    // a segunda instância dividiria o estado global da mesma biblioteca
+    assert!(get_core_test(dir).is_err());
+
+    core.de_init()?;
+    drop(core);
+
+    let core = get_core_test(dir)?;
+    core.de_init()?;
+
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_input_descriptors_after_load() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_input_descriptors_after_load";
+    let core = get_core_test(dir)?;
+
+    assert!(
+        core.get_input_descriptors(0, RETRO_DEVICE_JOYPAD)?
+            .is_empty()
+    );
+
+    core.load_game(&get_test_rom_path().display().to_string())?;
+
+    let descriptors = core.get_input_descriptors(0, RETRO_DEVICE_JOYPAD)?;
+    assert!(descriptors.iter().all(|descriptor| descriptor.port == 0));
+    assert!(descriptors.iter().any(|descriptor| {
+        descriptor.id == RETRO_DEVICE_ID_JOYPAD_START && descriptor.description == "Start"
+    }));
+    assert!(
+        core.get_input_descriptors(0, RETRO_DEVICE_MOUSE)?
+            .is_empty()
+    );
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_controller_type_is_remembered_per_core() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_controller_type_is_remembered_per_core";
+    let core = get_core_test(dir)?;
+
+    let types = core.get_controller_types(0)?;
+    assert!(!types.is_empty());
+    assert!(types.iter().all(|description| *description.port == 0));
+    assert_eq!(
+        core.get_controller_type(0, RETRO_DEVICE_JOYPAD)?,
+        RETRO_DEVICE_JOYPAD
+    );
+
+    // o núcleo não declarou esse tipo
+    assert!(core.set_controller_type(0, u32::MAX).is_err());
+
+    let device = *types.last().unwrap().id;
+    core.set_controller_type(0, device)?;
+
+    core.de_init()?;
+    drop(core);
+
+    let core = get_core_test(dir)?;
+    assert_eq!(core.get_controller_type(0, RETRO_DEVICE_JOYPAD)?, device);
+    assert_eq!(
+        core.get_controller_type(1, RETRO_DEVICE_JOYPAD)?,
+        RETRO_DEVICE_JOYPAD
+    );
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_core_option_reaches_core_on_next_frame() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_core_option_reaches_core_on_next_frame";
+    let core = get_core_test(dir)?;
+
+    core.load_game(&get_test_rom_path().display().to_string())?;
+
+    let aspect_ratio = core
+        .options
+        .list()?
+        .into_iter()
+        .find(|option| option.key == "mesen_aspect_ratio")
+        .expect("o núcleo de teste deveria declarar mesen_aspect_ratio");
+    assert!(aspect_ratio.values.len() > 1);
+
+    let (new_value, _) = aspect_ratio
+        .values
+        .iter()
+        .find(|(value, _)| *value != aspect_ratio.selected)
+        .unwrap()
+        .clone();
+
+    assert!(
+        core.options
+            .update_opt("mesen_aspect_ratio", "invalid")
+            .is_err()
+    );
+    core.options.update_opt("mesen_aspect_ratio", &new_value)?;
+    assert!(core.options.updated_count.load(Ordering::SeqCst) > 0);
+
+    // o núcleo lê o GET_VARIABLE_UPDATE durante o retro_run
+    core.run()?;
+    assert_eq!(core.options.updated_count.load(Ordering::SeqCst), 0);
+
+    let selected = core
+        .options
+        .list()?
+        .into_iter()
+        .find(|option| option.key == "mesen_aspect_ratio")
+        .map(|option| option.selected);
+    assert_eq!(selected, Some(new_value));
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_game_option_override_survives_reload() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_game_option_override_survives_reload";
+    let core = get_core_test(dir)?;
+    let rom_path = get_test_rom_path().display().to_string();
+    let aspect_ratio = |core: &retro_core::RetroCore| -> Result<String, ErrorHandle> {
+        Ok(core
+            .options
+            .list()?
+            .into_iter()
+            .find(|option| option.key == "mesen_aspect_ratio")
+            .map(|option| option.selected)
+            .unwrap_or_default())
+    };
+
+    core.load_game(&rom_path)?;
+    let core_value = aspect_ratio(&core)?;
+    let new_value = core
+        .options
+        .list()?
+        .into_iter()
+        .find(|option| option.key == "mesen_aspect_ratio")
+        .and_then(|option| {
+            option
+                .values
+                .into_iter()
+                .map(|(value, _)| value)
+                .find(|value| *value != core_value)
+        })
+        .expect("o núcleo de teste deveria declarar mesen_aspect_ratio");
+
+    core.options.save_override(OptionOverride::Game)?;
+    core.options.update_opt("mesen_aspect_ratio", &new_value)?;
+    assert_eq!(core.options.active_override()?, Some(OptionOverride::Game));
+
+    // sem o jogo os valores voltam a ser os do núcleo
+    core.unload_game()?;
+    assert_eq!(aspect_ratio(&core)?, core_value);
+
+    core.load_game(&rom_path)?;
+    assert_eq!(aspect_ratio(&core)?, new_value);
+
+    core.options.clear_override(OptionOverride::Game)?;
+    assert_eq!(aspect_ratio(&core)?, core_value);
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_read_and_write_system_ram() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_read_and_write_system_ram";
+    let core = get_core_test(dir)?;
+
+    assert!(core.read_memory(MemoryAddress::Virtual(0), 1).is_err());
+
+    core.load_game(&get_test_rom_path().display().to_string())?;
+    core.run()?;
+
+    // a ram interna do NES começa no endereço 0, tanto no mapa quanto na região
+    let ram = MemoryAddress::Region(MemoryRegion::SystemRam, 0x10);
+    core.write_memory(ram, &[0x12, 0x34])?;
+    assert_eq!(
+        core.read_memory(MemoryAddress::Virtual(0x10), 2)?,
+        vec![0x12, 0x34]
+    );
+    assert!(
+        core.read_memory(
+            MemoryAddress::Region(MemoryRegion::SystemRam, usize::MAX),
+            1
+        )
+        .is_err()
+    );
+
+    core.memory.add_watch(1, ram, 2)?;
+    assert!(core.check_memory_watches()?.is_empty());
+
+    core.write_memory(MemoryAddress::Virtual(0x10), &[0x56])?;
+    let events = core.check_memory_watches()?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].new, vec![0x56, 0x34]);
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cheat_search_finds_changed_byte() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_cheat_search_finds_changed_byte";
+    let core = get_core_test(dir)?;
+
+    core.load_game(&get_test_rom_path().display().to_string())?;
+    core.run()?;
+
+    let address = MemoryAddress::Region(MemoryRegion::SystemRam, 0x30);
+    let count = core.start_cheat_search(ValueWidth::U8, false)?;
+    assert!(count > 1);
+
+    // sem rodar nenhum frame apenas o byte alterado aqui pode ter aumentado
+    let old = core.read_memory(address, 1)?[0];
+    core.write_memory(address, &[old.wrapping_add(1)])?;
+    let filter = if old == u8::MAX {
+        SearchFilter::Decreased
+    } else {
+        SearchFilter::Increased
+    };
+    assert_eq!(core.filter_cheat_search(filter)?, 1);
+    assert_eq!(core.cheat_finder.candidates(10)?[0].offset, 0x30);
+
+    core.add_cheat_from_search(0x30, 99, "Teste")?;
+    core.run()?;
+    assert_eq!(core.read_memory(address, 1)?, vec![99]);
+    assert!(core.cheats.get_cheats()?.last().unwrap().memory.is_some());
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_save_state_header_and_legacy_files() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_save_state_header_and_legacy_files";
+    let core = get_core_test(dir)?;
+
+    core.load_game(&get_test_rom_path().display().to_string())?;
+    core.run()?;
+    assert_ne!(core.rom_crc32.load(Ordering::SeqCst), 0);
+
+    let path = core.save_state(1, Some(b"png"))?;
+    let file = fs::read(&path)?;
+    assert!(file.starts_with(SAVE_STATE_MAGIC));
+
+    let state = SaveStateTools::decode(&file, usize::MAX)?;
+    let header = state.header.unwrap();
+    assert_eq!(header.library_name, *core.system.info.library_name);
+    assert_eq!(header.rom_crc32, core.rom_crc32.load(Ordering::SeqCst));
+    assert_eq!(state.thumbnail.as_deref(), Some(b"png".as_slice()));
+    core.load_state(1)?;
+
+    // saves criados antes do cabeçalho são só o buffer do retro_serialize
+    fs::write(&path, &state.data)?;
+    core.load_state(1)?;
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_save_state_slots_undo_and_auto_state() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_save_state_slots_undo_and_auto_state";
+    let settings = RetroSettings {
+        load_auto_state: true,
+        ..RetroSettings::default()
+    };
+    let core = get_core_test_with_settings(dir, settings)?;
+    let rom_path = get_test_rom_path().display().to_string();
+    let ram = MemoryAddress::Region(MemoryRegion::SystemRam, 0x20);
+
+    core.load_game(&rom_path)?;
+    core.run()?;
+
+    core.write_memory(ram, &[0x01])?;
+    core.save_state(2, None)?;
+    core.write_memory(ram, &[0x02])?;
+    core.save_state(2, None)?;
+
+    assert_eq!(core.undo_save_state()?, Some(SaveSlot::Index(2)));
+    core.load_state(2)?;
+    assert_eq!(core.read_memory(ram, 1)?, vec![0x01]);
+
+    assert!(core.undo_load_state()?);
+    assert_eq!(core.read_memory(ram, 1)?, vec![0x02]);
+    assert!(!core.undo_load_state()?);
+
+    core.save_states
+        .copy(SaveSlot::Index(2), SaveSlot::Index(5))?;
+    core.save_states
+        .rename(SaveSlot::Index(5), SaveSlot::Index(6))?;
+    let slots = core.save_states.list()?;
+    let indexes: Vec<SaveSlot> = slots.iter().map(|info| info.slot).collect();
+    assert_eq!(indexes, vec![SaveSlot::Index(2), SaveSlot::Index(6)]);
+    assert!(slots[0].header.is_some());
+    core.save_states.delete(SaveSlot::Index(6))?;
+
+    // o auto state é escrito ao fechar e carregado de volta ao abrir o jogo
+    let auto_path = core.save_states.path(SaveSlot::Auto)?;
+    core.unload_game()?;
+    assert!(auto_path.is_file());
+
+    core.load_game(&rom_path)?;
+    assert_eq!(core.read_memory(ram, 1)?, vec![0x02]);
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_serialization_quirks_must_initialize() -> Result<(), ErrorHandle> {
+    let _lock = lock_core_test();
+    let dir = "retro_core.test_serialization_quirks_must_initialize";
+    let settings = RetroSettings {
+        load_auto_state: true,
+        ..RetroSettings::default()
+    };
+    let core = get_core_test_with_settings(dir, settings)?;
+    let rom_path = get_test_rom_path().display().to_string();
+    let ram = MemoryAddress::Region(MemoryRegion::SystemRam, 0x20);
+
+    core.load_game(&rom_path)?;
+    core.serialization
+        .set_quirks(RETRO_SERIALIZATION_QUIRK_MUST_INITIALIZE as u64);
+
+    // o núcleo só aceita serializar depois do primeiro frame
+    assert!(core.save_state(1, None).is_err());
+    core.run()?;
+    core.write_memory(ram, &[0x03])?;
+    core.save_state(1, None)?;
+
+    core.unload_game()?;
+    core.load_game(&rom_path)?;
+
+    // o auto state fica para depois do primeiro frame
+    core.write_memory(ram, &[0x00])?;
+    core.run()?;
+    assert_eq!(core.read_memory(ram, 1)?, vec![0x03]);
+
+    core.de_init()?;
+    remove_test_work_dir_path(dir)?;
+    Ok(())
+}
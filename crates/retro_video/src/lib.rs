@@ -10,9 +10,10 @@ mod retro_env_callback;
 mod retro_gl;
 mod retro_window;
 mod sync;
+mod triple_buffer;
 mod video;
 
 pub use retro_env_callback::RetroVideoCb;
 pub use retro_window::RetroWindowMode;
-pub use sync::SyncData;
+pub use sync::{RetroSync, SyncData};
 pub use video::RetroVideo;
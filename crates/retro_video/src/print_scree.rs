@@ -1,9 +1,10 @@
 use crate::raw_texture::RawTextureData;
 use generics::error_handle::ErrorHandle;
-use image::{ImageBuffer, RgbImage};
+use image::{ImageBuffer, ImageFormat, RgbImage};
 use libretro_sys::binding_libretro::retro_pixel_format;
 use retro_core::av_info::AvInfo;
 use std::{
+    io::Cursor,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -16,29 +17,44 @@ impl PrintScree {
         av_info: &Arc<AvInfo>,
         out_path: &mut PathBuf,
     ) -> Result<(), ErrorHandle> {
+        Self::image(raw_texture, av_info)?
+            .save(Path::new(out_path))
+            .map_err(|e| ErrorHandle::new(&e.to_string()))
+    }
+
+    #[doc = "mesma imagem do `take`, mas codificada como PNG em memória"]
+    pub fn take_png(
+        raw_texture: &RawTextureData,
+        av_info: &Arc<AvInfo>,
+    ) -> Result<Vec<u8>, ErrorHandle> {
+        let mut png = Cursor::new(Vec::new());
+
+        Self::image(raw_texture, av_info)?
+            .write_to(&mut png, ImageFormat::Png)
+            .map_err(|e| ErrorHandle::new(&e.to_string()))?;
+
+        Ok(png.into_inner())
+    }
+
+    fn image(raw_texture: &RawTextureData, av_info: &Arc<AvInfo>) -> Result<RgbImage, ErrorHandle> {
+        if raw_texture.is_empty() {
+            return Err(ErrorHandle::new("Nenhum frame foi desenhado ainda"));
+        }
+
         match &*av_info
             .video
             .pixel_format
             .load_or_spawn_err("Pixel está inacessivel")?
         {
-            retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888 => {
-                Self::_from_xrgb8888(raw_texture, out_path)
-            }
-            retro_pixel_format::RETRO_PIXEL_FORMAT_0RGB1555 => {
-                Self::_from_0rgb1555(raw_texture, out_path)
-            }
-            retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565 => {
-                Self::_from_rgb565(raw_texture, out_path)
-            }
+            retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888 => Self::_from_xrgb8888(raw_texture),
+            retro_pixel_format::RETRO_PIXEL_FORMAT_0RGB1555 => Self::_from_0rgb1555(raw_texture),
+            retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565 => Self::_from_rgb565(raw_texture),
             _ => Err(ErrorHandle::new("Formato de pixel desconhecido")),
         }
     }
 
-    fn _from_xrgb8888(
-        raw_texture: &RawTextureData,
-        out_path: &mut PathBuf,
-    ) -> Result<(), ErrorHandle> {
-        let data_ptr = unsafe { raw_texture.data.get().read() as *const u8 };
+    fn _from_xrgb8888(raw_texture: &RawTextureData) -> Result<RgbImage, ErrorHandle> {
+        let data_ptr = raw_texture.data.as_ptr();
 
         let width = raw_texture.width as usize;
         let height = raw_texture.height as usize;
@@ -65,22 +81,12 @@ impl PrintScree {
             }
         }
 
-        let img: RgbImage =
-            ImageBuffer::from_raw(raw_texture.width, raw_texture.height, img_buffer)
-                .ok_or_else(|| ErrorHandle::new("Falha ao criar ImageBuffer"))?;
-
-        img.save(Path::new(out_path))
-            .map_err(|e| e.to_string())
-            .unwrap();
-
-        Ok(())
+        ImageBuffer::from_raw(raw_texture.width, raw_texture.height, img_buffer)
+            .ok_or_else(|| ErrorHandle::new("Falha ao criar ImageBuffer"))
     }
 
-    fn _from_0rgb1555(
-        raw_texture: &RawTextureData,
-        out_path: &mut PathBuf,
-    ) -> Result<(), ErrorHandle> {
-        let data_ptr = unsafe { raw_texture.data.get().read() as *const u8 };
+    fn _from_0rgb1555(raw_texture: &RawTextureData) -> Result<RgbImage, ErrorHandle> {
+        let data_ptr = raw_texture.data.as_ptr();
 
         let mut img_buffer =
             Vec::with_capacity((raw_texture.width * raw_texture.height * 3) as usize);
@@ -110,21 +116,12 @@ impl PrintScree {
             }
         }
 
-        let img: RgbImage =
-            ImageBuffer::from_raw(raw_texture.width, raw_texture.height, img_buffer)
-                .ok_or_else(|| ErrorHandle::new("Falha ao criar ImageBuffer"))?;
-
-        img.save(Path::new(out_path))
-            .map_err(|e| ErrorHandle::new(&e.to_string()))?;
-
-        Ok(())
+        ImageBuffer::from_raw(raw_texture.width, raw_texture.height, img_buffer)
+            .ok_or_else(|| ErrorHandle::new("Falha ao criar ImageBuffer"))
     }
 
-    fn _from_rgb565(
-        raw_texture: &RawTextureData,
-        out_path: &mut PathBuf,
-    ) -> Result<(), ErrorHandle> {
-        let data_ptr = unsafe { raw_texture.data.get().read() as *const u8 };
+    fn _from_rgb565(raw_texture: &RawTextureData) -> Result<RgbImage, ErrorHandle> {
+        let data_ptr = raw_texture.data.as_ptr();
 
         let width = raw_texture.width as usize;
         let height = raw_texture.height as usize;
@@ -151,13 +148,7 @@ impl PrintScree {
             }
         }
 
-        let img: RgbImage =
-            ImageBuffer::from_raw(raw_texture.width, raw_texture.height, img_buffer)
-                .ok_or_else(|| ErrorHandle::new("Falha ao criar ImageBuffer"))?;
-
-        img.save(Path::new(out_path))
-            .map_err(|e| ErrorHandle::new(&e.to_string()))?;
-
-        Ok(())
+        ImageBuffer::from_raw(raw_texture.width, raw_texture.height, img_buffer)
+            .ok_or_else(|| ErrorHandle::new("Falha ao criar ImageBuffer"))
     }
 }
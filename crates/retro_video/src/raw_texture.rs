@@ -1,9 +1,8 @@
-use std::cell::UnsafeCell;
-use std::ffi::{c_uint, c_void};
-use std::ptr::null;
+use std::ffi::c_uint;
 
+#[derive(Default)]
 pub struct RawTextureData {
-    pub data: UnsafeCell<*const c_void>,
+    pub data: Vec<u8>,
     pub width: c_uint,
     pub height: c_uint,
     pub pitch: usize,
@@ -11,11 +10,22 @@ pub struct RawTextureData {
 
 impl RawTextureData {
     pub fn new() -> Self {
-        Self {
-            data: UnsafeCell::new(null()),
-            pitch: 0,
-            height: 0,
-            width: 0,
-        }
+        Self::default()
     }
-}
\ No newline at end of file
+
+    // o ponteiro do núcleo só é válido durante o video_refresh, então o frame é copiado
+    pub fn copy_from(&mut self, data: *const u8, width: c_uint, height: c_uint, pitch: usize) {
+        let len = pitch * height as usize;
+
+        self.data.clear();
+        self.data
+            .extend_from_slice(unsafe { std::slice::from_raw_parts(data, len) });
+        self.width = width;
+        self.height = height;
+        self.pitch = pitch;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
@@ -1,28 +1,34 @@
 use crate::raw_texture::RawTextureData;
-use crate::retro_window::RetroWindowContext;
+use crate::triple_buffer::TripleBufferWriter;
+use crate::video::with_thread_window_ctx;
 use generics::error_handle::ErrorHandle;
-use generics::types::ArcTMutex;
+use generics::types::{ArcTMutex, TMutex};
 use retro_core::RetroVideoEnvCallbacks;
 use std::ffi::c_void;
 use std::ptr::null;
 
+// RETRO_HW_FRAME_BUFFER_VALID, o núcleo já desenhou o frame no fbo do frontend
+const HW_FRAME_BUFFER_VALID: usize = usize::MAX;
+
 pub struct RetroVideoCb {
-    texture: ArcTMutex<RawTextureData>,
-    window_ctx: ArcTMutex<Option<Box<dyn RetroWindowContext>>>,
+    frame_writer: ArcTMutex<TripleBufferWriter<RawTextureData>>,
+    on_new_frame: Box<dyn Fn() + Send + Sync>,
 }
 
 impl RetroVideoCb {
     pub fn new(
-        texture: ArcTMutex<RawTextureData>,
-        window_ctx: ArcTMutex<Option<Box<dyn RetroWindowContext>>>,
+        frame_writer: TripleBufferWriter<RawTextureData>,
+        on_new_frame: Box<dyn Fn() + Send + Sync>,
     ) -> Self {
         Self {
-            texture,
-            window_ctx,
+            frame_writer: TMutex::new(frame_writer),
+            on_new_frame,
         }
     }
 }
 
+// os frames por software chegam a janela pelo triple buffer, já os núcleos com renderização
+// por hardware rodam na thread do winit e usam a janela diretamente
 impl RetroVideoEnvCallbacks for RetroVideoCb {
     fn video_refresh_callback(
         &self,
@@ -31,42 +37,46 @@ impl RetroVideoEnvCallbacks for RetroVideoCb {
         height: u32,
         pitch: usize,
     ) -> Result<(), ErrorHandle> {
-        let mut texture = self.texture.try_load()?;
-        {
-            let tex_data = texture.data.get_mut();
-
-            *tex_data = data;
-            texture.width = width;
-            texture.height = height;
-            texture.pitch = pitch;
+        // frame duplicado, a janela continua mostrando o anterior
+        if data.is_null() {
+            return Ok(());
         }
 
-        if let Some(win) = &mut *self.window_ctx.try_load()? {
-            win.draw_new_frame(&texture);
+        if data as usize == HW_FRAME_BUFFER_VALID {
+            let texture = RawTextureData {
+                width,
+                height,
+                pitch,
+                ..RawTextureData::new()
+            };
+
+            with_thread_window_ctx(|win| win.draw_new_frame(&texture))?;
+            return Ok(());
         }
 
+        let mut frame_writer = self.frame_writer.try_load()?;
+
+        frame_writer
+            .back_buffer()
+            .copy_from(data.cast(), width, height, pitch);
+        frame_writer.publish();
+
+        (self.on_new_frame)();
+
         Ok(())
     }
 
     fn context_reset(&self) -> Result<(), ErrorHandle> {
-        if let Some(win) = &mut *self.window_ctx.try_load()? {
-            win.context_reset();
-        }
+        with_thread_window_ctx(|win| win.context_reset())?;
         Ok(())
     }
 
     fn get_proc_address(&self, proc_name: &str) -> Result<*const (), ErrorHandle> {
-        if let Some(win) = &mut *self.window_ctx.try_load()? {
-            return Ok(win.get_proc_address(proc_name));
-        }
-
-        Ok(null())
+        Ok(with_thread_window_ctx(|win| win.get_proc_address(proc_name))?.unwrap_or(null()))
     }
 
     fn context_destroy(&self) -> Result<(), ErrorHandle> {
-        if let Some(win) = &mut *self.window_ctx.try_load()? {
-            win.context_destroy();
-        }
+        with_thread_window_ctx(|win| win.context_destroy())?;
         Ok(())
     }
 }
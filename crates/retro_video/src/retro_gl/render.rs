@@ -80,7 +80,10 @@ impl Render {
             self.gl.ClearColor(0.0, 0.0, 0.0, 1.0);
             self.gl.Clear(gl::COLOR_BUFFER_BIT);
 
-            self._texture.push(texture);
+            // nos núcleos com renderização por hardware o frame já está na textura do fbo
+            if !texture.is_empty() {
+                self._texture.push(texture);
+            }
             self._program.use_program();
             self._texture.active();
 
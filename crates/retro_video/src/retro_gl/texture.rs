@@ -46,7 +46,7 @@ impl Texture2D {
                 texture.height as i32,
                 self.pixel.typ,
                 self.pixel.format,
-                texture.data.get().read(),
+                texture.data.as_ptr().cast(),
             );
             self.gl.BindTexture(gl::TEXTURE0, 0);
         }
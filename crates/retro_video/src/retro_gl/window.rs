@@ -1,6 +1,7 @@
 use super::render::Render;
 use crate::raw_texture::RawTextureData;
 use crate::winit::{event_loop::ActiveEventLoop, window::Window};
+use generics::logger::{self, LogTarget};
 use glutin::{
     config::{Config, ConfigTemplateBuilder},
     context::{
@@ -13,6 +14,7 @@ use glutin::{
 use glutin_winit::{DisplayBuilder, GlWindow};
 use raw_window_handle::HasWindowHandle;
 use retro_core::av_info::AvInfo;
+use std::ffi::CString;
 use std::num::NonZeroU32;
 use std::ptr::null;
 use std::sync::atomic::Ordering;
@@ -129,8 +131,12 @@ impl RetroWindowContext for RetroGlWindow {
     }
 
     fn get_proc_address(&self, proc_name: &str) -> *const () {
-        println!("get_proc_address({:?})", proc_name);
-        let cstr = std::ffi::CString::new(proc_name).unwrap();
+        logger::debug(LogTarget::Video, format!("get_proc_address({proc_name:?})"));
+
+        // chamado pelo núcleo por uma callback extern "C", um nome inválido não pode causar panic
+        let Ok(cstr) = CString::new(proc_name) else {
+            return null();
+        };
 
         match &self.gl_context {
             Some(gl_context) => gl_context.display().get_proc_address(cstr.as_c_str()) as *const (),
@@ -9,6 +9,8 @@ pub struct RetroSync {
     last_frame_time: Instant,
     rate_control_delta: f64,
     frame_count: u32,
+    // None mantém os frames sem limite de velocidade
+    speed: Option<f64>,
     pub sync_data: ArcTMutex<SyncData>,
 }
 
@@ -26,6 +28,7 @@ impl RetroSync {
         Self {
             last_frame_time: Instant::now(),
             frame_count: 0,
+            speed: Some(1.0),
             rate_control_delta,
             sync_data: TMutex::new(SyncData {
                 elapsed: Duration::from_secs_f64(0.0),
@@ -37,8 +40,13 @@ impl RetroSync {
         }
     }
 
+    #[doc = "multiplicador aplicado ao fps do núcleo, use None para não limitar a velocidade"]
+    pub fn set_speed(&mut self, speed: Option<f64>) {
+        self.speed = speed.filter(|speed| *speed > 0.0);
+    }
+
     pub fn prepare_sync(&mut self, av: &Arc<AvInfo>) -> Result<(), ErrorHandle> {
-        let fps = *av.timing.fps.read()?;
+        let fps = *av.timing.fps.read()? * self.speed.unwrap_or(1.0);
         let target_frame_duration = Duration::from_secs_f64(1.0 / fps);
         let now = Instant::now();
         let elapsed = now.duration_since(self.last_frame_time);
@@ -64,6 +72,12 @@ impl RetroSync {
     pub fn sync_now(&mut self) -> Result<(), ErrorHandle> {
         let sync_data = self.sync_data.try_load()?;
 
+        if self.speed.is_none() {
+            self.last_frame_time = sync_data.now;
+            self.frame_count += 1;
+            return Ok(());
+        }
+
         let sleep_time = if sync_data.adjustment < 0.0 {
             sync_data.target_frame_duration - sync_data.elapsed
         } else {
@@ -0,0 +1,118 @@
+use std::cell::UnsafeCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// bit marcado no índice do buffer do meio quando ele tem um frame que o leitor ainda não viu
+const NEW_DATA: usize = 0b100;
+const INDEX_MASK: usize = 0b011;
+
+struct Shared<T> {
+    buffers: [UnsafeCell<T>; 3],
+    middle: AtomicUsize,
+}
+
+// cada buffer só é acessado por quem possui o seu índice, e os índices só trocam de dono
+// pelo swap atômico do buffer do meio
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// Cria um triple buffer para trocar frames entre a thread de emulação e a de renderização.
+///
+/// O escritor sempre tem um buffer livre para preencher e o leitor sempre tem o último frame
+/// completo, nenhum dos dois espera pelo outro. Frames que o leitor não conseguiu pegar a
+/// tempo são simplesmente substituídos pelo próximo.
+pub fn triple_buffer<T: Default>() -> (TripleBufferWriter<T>, TripleBufferReader<T>) {
+    let shared = Arc::new(Shared {
+        buffers: [
+            UnsafeCell::new(T::default()),
+            UnsafeCell::new(T::default()),
+            UnsafeCell::new(T::default()),
+        ],
+        middle: AtomicUsize::new(1),
+    });
+
+    (
+        TripleBufferWriter {
+            shared: shared.clone(),
+            index: 0,
+        },
+        TripleBufferReader { shared, index: 2 },
+    )
+}
+
+pub struct TripleBufferWriter<T> {
+    shared: Arc<Shared<T>>,
+    index: usize,
+}
+
+impl<T> TripleBufferWriter<T> {
+    pub fn back_buffer(&mut self) -> &mut T {
+        unsafe { &mut *self.shared.buffers[self.index].get() }
+    }
+
+    #[doc = "entrega o back buffer para o leitor e passa a escrever no buffer antigo do meio"]
+    pub fn publish(&mut self) {
+        let old_middle = self
+            .shared
+            .middle
+            .swap(self.index | NEW_DATA, Ordering::AcqRel);
+
+        self.index = old_middle & INDEX_MASK;
+    }
+}
+
+pub struct TripleBufferReader<T> {
+    shared: Arc<Shared<T>>,
+    index: usize,
+}
+
+impl<T> TripleBufferReader<T> {
+    pub fn has_new_data(&self) -> bool {
+        self.shared.middle.load(Ordering::Acquire) & NEW_DATA != 0
+    }
+
+    #[doc = "pega o frame mais recente, retorna false se nada mudou desde a última leitura"]
+    pub fn update(&mut self) -> bool {
+        if !self.has_new_data() {
+            return false;
+        }
+
+        let old_middle = self.shared.middle.swap(self.index, Ordering::AcqRel);
+        self.index = old_middle & INDEX_MASK;
+
+        true
+    }
+
+    pub fn front_buffer(&self) -> &T {
+        unsafe { &*self.shared.buffers[self.index].get() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader_gets_latest_published_value() {
+        let (mut writer, mut reader) = triple_buffer::<u32>();
+        assert!(!reader.update());
+
+        *writer.back_buffer() = 1;
+        writer.publish();
+        *writer.back_buffer() = 2;
+        writer.publish();
+
+        // o frame 1 foi descartado, o leitor só vê o mais recente
+        assert!(reader.has_new_data());
+        assert!(reader.update());
+        assert_eq!(*reader.front_buffer(), 2);
+
+        assert!(!reader.update());
+        assert_eq!(*reader.front_buffer(), 2);
+
+        *writer.back_buffer() = 3;
+        assert!(!reader.has_new_data());
+        writer.publish();
+        assert!(reader.update());
+        assert_eq!(*reader.front_buffer(), 3);
+    }
+}
@@ -1,34 +1,65 @@
 use crate::raw_texture::RawTextureData;
 use crate::retro_env_callback::RetroVideoCb;
 use crate::retro_window::{RetroWindowContext, RetroWindowMode};
-use crate::sync::RetroSync;
+use crate::triple_buffer::{TripleBufferReader, TripleBufferWriter, triple_buffer};
 use crate::{print_scree::PrintScree, retro_gl::window::RetroGlWindow};
-use generics::{
-    error_handle::ErrorHandle,
-    types::{ArcTMutex, TMutex},
-};
+use generics::error_handle::ErrorHandle;
 use libretro_sys::binding_libretro::retro_hw_context_type::{
     RETRO_HW_CONTEXT_NONE, RETRO_HW_CONTEXT_OPENGL, RETRO_HW_CONTEXT_OPENGL_CORE,
 };
 use retro_core::av_info::AvInfo;
 use std::{
+    cell::RefCell,
     path::{Path, PathBuf},
+    rc::{Rc, Weak},
     sync::Arc,
 };
 use winit::event_loop::ActiveEventLoop;
 
+type WindowCtxCell = RefCell<Option<Box<dyn RetroWindowContext>>>;
+type WindowCtx = Rc<WindowCtxCell>;
+
+thread_local! {
+    // o contexto GL só pode ser usado na thread em que foi criado, então as callbacks de
+    // renderização por hardware só encontram a janela quando o núcleo roda na thread do winit
+    static THREAD_WINDOW_CTX: RefCell<Weak<WindowCtxCell>> = RefCell::new(Weak::new());
+}
+
+#[doc = "usa a janela da thread atual, Ok(None) quando ela ainda não foi criada"]
+pub(crate) fn with_thread_window_ctx<R>(
+    f: impl FnOnce(&mut Box<dyn RetroWindowContext>) -> R,
+) -> Result<Option<R>, ErrorHandle> {
+    let window_ctx = THREAD_WINDOW_CTX
+        .with(|window_ctx| window_ctx.borrow().upgrade())
+        .ok_or_else(|| {
+            ErrorHandle::new("A renderização por hardware só pode ser usada na thread da janela")
+        })?;
+
+    let mut window_ctx = window_ctx
+        .try_borrow_mut()
+        .map_err(|e| ErrorHandle::new(&e.to_string()))?;
+
+    Ok(window_ctx.as_mut().map(f))
+}
+
 pub struct RetroVideo {
-    window_ctx: ArcTMutex<Option<Box<dyn RetroWindowContext>>>,
-    texture: ArcTMutex<RawTextureData>,
-    pub sync: RetroSync,
+    window_ctx: WindowCtx,
+    frame_reader: TripleBufferReader<RawTextureData>,
+    // entregue ao núcleo pelo get_core_cb, só existe um escritor por RetroVideo
+    frame_writer: Option<TripleBufferWriter<RawTextureData>>,
 }
 
 impl Default for RetroVideo {
     fn default() -> Self {
+        let (frame_writer, frame_reader) = triple_buffer();
+        let window_ctx: WindowCtx = Rc::new(RefCell::new(None));
+
+        THREAD_WINDOW_CTX.with(|thread_ctx| *thread_ctx.borrow_mut() = Rc::downgrade(&window_ctx));
+
         Self {
-            window_ctx: TMutex::new(None),
-            texture: TMutex::new(RawTextureData::new()),
-            sync: RetroSync::new(0.0002),
+            window_ctx,
+            frame_reader,
+            frame_writer: Some(frame_writer),
         }
     }
 }
@@ -42,7 +73,7 @@ impl RetroVideo {
         match &av_info.video.graphic_api.context_type {
             RETRO_HW_CONTEXT_OPENGL_CORE | RETRO_HW_CONTEXT_OPENGL | RETRO_HW_CONTEXT_NONE => {
                 self.window_ctx
-                    .try_load()?
+                    .borrow_mut()
                     .replace(Box::new(RetroGlWindow::new(event_loop, av_info)));
             }
             // RETRO_HW_CONTEXT_VULKAN => {}
@@ -56,10 +87,10 @@ impl RetroVideo {
         Ok(())
     }
 
-    pub fn create_draw_context(&self) -> Result<(), ErrorHandle> {
-        let window_ctx = &mut *self.window_ctx.try_load()?;
+    pub fn create_draw_context(&mut self) -> Result<(), ErrorHandle> {
+        let mut window_ctx = self.window_ctx.borrow_mut();
 
-        let window_ctx = match window_ctx {
+        let window_ctx = match &mut *window_ctx {
             Some(ctx) => ctx,
             None => return Err(ErrorHandle::new("windows context is not initialized")),
         };
@@ -69,61 +100,96 @@ impl RetroVideo {
     }
 
     pub fn draw_context_as_initialized(&self) -> bool {
-        let window_ctx = match self.window_ctx.try_load() {
-            Ok(ctx) => ctx,
-            Err(_) => return false,
-        };
-
-        match &*window_ctx {
+        match &*self.window_ctx.borrow() {
             Some(ctx) => ctx.draw_context_as_initialized(),
             None => false,
         }
     }
 
-    pub fn destroy_window(&self) {
-        self.window_ctx.store(None);
-        self.texture.store(RawTextureData::new());
+    pub fn destroy_window(&mut self) {
+        // o borrow precisa terminar antes do drop da janela
+        let window_ctx = self.window_ctx.borrow_mut().take();
+
+        if let Some(mut win) = window_ctx {
+            win.context_destroy();
+        }
     }
 
+    #[doc = "só pede um novo desenho quando a thread de emulação já entregou um frame novo"]
     pub fn request_redraw(&self) -> Result<(), ErrorHandle> {
-        if let Some(win) = &*self.window_ctx.try_load()? {
+        if let Some(win) = &*self.window_ctx.borrow()
+            && self.frame_reader.has_new_data()
+        {
             win.request_redraw();
         }
 
         Ok(())
     }
 
-    pub fn print_screen(&self, out_path: &Path, av_info: &Arc<AvInfo>) -> Result<(), ErrorHandle> {
+    pub fn draw_new_frame(&mut self) -> Result<(), ErrorHandle> {
+        self.frame_reader.update();
+        let texture = self.frame_reader.front_buffer();
+
+        if let Some(win) = &*self.window_ctx.borrow()
+            && !texture.is_empty()
+        {
+            win.draw_new_frame(texture);
+        }
+
+        Ok(())
+    }
+
+    pub fn print_screen(
+        &mut self,
+        out_path: &Path,
+        av_info: &Arc<AvInfo>,
+    ) -> Result<(), ErrorHandle> {
+        self.frame_reader.update();
+
         PrintScree::take(
-            &*self.texture.try_load()?,
+            self.frame_reader.front_buffer(),
             av_info,
             &mut PathBuf::from(out_path),
         )
     }
 
+    pub fn print_screen_png(&mut self, av_info: &Arc<AvInfo>) -> Result<Vec<u8>, ErrorHandle> {
+        self.frame_reader.update();
+
+        PrintScree::take_png(self.frame_reader.front_buffer(), av_info)
+    }
+
     pub fn toggle_window_mode(&mut self) -> Result<(), ErrorHandle> {
-        if let Some(win) = &mut *self.window_ctx.try_load()? {
+        if let Some(win) = &mut *self.window_ctx.borrow_mut() {
             win.toggle_window_model();
         }
         Ok(())
     }
 
     pub fn set_window_mode(&mut self, mode: RetroWindowMode) -> Result<(), ErrorHandle> {
-        if let Some(win) = &mut *self.window_ctx.try_load()? {
+        if let Some(win) = &mut *self.window_ctx.borrow_mut() {
             win.set_window_mode(mode);
         }
         Ok(())
     }
 
     pub fn resize_window(&mut self, width: u32, height: u32) -> Result<(), ErrorHandle> {
-        if let Some(win) = &mut *self.window_ctx.try_load()? {
+        if let Some(win) = &mut *self.window_ctx.borrow_mut() {
             win.resize(width, height);
         }
 
         Ok(())
     }
 
-    pub fn get_core_cb(&self) -> RetroVideoCb {
-        RetroVideoCb::new(self.texture.clone(), self.window_ctx.clone())
+    #[doc = "`on_new_frame` é chamado pela thread de emulação sempre que um frame novo fica pronto"]
+    pub fn get_core_cb<F>(&mut self, on_new_frame: F) -> Result<RetroVideoCb, ErrorHandle>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let frame_writer = self.frame_writer.take().ok_or_else(|| {
+            ErrorHandle::new("As callbacks de vídeo já foram entregues a um núcleo")
+        })?;
+
+        Ok(RetroVideoCb::new(frame_writer, Box::new(on_new_frame)))
     }
 }
@@ -1,4 +1,9 @@
-use tinic::{DeviceListener, GameState, RetroGamePad, SaveStateInfo, WindowListener, WindowState};
+use tinic::{
+    CheatCandidateInfo, CheatInfo, ControllerPortInfo, CoreMessageInfo, CoreOptionCategoryInfo,
+    CoreOptionInfo, DeviceListener, DiskInfo, GameState, InputDescriptorInfo, MemoryAddressInfo,
+    MemoryWatchInfo, RetroGamePad, SaveRamInfo, SaveStateInfo, SaveStateSlotInfo, WindowListener,
+    WindowState,
+};
 
 #[derive(Debug, Default)]
 pub struct DeviceEvents;
@@ -39,4 +44,67 @@ impl WindowListener for WindowEvents {
     fn keyboard_state(&self, has_using: bool) {
         println!("keyboard_state: has_using -> {has_using}");
     }
+
+    fn save_ram_result(&self, info: SaveRamInfo) {
+        println!("save_ram_result: {info:?}");
+    }
+
+    fn cheats_changed(&self, cheats: Vec<CheatInfo>) {
+        println!("cheats_changed: {cheats:?}");
+    }
+
+    fn disk_state_changed(&self, info: DiskInfo) {
+        println!("disk_state_changed: {info:?}");
+    }
+
+    fn frame_advanced(&self, frame_count: u64) {
+        println!("frame_advanced: {frame_count}");
+    }
+
+    fn input_descriptors_changed(&self, descriptors: Vec<InputDescriptorInfo>) {
+        println!("input_descriptors_changed: {descriptors:?}");
+    }
+
+    fn controller_types_changed(&self, ports: Vec<ControllerPortInfo>) {
+        println!("controller_types_changed: {ports:?}");
+    }
+
+    fn core_message(&self, message: CoreMessageInfo) {
+        println!("core_message: {message:?}");
+    }
+
+    fn core_options_changed(
+        &self,
+        options: Vec<CoreOptionInfo>,
+        categories: Vec<CoreOptionCategoryInfo>,
+    ) {
+        println!(
+            "core_options_changed: {} opções, {} categorias",
+            options.len(),
+            categories.len()
+        );
+    }
+
+    fn memory_read(&self, address: MemoryAddressInfo, data: Vec<u8>) {
+        println!("memory_read: {address:?} -> {data:02X?}");
+    }
+
+    fn cheat_search_changed(&self, count: usize, candidates: Vec<CheatCandidateInfo>) {
+        println!("cheat_search_changed: {count} candidatos");
+
+        for candidate in candidates {
+            println!("  {:#06x} = {}", candidate.offset, candidate.value);
+        }
+    }
+
+    fn memory_watch_triggered(&self, watch: MemoryWatchInfo) {
+        println!(
+            "memory_watch_triggered: {} {:02X?} -> {:02X?}",
+            watch.id, watch.old, watch.new
+        );
+    }
+
+    fn save_state_slots_changed(&self, slots: Vec<SaveStateSlotInfo>) {
+        println!("save_state_slots_changed: {slots:?}");
+    }
 }
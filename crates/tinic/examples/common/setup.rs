@@ -20,6 +20,7 @@ pub fn create_game_instance(tinic: &mut Tinic) -> Result<GameInstance, ErrorHand
         sys_dir: create_test_work_dir_path(TINIC_EXAMPLE_DIR)
             .display()
             .to_string(),
+        ..Default::default()
     };
 
     tinic.create_game_instance(game_info)
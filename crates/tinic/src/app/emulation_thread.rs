@@ -0,0 +1,417 @@
+use crate::app::listener::{
+    CoreMessageInfo, CoreMessageKind, CoreMessageLevel, CoreMessageTarget, CoreOptionCategoryInfo,
+    CoreOptionInfo, CoreOptionValueInfo, MemoryAddressInfo, MemoryRegionInfo, MemoryWatchInfo,
+    SaveRamInfo, WindowListener,
+};
+use crate::app_dispatcher::GameInstanceDispatchers;
+use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
+use retro_audio::RetroAudioCb;
+use retro_core::RetroCoreIns;
+use retro_core::memory_manager::{MemoryAddress, MemoryRegion, MemoryWatchEvent};
+use retro_core::message_manager::{self, CoreMessage};
+use retro_core::option_manager::{CategoryInfo, CoreOptInfo};
+use retro_video::RetroSync;
+use std::cell::{RefCell, RefMut};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+type EmulationCommand = Box<dyn FnOnce(&mut EmulationCtx) + Send>;
+
+/// Estado que pertence exclusivamente a thread de emulação.
+///
+/// O núcleo nunca é chamado pela thread da janela, tudo que precisa dele é enviado como um
+/// comando e roda entre um frame e outro.
+pub struct EmulationCtx {
+    pub retro_core: RetroCoreIns,
+    sync: RetroSync,
+    audio: RetroAudioCb,
+    running: bool,
+    rewinding: bool,
+    // frames que ainda faltam rodar no frame advance
+    pending_frames: usize,
+    frame_count: u64,
+    window_listener: Arc<Box<dyn WindowListener>>,
+    game_dispatchers: GameInstanceDispatchers,
+}
+
+impl EmulationCtx {
+    pub fn set_running(&mut self, running: bool) {
+        self.running = running;
+    }
+
+    pub fn rewind(&mut self, active: bool) {
+        self.rewinding = active;
+    }
+
+    // o jogo continua pausado durante todo o processo, apenas os frames pedidos são rodados
+    pub fn frame_advance(&mut self, frames: usize) {
+        self.running = false;
+
+        if frames == 0 {
+            self.window_listener.frame_advanced(self.frame_count);
+            return;
+        }
+
+        self.pending_frames += frames;
+        self.retro_core.speed.set_frame_stepping(true);
+        self.update_speed();
+    }
+
+    pub fn stop_frame_stepping(&mut self) {
+        self.pending_frames = 0;
+        self.retro_core.speed.set_frame_stepping(false);
+        self.update_speed();
+    }
+
+    // o sync e o áudio precisam seguir a mesma velocidade, senão o buffer de áudio transborda
+    pub fn update_speed(&mut self) {
+        let ratio = self.retro_core.speed.ratio();
+
+        self.sync.set_speed(ratio);
+        self.audio.set_speed(ratio);
+    }
+
+    fn can_run_frames(&self) -> bool {
+        self.running || self.pending_frames > 0
+    }
+
+    fn run_frame(&mut self) -> Result<(), ErrorHandle> {
+        self.sync.prepare_sync(&self.retro_core.av_info)?;
+
+        if self.rewinding {
+            // sem mais snapshots o jogo apenas fica parado no ponto mais antigo
+            self.retro_core.rewind_frame()?;
+        } else {
+            self.retro_core.run_with_run_ahead()?;
+            self.retro_core.rewind_capture()?;
+            self.frame_count += 1;
+            self.notify_memory_watches();
+        }
+
+        self.autosave_save_ram();
+        self.sync.sync_now()?;
+
+        if self.pending_frames > 0 {
+            self.pending_frames -= 1;
+
+            if self.pending_frames == 0 {
+                self.stop_frame_stepping();
+                self.window_listener.frame_advanced(self.frame_count);
+            }
+        }
+
+        Ok(())
+    }
+
+    // uma falha ao gravar a SRAM não deve interromper o jogo, apenas avisar o listener
+    fn autosave_save_ram(&self) {
+        match self.retro_core.autosave_save_ram() {
+            Ok(Some(path)) => self.window_listener.save_ram_result(SaveRamInfo::Saved {
+                save_path: path.display().to_string(),
+            }),
+            Ok(None) => {}
+            Err(_) => self.window_listener.save_ram_result(SaveRamInfo::Failed),
+        }
+    }
+
+    fn notify_memory_watches(&self) {
+        match self.retro_core.check_memory_watches() {
+            Ok(events) => {
+                for event in events {
+                    self.window_listener
+                        .memory_watch_triggered(memory_watch_info(event));
+                }
+            }
+            Err(e) => logger::error(LogTarget::Frontend, format!("{e:?}")),
+        }
+    }
+
+    // o núcleo envia as mensagens durante o retro_run ou o load_game, elas só são entregues
+    // depois que a chamada termina
+    fn notify_core_messages(&self) {
+        let messages = match self.retro_core.messages.take_all() {
+            Ok(messages) => messages,
+            Err(e) => {
+                logger::error(LogTarget::Frontend, format!("{e:?}"));
+                return;
+            }
+        };
+
+        for message in messages {
+            self.window_listener.core_message(message_info(message));
+        }
+    }
+
+    #[doc = "envia as opções para o listener se elas mudaram, ou sempre quando `force` é true"]
+    pub fn notify_core_options(&self, force: bool) {
+        let options = &self.retro_core.options;
+
+        if !options.take_changed() && !force {
+            return;
+        }
+
+        match options
+            .list()
+            .and_then(|list| Ok((list, options.list_categories()?)))
+        {
+            Ok((list, categories)) => self.window_listener.core_options_changed(
+                list.into_iter().map(option_info).collect(),
+                categories.into_iter().map(category_info).collect(),
+            ),
+            Err(e) => logger::error(LogTarget::Frontend, format!("{e:?}")),
+        }
+    }
+
+    fn run_next_frame(&mut self) {
+        if let Err(e) = self.run_frame() {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+            self.running = false;
+            self.pending_frames = 0;
+            let _ = self.game_dispatchers.exit();
+        }
+    }
+
+    fn notify_changes(&self) {
+        self.notify_core_messages();
+        self.notify_core_options(false);
+    }
+
+    fn run(mut self, commands: Receiver<EmulationCommand>) {
+        loop {
+            // enquanto o jogo está parado não há motivo para ocupar a cpu, apenas espera
+            // pelo próximo comando
+            let command = if self.can_run_frames() {
+                match commands.try_recv() {
+                    Ok(command) => Some(command),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => break,
+                }
+            } else {
+                match commands.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => break,
+                }
+            };
+
+            match command {
+                Some(command) => command(&mut self),
+                None => self.run_next_frame(),
+            }
+
+            self.notify_changes();
+        }
+    }
+}
+
+fn message_info(message: CoreMessage) -> CoreMessageInfo {
+    CoreMessageInfo {
+        text: message.text,
+        duration_ms: message.duration_ms,
+        priority: message.priority,
+        level: match message.level {
+            message_manager::CoreMessageLevel::Debug => CoreMessageLevel::Debug,
+            message_manager::CoreMessageLevel::Info => CoreMessageLevel::Info,
+            message_manager::CoreMessageLevel::Warn => CoreMessageLevel::Warn,
+            message_manager::CoreMessageLevel::Error => CoreMessageLevel::Error,
+        },
+        target: match message.target {
+            message_manager::CoreMessageTarget::All => CoreMessageTarget::All,
+            message_manager::CoreMessageTarget::Osd => CoreMessageTarget::Osd,
+            message_manager::CoreMessageTarget::Log => CoreMessageTarget::Log,
+        },
+        kind: match message.kind {
+            message_manager::CoreMessageKind::Notification => CoreMessageKind::Notification,
+            message_manager::CoreMessageKind::NotificationAlt => CoreMessageKind::NotificationAlt,
+            message_manager::CoreMessageKind::Status => CoreMessageKind::Status,
+            message_manager::CoreMessageKind::Progress => CoreMessageKind::Progress,
+        },
+        progress: message.progress,
+    }
+}
+
+fn option_info(option: CoreOptInfo) -> CoreOptionInfo {
+    CoreOptionInfo {
+        key: option.key,
+        desc: option.desc,
+        desc_categorized: option.desc_categorized,
+        info: option.info,
+        category: option.category_key,
+        selected: option.selected,
+        default_value: option.default_value,
+        values: option
+            .values
+            .into_iter()
+            .map(|(value, label)| CoreOptionValueInfo { value, label })
+            .collect(),
+        visible: option.visible,
+    }
+}
+
+fn category_info(category: CategoryInfo) -> CoreOptionCategoryInfo {
+    CoreOptionCategoryInfo {
+        key: category.key,
+        desc: category.desc,
+        info: category.info,
+    }
+}
+
+/// Dono do [EmulationCtx].
+///
+/// O contexto começa na thread da janela, onde o jogo é carregado, e o [spawn](Self::spawn)
+/// move ele para a thread de emulação. Núcleos com renderização por hardware continuam na
+/// thread da janela, porque o contexto GL só é válido nela, e rodam pelo
+/// [run_inline_frame](Self::run_inline_frame).
+pub struct EmulationThread {
+    inline: Option<RefCell<EmulationCtx>>,
+    commands: Option<Sender<EmulationCommand>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EmulationThread {
+    pub fn new(
+        retro_core: RetroCoreIns,
+        audio: RetroAudioCb,
+        window_listener: Arc<Box<dyn WindowListener>>,
+        game_dispatchers: GameInstanceDispatchers,
+    ) -> Self {
+        let emulation_ctx = EmulationCtx {
+            retro_core,
+            sync: RetroSync::new(0.0002),
+            audio,
+            running: false,
+            rewinding: false,
+            pending_frames: 0,
+            frame_count: 0,
+            window_listener,
+            game_dispatchers,
+        };
+
+        Self {
+            inline: Some(RefCell::new(emulation_ctx)),
+            commands: None,
+            handle: None,
+        }
+    }
+
+    #[doc = "move o contexto para a thread de emulação, não faz nada se ele já foi movido"]
+    pub fn spawn(&mut self) -> Result<(), ErrorHandle> {
+        let emulation_ctx = match self.inline.take() {
+            Some(emulation_ctx) => emulation_ctx.into_inner(),
+            None => return Ok(()),
+        };
+
+        let (commands, receiver) = mpsc::channel();
+
+        let handle = thread::Builder::new()
+            .name("tinic_emulation".to_owned())
+            .spawn(move || emulation_ctx.run(receiver))?;
+
+        self.commands = Some(commands);
+        self.handle = Some(handle);
+
+        Ok(())
+    }
+
+    #[doc = "roda um frame na thread atual se o contexto não foi movido, retorna se o jogo está rodando"]
+    pub fn run_inline_frame(&self) -> Result<bool, ErrorHandle> {
+        let mut emulation_ctx = match self.inline_ctx()? {
+            Some(emulation_ctx) => emulation_ctx,
+            None => return Ok(false),
+        };
+
+        if !emulation_ctx.can_run_frames() {
+            return Ok(false);
+        }
+
+        emulation_ctx.run_next_frame();
+        emulation_ctx.notify_changes();
+
+        Ok(true)
+    }
+
+    fn inline_ctx(&self) -> Result<Option<RefMut<'_, EmulationCtx>>, ErrorHandle> {
+        self.inline
+            .as_ref()
+            .map(|emulation_ctx| emulation_ctx.try_borrow_mut())
+            .transpose()
+            .map_err(|e| ErrorHandle::new(&e.to_string()))
+    }
+
+    #[doc = "envia um comando para ser executado na thread de emulação sem esperar o resultado"]
+    pub fn send<F>(&self, command: F) -> Result<(), ErrorHandle>
+    where
+        F: FnOnce(&mut EmulationCtx) + Send + 'static,
+    {
+        // sem a thread de emulação o comando roda agora, entre um frame e outro
+        if let Some(mut emulation_ctx) = self.inline_ctx()? {
+            command(&mut emulation_ctx);
+            emulation_ctx.notify_changes();
+            return Ok(());
+        }
+
+        self.commands
+            .as_ref()
+            .and_then(|commands| commands.send(Box::new(command)).ok())
+            .ok_or_else(|| ErrorHandle::new("A thread de emulação já foi encerrada"))
+    }
+
+    #[doc = "executa o comando na thread de emulação e espera o resultado"]
+    pub fn call<T, F>(&self, command: F) -> Result<T, ErrorHandle>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut EmulationCtx) -> T + Send + 'static,
+    {
+        let (reply, response) = mpsc::channel();
+
+        self.send(move |emulation_ctx| {
+            let _ = reply.send(command(emulation_ctx));
+        })?;
+
+        response
+            .recv()
+            .map_err(|_| ErrorHandle::new("A thread de emulação foi encerrada antes de responder"))
+    }
+
+    pub fn try_call<T, F>(&self, command: F) -> Result<T, ErrorHandle>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut EmulationCtx) -> Result<T, ErrorHandle> + Send + 'static,
+    {
+        self.call(command)?
+    }
+}
+
+impl Drop for EmulationThread {
+    fn drop(&mut self) {
+        // sem o sender a thread sai do loop assim que terminar o frame atual
+        self.commands.take();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn memory_watch_info(event: MemoryWatchEvent) -> MemoryWatchInfo {
+    let address = match event.address {
+        MemoryAddress::Virtual(address) => MemoryAddressInfo::Virtual { address },
+        MemoryAddress::Region(region, offset) => MemoryAddressInfo::Region {
+            region: match region {
+                MemoryRegion::SaveRam => MemoryRegionInfo::SaveRam,
+                MemoryRegion::Rtc => MemoryRegionInfo::Rtc,
+                MemoryRegion::SystemRam => MemoryRegionInfo::SystemRam,
+                MemoryRegion::VideoRam => MemoryRegionInfo::VideoRam,
+            },
+            offset,
+        },
+    };
+
+    MemoryWatchInfo {
+        id: event.id,
+        address,
+        old: event.old,
+        new: event.new,
+    }
+}
@@ -22,11 +22,216 @@ pub type SaveImgPreview = String;
 pub enum SaveStateInfo {
     Susses {
         save_path: String,
-        save_img_preview: String,
+        #[doc = "None quando não foi possível gerar a miniatura, o save continua válido"]
+        save_img_preview: Option<String>,
     },
     Failed,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveRamInfo {
+    Saved { save_path: String },
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheatInfo {
+    pub index: usize,
+    pub desc: String,
+    pub code: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskInfo {
+    pub ejected: bool,
+    pub index: usize,
+    pub count: usize,
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputDescriptorInfo {
+    pub port: u32,
+    pub device: u32,
+    pub index: u32,
+    pub id: u32,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControllerTypeInfo {
+    pub id: u32,
+    pub desc: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControllerPortInfo {
+    pub port: u32,
+    pub selected: u32,
+    pub types: Vec<ControllerTypeInfo>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreMessageLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreMessageTarget {
+    All,
+    Osd,
+    Log,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreMessageKind {
+    Notification,
+    NotificationAlt,
+    Status,
+    Progress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreMessageInfo {
+    pub text: String,
+    pub duration_ms: u32,
+    pub priority: u32,
+    pub level: CoreMessageLevel,
+    pub target: CoreMessageTarget,
+    pub kind: CoreMessageKind,
+    pub progress: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreOptionValueInfo {
+    pub value: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreOptionInfo {
+    pub key: String,
+    pub desc: String,
+    #[doc = "descrição mais curta usada quando a opção aparece dentro da categoria"]
+    pub desc_categorized: String,
+    pub info: String,
+    #[doc = "vazio quando a opção não pertence a nenhuma categoria"]
+    pub category: String,
+    pub selected: String,
+    pub default_value: String,
+    pub values: Vec<CoreOptionValueInfo>,
+    pub visible: bool,
+}
+
+#[doc = "arquivo de opções que vale só para um jogo ou para a pasta do conteúdo"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreOptionOverride {
+    Game,
+    Directory,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreOptionCategoryInfo {
+    pub key: String,
+    pub desc: String,
+    pub info: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheatSearchWidth {
+    U8,
+    U16,
+    U32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CheatSearchFilter {
+    #[doc = "o valor não mudou desde a última busca"]
+    Equal,
+    Changed,
+    Increased,
+    Decreased,
+    Value {
+        value: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheatCandidateInfo {
+    #[doc = "posição dentro da SYSTEM_RAM, o mesmo valor usado para criar o cheat"]
+    pub offset: usize,
+    pub value: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryRegionInfo {
+    SaveRam,
+    Rtc,
+    SystemRam,
+    VideoRam,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MemoryAddressInfo {
+    #[doc = "endereço visto pela cpu emulada, como os usados em cheats e conquistas"]
+    Virtual { address: usize },
+    Region {
+        region: MemoryRegionInfo,
+        offset: usize,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryWatchInfo {
+    pub id: u32,
+    pub address: MemoryAddressInfo,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+}
+
+#[doc = "o slot auto é escrito ao fechar o jogo"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SaveStateSlot {
+    Auto,
+    Index { slot: usize },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveStateHeaderInfo {
+    pub library_name: String,
+    pub library_version: String,
+    pub rom_crc32: u32,
+    #[doc = "segundos desde UNIX_EPOCH"]
+    pub timestamp: u64,
+    #[doc = "tempo de jogo em segundos"]
+    pub playtime: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveStateSlotInfo {
+    pub slot: SaveStateSlot,
+    pub save_path: String,
+    pub save_img_preview: Option<String>,
+    #[doc = "None para states criados antes do cabeçalho existir"]
+    pub header: Option<SaveStateHeaderInfo>,
+    pub size: u64,
+    pub modified: u64,
+}
+
 pub trait WindowListener: Send + Sync {
     fn window_state_change(&self, state: WindowState);
 
@@ -37,4 +242,32 @@ pub trait WindowListener: Send + Sync {
     fn load_state_result(&self, suss: bool);
 
     fn keyboard_state(&self, has_using: bool);
+
+    fn save_ram_result(&self, info: SaveRamInfo);
+
+    fn cheats_changed(&self, cheats: Vec<CheatInfo>);
+
+    fn disk_state_changed(&self, info: DiskInfo);
+
+    fn frame_advanced(&self, frame_count: u64);
+
+    fn input_descriptors_changed(&self, descriptors: Vec<InputDescriptorInfo>);
+
+    fn controller_types_changed(&self, ports: Vec<ControllerPortInfo>);
+
+    fn core_message(&self, message: CoreMessageInfo);
+
+    fn core_options_changed(
+        &self,
+        options: Vec<CoreOptionInfo>,
+        categories: Vec<CoreOptionCategoryInfo>,
+    );
+
+    fn memory_read(&self, address: MemoryAddressInfo, data: Vec<u8>);
+
+    fn cheat_search_changed(&self, count: usize, candidates: Vec<CheatCandidateInfo>);
+
+    fn memory_watch_triggered(&self, watch: MemoryWatchInfo);
+
+    fn save_state_slots_changed(&self, slots: Vec<SaveStateSlotInfo>);
 }
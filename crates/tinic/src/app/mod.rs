@@ -1,3 +1,4 @@
+mod emulation_thread;
 pub mod listener;
 mod user_events;
 mod window_events;
@@ -7,10 +8,13 @@ use crate::app::listener::WindowListener;
 use crate::app_dispatcher::{GameInstanceActions, GameInstanceDispatchers};
 use crate::TinicGameInfo;
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 use retro_controllers::RetroController;
 use std::sync::Arc;
 use winit::{
-    application::ApplicationHandler, event::WindowEvent, event_loop::ActiveEventLoop,
+    application::ApplicationHandler,
+    event::WindowEvent,
+    event_loop::{ActiveEventLoop, ControlFlow},
     window::WindowId,
 };
 use crate::app::tinic_app_ctx::TinicGameCtx;
@@ -29,7 +33,12 @@ impl GameInstance {
         window_listener: Arc<Box<dyn WindowListener>>,
         game_dispatchers: GameInstanceDispatchers,
     ) -> Result<Self, ErrorHandle> {
-        let ctx = TinicGameCtx::new(game_info, retro_controle, window_listener.clone())?;
+        let ctx = TinicGameCtx::new(
+            game_info,
+            retro_controle,
+            window_listener.clone(),
+            game_dispatchers.clone(),
+        )?;
 
         Ok(Self {
             ctx,
@@ -43,8 +52,8 @@ impl GameInstance {
         self.game_dispatchers.clone()
     }
 
-    fn destroy_window_and_render_context(&self, event_loop: &ActiveEventLoop, ctx: &TinicGameCtx) {
-        let _ = ctx.destroy_retro_ctx();
+    fn destroy_window_and_render_context(&mut self, event_loop: &ActiveEventLoop) {
+        let _ = self.ctx.destroy_retro_ctx();
         event_loop.exit();
     }
 }
@@ -52,7 +61,7 @@ impl GameInstance {
 impl ApplicationHandler<GameInstanceActions> for GameInstance {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if let Err(e) = self.ctx.create_window(event_loop) {
-            println!("{:?}", e);
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
             event_loop.exit();
         }
     }
@@ -66,10 +75,27 @@ impl ApplicationHandler<GameInstanceActions> for GameInstance {
     }
 
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        if let Err(e) = self.ctx.redraw_request() {
-            println!("{:?}", e);
-            self.destroy_window_and_render_context(event_loop, &self.ctx);
-        }
+        let result = self.ctx.run_inline_frame().and_then(|running| {
+            self.ctx.redraw_request()?;
+            Ok(running)
+        });
+
+        let running = match result {
+            Ok(running) => running,
+            Err(e) => {
+                logger::error(LogTarget::Frontend, format!("{e:?}"));
+                self.destroy_window_and_render_context(event_loop);
+                return;
+            }
+        };
+
+        // a thread de emulação acorda o event loop com um NewFrame a cada frame, só núcleos
+        // que rodam nesta thread precisam que o loop continue girando
+        event_loop.set_control_flow(if running {
+            ControlFlow::Poll
+        } else {
+            ControlFlow::Wait
+        });
     }
 
     fn suspended(&mut self, _: &ActiveEventLoop) {
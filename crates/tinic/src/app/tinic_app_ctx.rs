@@ -1,14 +1,32 @@
-use crate::app::listener::{GameState, WindowState};
+use crate::app::emulation_thread::EmulationThread;
+use crate::app::listener::{
+    CheatCandidateInfo, CheatInfo, CheatSearchFilter, CheatSearchWidth, ControllerPortInfo,
+    ControllerTypeInfo, CoreOptionOverride, DiskInfo, GameState, InputDescriptorInfo,
+    MemoryAddressInfo, MemoryRegionInfo, SaveStateHeaderInfo, SaveStateSlot, SaveStateSlotInfo,
+    WindowState,
+};
+use crate::app_dispatcher::GameInstanceDispatchers;
 use crate::{SaveStateInfo, TinicGameInfo, WindowListener};
+use generics::constants::{MAX_REPORTED_CHEAT_CANDIDATES, SAVE_IMAGE_EXTENSION_FILE};
+use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 use generics::retro_paths::RetroPaths;
-use generics::{constants::SAVE_IMAGE_EXTENSION_FILE, error_handle::ErrorHandle};
-use libretro_sys::binding_libretro::retro_hw_context_type;
+use libretro_sys::binding_libretro::{RETRO_DEVICE_JOYPAD, retro_hw_context_type};
 use retro_audio::RetroAudio;
 use retro_controllers::{RetroController, RetroGamePad};
-use retro_core::{RetroCore, RetroCoreIns, RetroEnvCallbacks, graphic_api::GraphicApi};
+use retro_core::av_info::AvInfo;
+use retro_core::cheat_finder_manager::SearchFilter;
+use retro_core::disk_control_manager::DiskState;
+use retro_core::memory_manager::{MemoryAddress, MemoryRegion, ValueWidth};
+use retro_core::option_manager::OptionOverride;
+use retro_core::save_state_manager::SaveSlot;
+use retro_core::{RetroCore, RetroEnvCallbacks, graphic_api::GraphicApi};
 use retro_video::RetroVideo;
+use std::collections::BTreeMap;
+use std::fs;
 use std::path::PathBuf;
-use std::{path::Path, sync::Arc};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use winit::dpi::PhysicalSize;
 use winit::event_loop::ActiveEventLoop;
 use winit::keyboard::PhysicalKey;
@@ -16,9 +34,13 @@ use winit::keyboard::PhysicalKey;
 pub struct TinicGameCtx {
     retro_video: RetroVideo,
     retro_audio: RetroAudio,
-    retro_core: RetroCoreIns,
+    // o núcleo roda na thread de emulação, aqui fica só o que a janela precisa para desenhar
+    emulation: EmulationThread,
+    av_info: Arc<AvInfo>,
     can_request_new_frames: bool,
     rom_path: String,
+    subsystem: Option<String>,
+    extra_roms: Vec<String>,
     pub controller: Arc<RetroController>,
     window_listener: Arc<Box<dyn WindowListener>>,
 }
@@ -28,13 +50,19 @@ impl TinicGameCtx {
         game_info: TinicGameInfo,
         controller: Arc<RetroController>,
         window_listener: Arc<Box<dyn WindowListener>>,
+        game_dispatchers: GameInstanceDispatchers,
     ) -> Result<Self, ErrorHandle> {
-        let retro_video = RetroVideo::default();
+        let mut retro_video = RetroVideo::default();
         let retro_audio = RetroAudio::new()?;
 
+        let frame_dispatchers = game_dispatchers.clone();
+        let on_new_frame = move || {
+            let _ = frame_dispatchers.new_frame();
+        };
+
         let callbacks = RetroEnvCallbacks {
             audio: Box::new(retro_audio.get_core_cb()),
-            video: Box::new(retro_video.get_core_cb()),
+            video: Box::new(retro_video.get_core_cb(on_new_frame)?),
             controller: Box::new(controller.get_core_cb()),
         };
 
@@ -43,6 +71,7 @@ impl TinicGameCtx {
         let retro_core = RetroCore::new(
             &game_info.core.into(),
             paths,
+            game_info.settings,
             callbacks,
             GraphicApi::with(retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE),
         )?;
@@ -51,19 +80,30 @@ impl TinicGameCtx {
 
         if gamepads.len().eq(&0) {
             let keyboard = controller.active_keyboard();
-            retro_core.connect_controller(keyboard.retro_port, keyboard.retro_type)?;
+            connect_device(&retro_core, keyboard.retro_port, keyboard.retro_type)?;
         } else {
             for gamepad in gamepads {
-                retro_core.connect_controller(gamepad.retro_port, gamepad.retro_type)?;
+                connect_device(&retro_core, gamepad.retro_port, gamepad.retro_type)?;
             }
         }
 
+        let av_info = retro_core.av_info.clone();
+        let emulation = EmulationThread::new(
+            retro_core,
+            retro_audio.get_core_cb(),
+            window_listener.clone(),
+            game_dispatchers,
+        );
+
         Ok(Self {
             retro_video,
             retro_audio,
-            retro_core,
+            emulation,
+            av_info,
             controller,
             rom_path: game_info.rom,
+            subsystem: game_info.subsystem,
+            extra_roms: game_info.extra_roms,
             window_listener,
             can_request_new_frames: true,
         })
@@ -91,8 +131,14 @@ impl TinicGameCtx {
         let keyboard = self.controller.active_keyboard();
         self.window_listener.keyboard_state(true);
 
-        self.retro_core
-            .connect_controller(keyboard.retro_port, keyboard.retro_type)
+        self.emulation.try_call(move |emulation_ctx| {
+            connect_device(
+                &emulation_ctx.retro_core,
+                keyboard.retro_port,
+                keyboard.retro_type,
+            )
+        })?;
+        self.notify_input_descriptors()
     }
 
     pub fn update_keyboard_state(&self, native: PhysicalKey, pressed: bool) {
@@ -106,22 +152,45 @@ impl TinicGameCtx {
         };
 
         self.retro_video
-            .create_window(&self.retro_core.av_info, event_loop)
+            .create_window(&self.av_info, event_loop)
             .map_err(err_handle)?;
 
-        self.retro_core
-            .load_game(&self.rom_path)
+        let rom_path = self.rom_path.clone();
+        let subsystem = self.subsystem.clone();
+        let extra_roms = self.extra_roms.clone();
+
+        self.emulation
+            .try_call(move |emulation_ctx| match subsystem {
+                Some(subsystem) => {
+                    let mut paths = vec![rom_path];
+                    paths.extend(extra_roms);
+
+                    emulation_ctx
+                        .retro_core
+                        .load_game_special(&subsystem, paths)
+                }
+                None => emulation_ctx.retro_core.load_game(&rom_path),
+            })
             .map_err(err_handle)?;
 
-        // se o contexto de desenho não for criado pelo core após o load_game,
-        // é necessário criá-lo manualmente!
+        // o núcleo não tem acesso ao contexto de desenho, ele sempre é criado aqui
         if !self.retro_video.draw_context_as_initialized() {
             self.retro_video.create_draw_context().map_err(err_handle)?;
         }
 
-        self.retro_audio
-            .init(&self.retro_core.av_info)
-            .map_err(err_handle)?;
+        // o contexto GL só é válido nesta thread, então núcleos com renderização por hardware
+        // continuam rodando nela
+        if !self
+            .av_info
+            .video
+            .graphic_api
+            .hw_render
+            .load(Ordering::SeqCst)
+        {
+            self.emulation.spawn().map_err(err_handle)?;
+        }
+
+        self.retro_audio.init(&self.av_info).map_err(err_handle)?;
 
         // essa thread é responsável por verificar o estado atual dos inputs dos controles,
         // de agora em diante o core fará requisições manuais para verificar os inputs,
@@ -130,11 +199,30 @@ impl TinicGameCtx {
         self.window_listener.game_state_change(GameState::Running);
         self.window_listener
             .window_state_change(WindowState::Opened);
+        self.notify_cheats()?;
+        self.notify_input_descriptors()?;
+        self.notify_controller_types()?;
+
+        let disk_state = self.emulation.call(|emulation_ctx| {
+            let retro_core = &emulation_ctx.retro_core;
+            retro_core
+                .disk_control
+                .is_available()
+                .then(|| retro_core.get_disk_state())
+        })?;
+
+        if let Some(disk_state) = disk_state {
+            self.notify_disk_state(disk_state);
+        }
 
-        Ok(())
+        self.emulation
+            .send(|emulation_ctx| emulation_ctx.set_running(true))
     }
 
     pub fn suspend_window(&mut self) {
+        let _ = self
+            .emulation
+            .send(|emulation_ctx| emulation_ctx.set_running(false));
         self.retro_video.destroy_window();
         self.retro_audio.stop();
         self.controller.resume_thread_events();
@@ -143,8 +231,11 @@ impl TinicGameCtx {
             .window_state_change(WindowState::Closed);
     }
 
-    pub fn destroy_retro_ctx(&self) -> Result<(), ErrorHandle> {
-        self.retro_core.de_init()?;
+    pub fn destroy_retro_ctx(&mut self) -> Result<(), ErrorHandle> {
+        self.emulation.try_call(|emulation_ctx| {
+            emulation_ctx.set_running(false);
+            emulation_ctx.retro_core.de_init()
+        })?;
         self.retro_audio.stop();
         self.controller.resume_thread_events();
         self.retro_video.destroy_window();
@@ -156,28 +247,393 @@ impl TinicGameCtx {
         Ok(())
     }
 
+    #[doc = "roda o próximo frame quando o núcleo está na thread da janela, retorna se o jogo está rodando"]
+    pub fn run_inline_frame(&self) -> Result<bool, ErrorHandle> {
+        self.emulation.run_inline_frame()
+    }
+
     pub fn redraw_request(&self) -> Result<(), ErrorHandle> {
         self.retro_video.request_redraw()
     }
 
     pub fn draw_new_frame(&mut self) -> Result<(), ErrorHandle> {
-        if !self.can_request_new_frames {
-            return Ok(());
+        self.retro_video.draw_new_frame()
+    }
+
+    pub fn frame_advance(&mut self, frames: usize) -> Result<(), ErrorHandle> {
+        if self.can_request_new_frames {
+            self.pause()?;
         }
 
-        self.retro_video
-            .sync
-            .prepare_sync(&self.retro_core.av_info)?;
-        self.retro_core.run()?;
-        self.retro_video.sync.sync_now()?;
+        self.emulation
+            .send(move |emulation_ctx| emulation_ctx.frame_advance(frames))
+    }
+
+    pub fn rewind(&self, active: bool) -> Result<(), ErrorHandle> {
+        self.emulation
+            .send(move |emulation_ctx| emulation_ctx.rewind(active))
+    }
+
+    pub fn fast_forward(&self, active: bool) -> Result<(), ErrorHandle> {
+        self.emulation.send(move |emulation_ctx| {
+            emulation_ctx.retro_core.speed.set_fast_forward(active);
+            emulation_ctx.update_speed();
+        })
+    }
+
+    pub fn toggle_fast_forward(&self) -> Result<(), ErrorHandle> {
+        self.emulation.send(|emulation_ctx| {
+            let speed = &emulation_ctx.retro_core.speed;
+            speed.set_fast_forward(!speed.is_fast_forwarding());
+            emulation_ctx.update_speed();
+        })
+    }
+
+    pub fn slow_motion(&self, active: bool) -> Result<(), ErrorHandle> {
+        self.emulation.send(move |emulation_ctx| {
+            emulation_ctx.retro_core.speed.set_slow_motion(active);
+            emulation_ctx.update_speed();
+        })
+    }
+
+    pub fn set_fast_forward_ratio(&self, ratio: f64) -> Result<(), ErrorHandle> {
+        self.emulation.send(move |emulation_ctx| {
+            emulation_ctx.retro_core.speed.set_fast_forward_ratio(ratio);
+            emulation_ctx.update_speed();
+        })
+    }
+
+    pub fn set_slow_motion_ratio(&self, ratio: f64) -> Result<(), ErrorHandle> {
+        self.emulation.send(move |emulation_ctx| {
+            emulation_ctx.retro_core.speed.set_slow_motion_ratio(ratio);
+            emulation_ctx.update_speed();
+        })
+    }
+
+    pub fn set_cheat_enabled(&self, index: usize, enabled: bool) -> Result<(), ErrorHandle> {
+        self.emulation.try_call(move |emulation_ctx| {
+            emulation_ctx.retro_core.set_cheat_enabled(index, enabled)
+        })?;
+        self.notify_cheats()
+    }
+
+    pub fn start_cheat_search(&self, width: CheatSearchWidth, big_endian: bool) {
+        let width = match width {
+            CheatSearchWidth::U8 => ValueWidth::U8,
+            CheatSearchWidth::U16 => ValueWidth::U16,
+            CheatSearchWidth::U32 => ValueWidth::U32,
+        };
+
+        let result = self
+            .emulation
+            .try_call(move |emulation_ctx| {
+                emulation_ctx
+                    .retro_core
+                    .start_cheat_search(width, big_endian)
+            })
+            .and_then(|_| self.notify_cheat_search());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn filter_cheat_search(&self, filter: CheatSearchFilter) {
+        let filter = match filter {
+            CheatSearchFilter::Equal => SearchFilter::Equal,
+            CheatSearchFilter::Changed => SearchFilter::Changed,
+            CheatSearchFilter::Increased => SearchFilter::Increased,
+            CheatSearchFilter::Decreased => SearchFilter::Decreased,
+            CheatSearchFilter::Value { value } => SearchFilter::Value(value),
+        };
+
+        let result = self
+            .emulation
+            .try_call(move |emulation_ctx| emulation_ctx.retro_core.filter_cheat_search(filter))
+            .and_then(|_| self.notify_cheat_search());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn add_cheat_from_search(&self, offset: usize, value: u32, desc: String) {
+        let result = self
+            .emulation
+            .try_call(move |emulation_ctx| {
+                emulation_ctx
+                    .retro_core
+                    .add_cheat_from_search(offset, value, &desc)
+            })
+            .and_then(|_| self.notify_cheats());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    fn notify_cheat_search(&self) -> Result<(), ErrorHandle> {
+        let (count, candidates) = self.emulation.try_call(|emulation_ctx| {
+            let finder = &emulation_ctx.retro_core.cheat_finder;
+
+            Ok((
+                finder.count()?,
+                finder.candidates(MAX_REPORTED_CHEAT_CANDIDATES)?,
+            ))
+        })?;
+
+        let candidates = candidates
+            .into_iter()
+            .map(|candidate| CheatCandidateInfo {
+                offset: candidate.offset,
+                value: candidate.value,
+            })
+            .collect();
+
+        self.window_listener.cheat_search_changed(count, candidates);
+        Ok(())
+    }
+
+    fn notify_cheats(&self) -> Result<(), ErrorHandle> {
+        let cheats = self
+            .emulation
+            .try_call(|emulation_ctx| emulation_ctx.retro_core.cheats.get_cheats())?
+            .into_iter()
+            .enumerate()
+            .map(|(index, cheat)| CheatInfo {
+                index,
+                desc: cheat.desc,
+                code: cheat.code,
+                enabled: cheat.enabled,
+            })
+            .collect();
+
+        self.window_listener.cheats_changed(cheats);
+        Ok(())
+    }
+
+    // o núcleo pode trocar os descritores ao carregar o jogo ou ao trocar o controle de uma porta
+    fn notify_input_descriptors(&self) -> Result<(), ErrorHandle> {
+        let descriptors = self
+            .emulation
+            .try_call(|emulation_ctx| {
+                Ok(emulation_ctx
+                    .retro_core
+                    .system
+                    .input_descriptors
+                    .read()?
+                    .clone())
+            })?
+            .into_iter()
+            .map(|descriptor| InputDescriptorInfo {
+                port: descriptor.port,
+                device: descriptor.device,
+                index: descriptor.index,
+                id: descriptor.id,
+                description: descriptor.description,
+            })
+            .collect();
+
+        self.window_listener.input_descriptors_changed(descriptors);
+        Ok(())
+    }
+
+    // um valor inválido não deve fechar o jogo
+    pub fn set_core_option(&self, key: String, value: String) {
+        let result = self.emulation.try_call(move |emulation_ctx| {
+            emulation_ctx.retro_core.options.update_opt(&key, &value)
+        });
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn report_core_options(&self) -> Result<(), ErrorHandle> {
+        self.emulation
+            .send(|emulation_ctx| emulation_ctx.notify_core_options(true))
+    }
+
+    pub fn save_core_option_override(&self, scope: CoreOptionOverride) {
+        let result = self.emulation.try_call(move |emulation_ctx| {
+            emulation_ctx
+                .retro_core
+                .options
+                .save_override(option_override(scope))
+        });
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn clear_core_option_override(&self, scope: CoreOptionOverride) {
+        let result = self.emulation.try_call(move |emulation_ctx| {
+            emulation_ctx
+                .retro_core
+                .options
+                .clear_override(option_override(scope))
+        });
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    // endereços fora do mapa são erros da ferramenta que pediu, não do jogo
+    pub fn read_memory(&self, address: MemoryAddressInfo, len: usize) {
+        let result = self.emulation.try_call(move |emulation_ctx| {
+            emulation_ctx
+                .retro_core
+                .read_memory(memory_address(address), len)
+        });
+
+        match result {
+            Ok(data) => self.window_listener.memory_read(address, data),
+            Err(e) => logger::error(LogTarget::Frontend, format!("{e:?}")),
+        }
+    }
+
+    pub fn write_memory(&self, address: MemoryAddressInfo, data: Vec<u8>) {
+        let result = self.emulation.try_call(move |emulation_ctx| {
+            emulation_ctx
+                .retro_core
+                .write_memory(memory_address(address), &data)
+        });
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn add_memory_watch(&self, id: u32, address: MemoryAddressInfo, len: usize) {
+        let result = self.emulation.try_call(move |emulation_ctx| {
+            emulation_ctx
+                .retro_core
+                .memory
+                .add_watch(id, memory_address(address), len)
+        });
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn remove_memory_watch(&self, id: u32) {
+        let result = self
+            .emulation
+            .try_call(move |emulation_ctx| emulation_ctx.retro_core.memory.remove_watch(id));
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    // um tipo recusado pelo núcleo não deve encerrar o jogo
+    pub fn set_controller_type(&self, port: u32, device: u32) {
+        let result = i16::try_from(port)
+            .map_err(|_| ErrorHandle::new(&format!("Porta {port} inválida")))
+            .and_then(|port| {
+                self.emulation.try_call(move |emulation_ctx| {
+                    emulation_ctx.retro_core.set_controller_type(port, device)
+                })
+            })
+            .and_then(|_| self.notify_input_descriptors())
+            .and_then(|_| self.notify_controller_types());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    fn notify_controller_types(&self) -> Result<(), ErrorHandle> {
+        let ports = self.emulation.try_call(|emulation_ctx| {
+            let retro_core = &emulation_ctx.retro_core;
+            let mut ports: BTreeMap<u32, Vec<ControllerTypeInfo>> = BTreeMap::new();
+
+            for description in &*retro_core.system.ports.read()? {
+                ports
+                    .entry(*description.port)
+                    .or_default()
+                    .push(ControllerTypeInfo {
+                        id: *description.id,
+                        desc: description.desc.to_string(),
+                    });
+            }
+
+            ports
+                .into_iter()
+                .map(|(port, types)| {
+                    Ok(ControllerPortInfo {
+                        port,
+                        selected: retro_core.get_controller_type(port, RETRO_DEVICE_JOYPAD)?,
+                        types,
+                    })
+                })
+                .collect::<Result<Vec<_>, ErrorHandle>>()
+        })?;
+
+        self.window_listener.controller_types_changed(ports);
         Ok(())
     }
 
+    pub fn set_disc_ejected(&self, ejected: bool) {
+        self.notify_disk_state(
+            self.emulation
+                .try_call(move |emulation_ctx| emulation_ctx.retro_core.set_disk_ejected(ejected)),
+        );
+    }
+
+    pub fn select_disc(&self, index: usize) {
+        self.notify_disk_state(
+            self.emulation
+                .try_call(move |emulation_ctx| emulation_ctx.retro_core.select_disk(index)),
+        );
+    }
+
+    // o núcleo pode recusar a troca de disco, isso não deve encerrar o jogo
+    fn notify_disk_state(&self, state: Result<DiskState, ErrorHandle>) {
+        match state {
+            Ok(state) => self.window_listener.disk_state_changed(DiskInfo {
+                ejected: state.ejected,
+                index: state.index,
+                count: state.count,
+                labels: state.labels,
+            }),
+            Err(e) => logger::error(LogTarget::Frontend, format!("{e:?}")),
+        }
+    }
+
+    pub fn set_run_ahead(&self, frames: usize) -> Result<(), ErrorHandle> {
+        self.emulation
+            .send(move |emulation_ctx| emulation_ctx.retro_core.run_ahead.set_frames(frames))
+    }
+
+    pub fn set_rewind_enabled(&self, enabled: bool) -> Result<(), ErrorHandle> {
+        self.emulation
+            .try_call(move |emulation_ctx| emulation_ctx.retro_core.rewind.set_enabled(enabled))
+    }
+
+    pub fn set_rewind_interval(&self, frames: usize) -> Result<(), ErrorHandle> {
+        self.emulation
+            .send(move |emulation_ctx| emulation_ctx.retro_core.rewind.set_interval(frames))
+    }
+
+    pub fn set_rewind_buffer_size(&self, size_mb: usize) -> Result<(), ErrorHandle> {
+        self.emulation.send(move |emulation_ctx| {
+            emulation_ctx
+                .retro_core
+                .rewind
+                .set_max_buffer_size(size_mb.saturating_mul(1024 * 1024))
+        })
+    }
+
     pub fn reset(&self) -> Result<(), ErrorHandle> {
-        self.retro_core.reset()
+        self.emulation
+            .try_call(|emulation_ctx| emulation_ctx.retro_core.reset())
     }
 
-    pub fn save_state(&self, slot: usize) -> Result<(), ErrorHandle> {
+    pub fn save_state(&mut self, slot: usize) -> Result<(), ErrorHandle> {
         // Erros handles
         let err_handle = |e: ErrorHandle| {
             self.window_listener
@@ -197,19 +653,40 @@ impl TinicGameCtx {
         };
         // =========================================================
 
-        let save_path = self.retro_core.save_state(slot).map_err(err_handle)?;
+        // o print é feito a partir do último frame entregue pela thread de emulação
+        let thumbnail = self.retro_video.print_screen_png(&self.av_info);
+        let state_thumbnail = thumbnail.as_ref().ok().cloned();
+
+        let save_path = self
+            .emulation
+            .try_call(move |emulation_ctx| {
+                emulation_ctx
+                    .retro_core
+                    .save_state(slot, state_thumbnail.as_deref())
+            })
+            .map_err(err_handle)?;
 
         let mut img_path = save_path.clone();
         img_path.set_extension(SAVE_IMAGE_EXTENSION_FILE);
 
-        if self.print_screen(&img_path).is_err() {
-            self.window_listener
-                .save_state_result(SaveStateInfo::Failed);
-            return Ok(());
-        }
+        // a miniatura também fica ao lado do save para quem só lê a imagem, sem ela o
+        // save continua válido
+        let save_img_preview = match thumbnail.and_then(|png| Ok(fs::write(&img_path, png)?)) {
+            Ok(()) => Some(file_err_handle(img_path)?),
+            Err(e) => {
+                logger::warn(
+                    LogTarget::Frontend,
+                    format!("O save state foi criado sem a miniatura: {e:?}"),
+                );
+
+                // a imagem do save anterior desse slot não corresponde mais ao state
+                let _ = fs::remove_file(&img_path);
+
+                None
+            }
+        };
 
         let save_path = file_err_handle(save_path)?;
-        let save_img_preview = file_err_handle(img_path)?;
 
         self.window_listener
             .save_state_result(SaveStateInfo::Susses {
@@ -221,7 +698,18 @@ impl TinicGameCtx {
     }
 
     pub fn load_state(&self, slot: usize) -> Result<(), ErrorHandle> {
-        match self.retro_core.load_state(slot) {
+        self.load_state_slot(SaveSlot::Index(slot))
+    }
+
+    pub fn load_auto_state(&self) -> Result<(), ErrorHandle> {
+        self.load_state_slot(SaveSlot::Auto)
+    }
+
+    fn load_state_slot(&self, slot: SaveSlot) -> Result<(), ErrorHandle> {
+        match self
+            .emulation
+            .try_call(move |emulation_ctx| emulation_ctx.retro_core.load_state_slot(slot))
+        {
             Ok(_) => {
                 self.window_listener.load_state_result(true);
                 Ok(())
@@ -233,9 +721,103 @@ impl TinicGameCtx {
         }
     }
 
-    pub fn print_screen(&self, out_path: &Path) -> Result<(), ErrorHandle> {
-        self.retro_video
-            .print_screen(out_path, &self.retro_core.av_info)
+    pub fn list_save_states(&self) {
+        if let Err(e) = self.notify_save_state_slots() {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn delete_save_state(&self, slot: SaveStateSlot) {
+        let slot = save_slot(slot);
+
+        let result = self
+            .emulation
+            .try_call(move |emulation_ctx| emulation_ctx.retro_core.save_states.delete(slot))
+            .and_then(|_| self.notify_save_state_slots());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn copy_save_state(&self, from: SaveStateSlot, to: SaveStateSlot) {
+        let (from, to) = (save_slot(from), save_slot(to));
+
+        let result = self
+            .emulation
+            .try_call(move |emulation_ctx| emulation_ctx.retro_core.save_states.copy(from, to))
+            .and_then(|_| self.notify_save_state_slots());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn rename_save_state(&self, from: SaveStateSlot, to: SaveStateSlot) {
+        let (from, to) = (save_slot(from), save_slot(to));
+
+        let result = self
+            .emulation
+            .try_call(move |emulation_ctx| emulation_ctx.retro_core.save_states.rename(from, to))
+            .and_then(|_| self.notify_save_state_slots());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn undo_save_state(&self) {
+        let result = self
+            .emulation
+            .try_call(|emulation_ctx| emulation_ctx.retro_core.undo_save_state())
+            .and_then(|_| self.notify_save_state_slots());
+
+        if let Err(e) = result {
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
+        }
+    }
+
+    pub fn undo_load_state(&self) {
+        match self
+            .emulation
+            .try_call(|emulation_ctx| emulation_ctx.retro_core.undo_load_state())
+        {
+            Ok(restored) => self.window_listener.load_state_result(restored),
+            Err(e) => {
+                self.window_listener.load_state_result(false);
+                logger::error(LogTarget::Frontend, format!("{e:?}"));
+            }
+        }
+    }
+
+    fn notify_save_state_slots(&self) -> Result<(), ErrorHandle> {
+        let slots = self
+            .emulation
+            .try_call(|emulation_ctx| emulation_ctx.retro_core.save_states.list())?
+            .into_iter()
+            .map(|info| SaveStateSlotInfo {
+                slot: match info.slot {
+                    SaveSlot::Auto => SaveStateSlot::Auto,
+                    SaveSlot::Index(slot) => SaveStateSlot::Index { slot },
+                },
+                save_path: info.path.to_string_lossy().to_string(),
+                save_img_preview: info
+                    .preview_path
+                    .map(|path| path.to_string_lossy().to_string()),
+                header: info.header.map(|header| SaveStateHeaderInfo {
+                    library_name: header.library_name,
+                    library_version: header.library_version,
+                    rom_crc32: header.rom_crc32,
+                    timestamp: header.timestamp,
+                    playtime: header.playtime,
+                }),
+                size: info.size,
+                modified: info.modified,
+            })
+            .collect();
+
+        self.window_listener.save_state_slots_changed(slots);
+        Ok(())
     }
 
     pub fn toggle_full_screen_mode(&mut self) -> Result<(), ErrorHandle> {
@@ -251,6 +833,8 @@ impl TinicGameCtx {
     }
 
     pub fn pause(&mut self) -> Result<(), ErrorHandle> {
+        self.emulation
+            .send(|emulation_ctx| emulation_ctx.set_running(false))?;
         self.controller.resume_thread_events();
         self.can_request_new_frames = false;
         self.retro_audio.pause()?;
@@ -259,6 +843,10 @@ impl TinicGameCtx {
     }
 
     pub fn resume(&mut self) -> Result<(), ErrorHandle> {
+        self.emulation.send(|emulation_ctx| {
+            emulation_ctx.stop_frame_stepping();
+            emulation_ctx.set_running(true);
+        })?;
         self.controller.stop_thread_events();
         self.can_request_new_frames = true;
         self.retro_audio.play()?;
@@ -267,7 +855,53 @@ impl TinicGameCtx {
     }
 
     pub fn connect_controller(&self, device: RetroGamePad) -> Result<(), ErrorHandle> {
-        self.retro_core
-            .connect_controller(device.retro_port, device.retro_type)
+        self.emulation.try_call(move |emulation_ctx| {
+            connect_device(
+                &emulation_ctx.retro_core,
+                device.retro_port,
+                device.retro_type,
+            )
+        })?;
+        self.notify_input_descriptors()
+    }
+}
+
+// o tipo escolhido pelo usuário para a porta tem prioridade sobre o tipo padrão do device
+fn connect_device(retro_core: &RetroCore, port: i16, default_type: u32) -> Result<(), ErrorHandle> {
+    let controller_type = match u32::try_from(port) {
+        Ok(port) => retro_core.get_controller_type(port, default_type)?,
+        Err(_) => default_type,
+    };
+
+    retro_core.connect_controller(port, controller_type)
+}
+
+fn save_slot(slot: SaveStateSlot) -> SaveSlot {
+    match slot {
+        SaveStateSlot::Auto => SaveSlot::Auto,
+        SaveStateSlot::Index { slot } => SaveSlot::Index(slot),
+    }
+}
+
+fn option_override(scope: CoreOptionOverride) -> OptionOverride {
+    match scope {
+        CoreOptionOverride::Game => OptionOverride::Game,
+        CoreOptionOverride::Directory => OptionOverride::Directory,
+    }
+}
+
+fn memory_address(address: MemoryAddressInfo) -> MemoryAddress {
+    match address {
+        MemoryAddressInfo::Virtual { address } => MemoryAddress::Virtual(address),
+        MemoryAddressInfo::Region { region, offset } => {
+            let region = match region {
+                MemoryRegionInfo::SaveRam => MemoryRegion::SaveRam,
+                MemoryRegionInfo::Rtc => MemoryRegion::Rtc,
+                MemoryRegionInfo::SystemRam => MemoryRegion::SystemRam,
+                MemoryRegionInfo::VideoRam => MemoryRegion::VideoRam,
+            };
+
+            MemoryAddress::Region(region, offset)
+        }
     }
 }
@@ -1,5 +1,6 @@
 use crate::app::GameInstance;
 use crate::app_dispatcher::GameInstanceActions;
+use generics::logger::{self, LogTarget};
 use winit::event_loop::ActiveEventLoop;
 
 impl GameInstance {
@@ -12,6 +13,31 @@ impl GameInstance {
             GameInstanceActions::ConnectDevice(device) => self.ctx.connect_controller(device),
             GameInstanceActions::LoadState(slot) => self.ctx.load_state(slot),
             GameInstanceActions::SaveState(slot) => self.ctx.save_state(slot),
+            GameInstanceActions::LoadAutoState => self.ctx.load_auto_state(),
+            GameInstanceActions::ListSaveStates => {
+                self.ctx.list_save_states();
+                Ok(())
+            }
+            GameInstanceActions::DeleteSaveState(slot) => {
+                self.ctx.delete_save_state(slot);
+                Ok(())
+            }
+            GameInstanceActions::CopySaveState(from, to) => {
+                self.ctx.copy_save_state(from, to);
+                Ok(())
+            }
+            GameInstanceActions::RenameSaveState(from, to) => {
+                self.ctx.rename_save_state(from, to);
+                Ok(())
+            }
+            GameInstanceActions::UndoSaveState => {
+                self.ctx.undo_save_state();
+                Ok(())
+            }
+            GameInstanceActions::UndoLoadState => {
+                self.ctx.undo_load_state();
+                Ok(())
+            }
             GameInstanceActions::ChangeDefaultSlot(slot) => {
                 self.default_slot = slot;
                 Ok(())
@@ -21,17 +47,91 @@ impl GameInstance {
                 self.ctx.disable_keyboard();
                 Ok(())
             }
+            GameInstanceActions::EnableCheat(index) => self.ctx.set_cheat_enabled(index, true),
+            GameInstanceActions::DisableCheat(index) => self.ctx.set_cheat_enabled(index, false),
+            GameInstanceActions::EjectDisc => {
+                self.ctx.set_disc_ejected(true);
+                Ok(())
+            }
+            GameInstanceActions::InsertDisc => {
+                self.ctx.set_disc_ejected(false);
+                Ok(())
+            }
+            GameInstanceActions::SelectDisc(index) => {
+                self.ctx.select_disc(index);
+                Ok(())
+            }
+            GameInstanceActions::SetControllerType(port, device) => {
+                self.ctx.set_controller_type(port, device);
+                Ok(())
+            }
+            GameInstanceActions::SetCoreOption(key, value) => {
+                self.ctx.set_core_option(key, value);
+                Ok(())
+            }
+            GameInstanceActions::ReportCoreOptions => self.ctx.report_core_options(),
+            GameInstanceActions::SaveCoreOptionOverride(scope) => {
+                self.ctx.save_core_option_override(scope);
+                Ok(())
+            }
+            GameInstanceActions::ClearCoreOptionOverride(scope) => {
+                self.ctx.clear_core_option_override(scope);
+                Ok(())
+            }
+            GameInstanceActions::ReadMemory(address, len) => {
+                self.ctx.read_memory(address, len);
+                Ok(())
+            }
+            GameInstanceActions::WriteMemory(address, data) => {
+                self.ctx.write_memory(address, data);
+                Ok(())
+            }
+            GameInstanceActions::AddMemoryWatch(id, address, len) => {
+                self.ctx.add_memory_watch(id, address, len);
+                Ok(())
+            }
+            GameInstanceActions::RemoveMemoryWatch(id) => {
+                self.ctx.remove_memory_watch(id);
+                Ok(())
+            }
+            GameInstanceActions::StartCheatSearch(width, big_endian) => {
+                self.ctx.start_cheat_search(width, big_endian);
+                Ok(())
+            }
+            GameInstanceActions::FilterCheatSearch(filter) => {
+                self.ctx.filter_cheat_search(filter);
+                Ok(())
+            }
+            GameInstanceActions::AddCheatFromSearch(offset, value, desc) => {
+                self.ctx.add_cheat_from_search(offset, value, desc);
+                Ok(())
+            }
             GameInstanceActions::Pause => self.ctx.pause(),
             GameInstanceActions::Resume => self.ctx.resume(),
+            GameInstanceActions::FrameAdvance(frames) => self.ctx.frame_advance(frames),
+            GameInstanceActions::Rewind(active) => self.ctx.rewind(active),
+            GameInstanceActions::FastForward(active) => self.ctx.fast_forward(active),
+            GameInstanceActions::SlowMotion(active) => self.ctx.slow_motion(active),
+            GameInstanceActions::SetFastForwardRatio(ratio) => {
+                self.ctx.set_fast_forward_ratio(ratio)
+            }
+            GameInstanceActions::SetSlowMotionRatio(ratio) => self.ctx.set_slow_motion_ratio(ratio),
+            GameInstanceActions::SetRunAhead(frames) => self.ctx.set_run_ahead(frames),
+            GameInstanceActions::SetRewindEnabled(enabled) => self.ctx.set_rewind_enabled(enabled),
+            GameInstanceActions::SetRewindInterval(frames) => self.ctx.set_rewind_interval(frames),
+            GameInstanceActions::SetRewindBufferSize(size_mb) => {
+                self.ctx.set_rewind_buffer_size(size_mb)
+            }
+            GameInstanceActions::NewFrame => self.ctx.redraw_request(),
             GameInstanceActions::Exit => {
-                self.destroy_window_and_render_context(event_loop, &self.ctx);
+                self.destroy_window_and_render_context(event_loop);
                 Ok(())
             }
         };
 
         if let Err(e) = result {
-            self.destroy_window_and_render_context(event_loop, &self.ctx);
-            println!("Error: {e:?}");
+            self.destroy_window_and_render_context(event_loop);
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
         }
     }
 }
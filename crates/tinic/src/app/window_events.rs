@@ -1,5 +1,6 @@
 use crate::app::GameInstance;
 use generics::error_handle::ErrorHandle;
+use generics::logger::{self, LogTarget};
 use winit::event::WindowEvent;
 use winit::event_loop::ActiveEventLoop;
 use winit::keyboard::{KeyCode, PhysicalKey};
@@ -12,7 +13,7 @@ impl GameInstance {
     ) {
         let result: Result<(), ErrorHandle> = match event {
             WindowEvent::CloseRequested => {
-                self.destroy_window_and_render_context(event_loop, &self.ctx);
+                self.destroy_window_and_render_context(event_loop);
                 Ok(())
             }
             WindowEvent::RedrawRequested => self.ctx.draw_new_frame(),
@@ -25,16 +26,23 @@ impl GameInstance {
                 self.ctx
                     .update_keyboard_state(event.physical_key, event.state.is_pressed());
 
-                if event.repeat || !event.state.is_pressed() {
-                    return;
-                }
-
                 match event.physical_key {
+                    // o rewind fica ativo somente enquanto a tecla estiver pressionada
+                    PhysicalKey::Code(KeyCode::KeyR) => self.ctx.rewind(event.state.is_pressed()),
+                    PhysicalKey::Code(KeyCode::Tab) => {
+                        self.ctx.fast_forward(event.state.is_pressed())
+                    }
+                    PhysicalKey::Code(KeyCode::Backquote) => {
+                        self.ctx.slow_motion(event.state.is_pressed())
+                    }
+                    _ if event.repeat || !event.state.is_pressed() => Ok(()),
                     PhysicalKey::Code(KeyCode::F1) => self.ctx.save_state(self.default_slot),
                     PhysicalKey::Code(KeyCode::F2) => self.ctx.load_state(self.default_slot),
                     PhysicalKey::Code(KeyCode::F3) => self.ctx.toggle_keyboard_usage(),
+                    PhysicalKey::Code(KeyCode::F4) => self.ctx.toggle_fast_forward(),
                     PhysicalKey::Code(KeyCode::F5) => self.ctx.reset(),
                     PhysicalKey::Code(KeyCode::F8) => self.ctx.toggle_can_request_new_frames(),
+                    PhysicalKey::Code(KeyCode::F9) => self.ctx.frame_advance(1),
                     PhysicalKey::Code(KeyCode::F11) => self.ctx.toggle_full_screen_mode(),
                     _ => Ok(()),
                 }
@@ -43,8 +51,8 @@ impl GameInstance {
         };
 
         if let Err(e) = result {
-            self.destroy_window_and_render_context(event_loop, &self.ctx);
-            println!("Error: {e:?}");
+            self.destroy_window_and_render_context(event_loop);
+            logger::error(LogTarget::Frontend, format!("{e:?}"));
         }
     }
 }
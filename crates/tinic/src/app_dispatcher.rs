@@ -1,3 +1,6 @@
+use crate::app::listener::{
+    CheatSearchFilter, CheatSearchWidth, CoreOptionOverride, MemoryAddressInfo, SaveStateSlot,
+};
 use retro_controllers::RetroGamePad;
 use winit::event_loop::{EventLoopClosed, EventLoopProxy};
 
@@ -6,10 +9,46 @@ pub enum GameInstanceActions {
     ChangeDefaultSlot(usize),
     Pause,
     Resume,
+    FrameAdvance(usize),
+    Rewind(bool),
+    FastForward(bool),
+    SlowMotion(bool),
+    SetFastForwardRatio(f64),
+    SetSlowMotionRatio(f64),
+    SetRunAhead(usize),
+    SetRewindEnabled(bool),
+    SetRewindInterval(usize),
+    SetRewindBufferSize(usize),
     SaveState(usize),
     LoadState(usize),
+    LoadAutoState,
+    ListSaveStates,
+    DeleteSaveState(SaveStateSlot),
+    CopySaveState(SaveStateSlot, SaveStateSlot),
+    RenameSaveState(SaveStateSlot, SaveStateSlot),
+    UndoSaveState,
+    UndoLoadState,
     DisableKeyboard,
     EnableKeyboard,
+    EnableCheat(usize),
+    DisableCheat(usize),
+    EjectDisc,
+    InsertDisc,
+    SelectDisc(usize),
+    SetControllerType(u32, u32),
+    SetCoreOption(String, String),
+    ReportCoreOptions,
+    SaveCoreOptionOverride(CoreOptionOverride),
+    ClearCoreOptionOverride(CoreOptionOverride),
+    ReadMemory(MemoryAddressInfo, usize),
+    WriteMemory(MemoryAddressInfo, Vec<u8>),
+    AddMemoryWatch(u32, MemoryAddressInfo, usize),
+    RemoveMemoryWatch(u32),
+    StartCheatSearch(CheatSearchWidth, bool),
+    FilterCheatSearch(CheatSearchFilter),
+    AddCheatFromSearch(usize, u32, String),
+    #[doc = "enviado pela thread de emulação para acordar o event loop quando um frame fica pronto"]
+    NewFrame,
     Exit,
 }
 
@@ -31,6 +70,10 @@ impl GameInstanceDispatchers {
         self.proxy.send_event(GameInstanceActions::Exit)
     }
 
+    pub(crate) fn new_frame(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::NewFrame)
+    }
+
     pub fn pause(&self) -> Result<(), GameInstanceActionsClosed> {
         self.proxy.send_event(GameInstanceActions::Pause)
     }
@@ -39,6 +82,62 @@ impl GameInstanceDispatchers {
         self.proxy.send_event(GameInstanceActions::Resume)
     }
 
+    #[doc = "Pausa o jogo e roda exatamente a quantidade de frames informada"]
+    pub fn frame_advance(&self, frames: usize) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::FrameAdvance(frames))
+    }
+
+    pub fn rewind(&self, active: bool) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::Rewind(active))
+    }
+
+    pub fn fast_forward(&self, active: bool) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::FastForward(active))
+    }
+
+    pub fn slow_motion(&self, active: bool) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SlowMotion(active))
+    }
+
+    #[doc = "Velocidade usada no fast-forward (ex: 4.0 para 4x), use 0 para não limitar a velocidade"]
+    pub fn set_fast_forward_ratio(&self, ratio: f64) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetFastForwardRatio(ratio))
+    }
+
+    #[doc = "Velocidade usada no slow-motion, entre 0 e 1 (ex: 0.5 para metade da velocidade)"]
+    pub fn set_slow_motion_ratio(&self, ratio: f64) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetSlowMotionRatio(ratio))
+    }
+
+    #[doc = "Quantidade de frames executados à frente para reduzir o input lag, use 0 para desativar"]
+    pub fn set_run_ahead(&self, frames: usize) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetRunAhead(frames))
+    }
+
+    #[doc = "O rewind começa desativado, desativar também apaga o histórico"]
+    pub fn set_rewind_enabled(&self, enabled: bool) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetRewindEnabled(enabled))
+    }
+
+    #[doc = "Quantidade de frames entre cada snapshot do rewind"]
+    pub fn set_rewind_interval(&self, frames: usize) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetRewindInterval(frames))
+    }
+
+    #[doc = "Memória máxima usada pelo histórico do rewind, em MB"]
+    pub fn set_rewind_buffer_size(&self, size_mb: usize) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetRewindBufferSize(size_mb))
+    }
+
     pub fn load_state(&self, slot: usize) -> Result<(), GameInstanceActionsClosed> {
         self.proxy.send_event(GameInstanceActions::LoadState(slot))
     }
@@ -47,6 +146,50 @@ impl GameInstanceDispatchers {
         self.proxy.send_event(GameInstanceActions::SaveState(slot))
     }
 
+    pub fn load_auto_state(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::LoadAutoState)
+    }
+
+    #[doc = "Envia os slots com save state para o listener, com o cabeçalho e a imagem de cada um"]
+    pub fn list_save_states(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::ListSaveStates)
+    }
+
+    pub fn delete_save_state(&self, slot: SaveStateSlot) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::DeleteSaveState(slot))
+    }
+
+    #[doc = "Copia o save state e a imagem, o que existir no slot de destino é substituído"]
+    pub fn copy_save_state(
+        &self,
+        from: SaveStateSlot,
+        to: SaveStateSlot,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::CopySaveState(from, to))
+    }
+
+    #[doc = "Move o save state para outro slot, o que existir no slot de destino é substituído"]
+    pub fn rename_save_state(
+        &self,
+        from: SaveStateSlot,
+        to: SaveStateSlot,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::RenameSaveState(from, to))
+    }
+
+    #[doc = "Devolve o slot sobrescrito pelo último save ao conteúdo anterior"]
+    pub fn undo_save_state(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::UndoSaveState)
+    }
+
+    #[doc = "Volta o jogo ao ponto em que estava antes do último load"]
+    pub fn undo_load_state(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::UndoLoadState)
+    }
+
     pub fn disable_keyboard(&self) -> Result<(), GameInstanceActionsClosed> {
         self.proxy.send_event(GameInstanceActions::DisableKeyboard)
     }
@@ -55,6 +198,138 @@ impl GameInstanceDispatchers {
         self.proxy.send_event(GameInstanceActions::EnableKeyboard)
     }
 
+    pub fn enable_cheat(&self, index: usize) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::EnableCheat(index))
+    }
+
+    pub fn disable_cheat(&self, index: usize) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::DisableCheat(index))
+    }
+
+    pub fn eject_disc(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::EjectDisc)
+    }
+
+    pub fn insert_disc(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy.send_event(GameInstanceActions::InsertDisc)
+    }
+
+    #[doc = "Troca o disco atual pelo disco no índice informado, começando em 0"]
+    pub fn select_disc(&self, index: usize) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SelectDisc(index))
+    }
+
+    #[doc = "Conecta na porta um dos tipos de controle declarados pelo núcleo, a escolha fica salva para o núcleo"]
+    pub fn set_controller_type(
+        &self,
+        port: u32,
+        device: u32,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetControllerType(port, device))
+    }
+
+    #[doc = "O núcleo recebe o novo valor no próximo frame"]
+    pub fn set_core_option(
+        &self,
+        key: String,
+        value: String,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SetCoreOption(key, value))
+    }
+
+    #[doc = "Envia todas as opções do núcleo para o WindowListener, mesmo sem nenhuma mudança"]
+    pub fn report_core_options(&self) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::ReportCoreOptions)
+    }
+
+    #[doc = "Salva os valores atuais das opções no override, as próximas mudanças também vão para ele"]
+    pub fn save_core_option_override(
+        &self,
+        scope: CoreOptionOverride,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::SaveCoreOptionOverride(scope))
+    }
+
+    #[doc = "Apaga o override e volta para os valores da pasta ou do núcleo"]
+    pub fn clear_core_option_override(
+        &self,
+        scope: CoreOptionOverride,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::ClearCoreOptionOverride(scope))
+    }
+
+    #[doc = "O resultado chega pelo WindowListener::memory_read"]
+    pub fn read_memory(
+        &self,
+        address: MemoryAddressInfo,
+        len: usize,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::ReadMemory(address, len))
+    }
+
+    pub fn write_memory(
+        &self,
+        address: MemoryAddressInfo,
+        data: Vec<u8>,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::WriteMemory(address, data))
+    }
+
+    #[doc = "Avisa o WindowListener sempre que os bytes observados mudarem entre um frame e outro, um id repetido substitui o watch anterior"]
+    pub fn add_memory_watch(
+        &self,
+        id: u32,
+        address: MemoryAddressInfo,
+        len: usize,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::AddMemoryWatch(id, address, len))
+    }
+
+    pub fn remove_memory_watch(&self, id: u32) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::RemoveMemoryWatch(id))
+    }
+
+    #[doc = "Tira um snapshot da SYSTEM_RAM, os candidatos chegam pelo WindowListener::cheat_search_changed"]
+    pub fn start_cheat_search(
+        &self,
+        width: CheatSearchWidth,
+        big_endian: bool,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::StartCheatSearch(width, big_endian))
+    }
+
+    pub fn filter_cheat_search(
+        &self,
+        filter: CheatSearchFilter,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::FilterCheatSearch(filter))
+    }
+
+    #[doc = "Salva o candidato como um cheat que mantém o valor informado"]
+    pub fn add_cheat_from_search(
+        &self,
+        offset: usize,
+        value: u32,
+        desc: String,
+    ) -> Result<(), GameInstanceActionsClosed> {
+        self.proxy
+            .send_event(GameInstanceActions::AddCheatFromSearch(offset, value, desc))
+    }
+
     pub fn change_default_slot(&self, slot: usize) -> Result<(), GameInstanceActionsClosed> {
         self.proxy
             .send_event(GameInstanceActions::ChangeDefaultSlot(slot))
@@ -1,4 +1,5 @@
 use crate::app_dispatcher::GameInstanceDispatchers;
+use generics::logger::{self, LogTarget};
 use retro_controllers::devices_manager::DeviceListener;
 use retro_controllers::RetroGamePad;
 
@@ -14,7 +15,7 @@ impl DeviceListener for DeviceHandle {
                 "O {} foi conectado! e não foi possível desconectar o teclado",
                 device.name
             );
-            println!("{msg}")
+            logger::warn(LogTarget::Input, msg)
         }
 
         if self
@@ -26,7 +27,7 @@ impl DeviceListener for DeviceHandle {
                 "O {} foi possível configurar o seu: {}",
                 device.name, device.name
             );
-            println!("{msg}")
+            logger::warn(LogTarget::Input, msg)
         }
 
         self.extern_listener.connected(device);
@@ -38,7 +39,7 @@ impl DeviceListener for DeviceHandle {
                 "O {} foi desconectado! e não foi possível conectar o teclado novamente",
                 device.name
             );
-            println!("{msg}")
+            logger::warn(LogTarget::Input, msg)
         }
 
         self.extern_listener.disconnected(device);
@@ -13,7 +13,9 @@ mod tinic;
 pub use app::{GameInstance, listener::*};
 pub use app_dispatcher::GameInstanceDispatchers;
 pub use generics::error_handle::ErrorHandle;
+pub use generics::logger;
 pub use generics::retro_paths::RetroPaths;
+pub use generics::retro_settings::RetroSettings;
 pub use retro_controllers::{
     RetroController, RetroGamePad,
     devices_manager::{DeviceListener, DeviceStateListener},
@@ -4,7 +4,11 @@ use crate::app_dispatcher::GameInstanceActions;
 use crate::device_listener::DeviceHandle;
 use crate::{
     GameInstanceDispatchers,
-    generics::error_handle::ErrorHandle,
+    generics::{
+        error_handle::ErrorHandle,
+        logger::{self, LogLevel, LogSink, LogTarget},
+        retro_settings::RetroSettings,
+    },
     retro_controllers::{RetroController, devices_manager::DeviceListener},
 };
 use std::sync::Arc;
@@ -26,11 +30,17 @@ pub enum TinicGameInstanceStatus {
     Exit(i32),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct TinicGameInfo {
     pub core: String,
     pub rom: String,
     pub sys_dir: String,
+    #[doc = "Ident do subsystem do núcleo usado para carregar `rom` junto com `extra_roms`, como o Super Game Boy"]
+    pub subsystem: Option<String>,
+    #[doc = "Conteúdos seguintes do subsystem, na mesma ordem informada pelo núcleo. Um caminho vazio pula um conteúdo opcional"]
+    pub extra_roms: Vec<String>,
+    #[doc = "Idioma e nome do usuário informados ao núcleo"]
+    pub settings: RetroSettings,
 }
 
 impl Tinic {
@@ -84,6 +94,27 @@ impl Tinic {
         self.window_listener.replace(Arc::new(listener));
     }
 
+    #[doc = "
+        # Set Log Sink
+
+        Sends every log message from the frontend and from the core to `sink`
+        instead of stdout. `FileSink` and `ChannelSink` are ready to use, and any
+        `Fn(&LogRecord)` closure works as a callback.
+    "]
+    pub fn set_log_sink(&self, sink: impl LogSink + 'static) {
+        logger::set_log_sink(sink);
+    }
+
+    #[doc = "
+        # Set Log Level
+
+        Changes the minimum level accepted for `target` while the game is running.
+        `None` silences the target.
+    "]
+    pub fn set_log_level(&self, target: LogTarget, level: Option<LogLevel>) {
+        logger::set_log_level(target, level);
+    }
+
     #[doc = "
         # Get Game Dispatchers
 
@@ -6,7 +6,8 @@ use std::sync::mpsc;
 use std::sync::mpsc::Receiver;
 use std::thread::sleep;
 use std::time::Duration;
-use tinic::TinicGameInfo;
+use tinic::logger::{self, LogTarget};
+use tinic::{RetroSettings, TinicGameInfo};
 use tinic_ipc_protocol::input::ProtocolInput;
 
 pub(crate) struct StdinReader;
@@ -45,11 +46,20 @@ impl StdinReader {
                             rom_path,
                             core_path,
                             base_retro_path,
+                            subsystem,
+                            extra_rom_paths,
+                            language,
+                            username,
+                            compress_save_states,
+                            load_auto_state,
                         } => {
                             if state.game_loaded.load(Ordering::SeqCst)
                                 && state.game_dispatchers.exit().is_err()
                             {
-                                println!("Não foi possível parar o jogo atual!");
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível parar o jogo atual!",
+                                );
                                 return;
                             }
 
@@ -59,11 +69,22 @@ impl StdinReader {
                                         rom: rom_path,
                                         core: core_path,
                                         sys_dir: base_retro_path,
+                                        subsystem,
+                                        extra_roms: extra_rom_paths,
+                                        settings: RetroSettings {
+                                            language: language.unwrap_or_else(|| {
+                                                RetroSettings::default().language
+                                            }),
+                                            username: username.unwrap_or_default(),
+                                            compress_save_states,
+                                            load_auto_state,
+                                        },
                                     });
                                 }
-                                Err(e) => {
-                                    println!("Erro ao tentar atualizar o game_info: {e}",)
-                                }
+                                Err(e) => logger::error(
+                                    LogTarget::Ipc,
+                                    format!("Erro ao tentar atualizar o game_info: {e}"),
+                                ),
                             }
                         }
                         ProtocolInput::GameClose => {
@@ -72,13 +93,388 @@ impl StdinReader {
                             }
 
                             if state.game_dispatchers.exit().is_err() {
-                                println!("Não foi possível parar o jogo atual!");
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível parar o jogo atual!",
+                                );
+                            }
+                        }
+                        ProtocolInput::FrameAdvance { frames } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.frame_advance(frames).is_err() {
+                                logger::warn(LogTarget::Ipc, "Não foi possível avançar os frames!");
+                            }
+                        }
+                        ProtocolInput::Rewind { active } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.rewind(active).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível alterar o estado do rewind!",
+                                );
+                            }
+                        }
+                        ProtocolInput::SetRewind {
+                            enabled,
+                            interval,
+                            buffer_size_mb,
+                        } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
                             }
+
+                            let dispatchers = &state.game_dispatchers;
+                            let result = interval
+                                .map_or(Ok(()), |frames| dispatchers.set_rewind_interval(frames))
+                                .and_then(|_| {
+                                    buffer_size_mb.map_or(Ok(()), |size| {
+                                        dispatchers.set_rewind_buffer_size(size)
+                                    })
+                                })
+                                .and_then(|_| dispatchers.set_rewind_enabled(enabled));
+
+                            if result.is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível alterar a configuração do rewind!",
+                                );
+                            }
+                        }
+                        ProtocolInput::FastForward { active } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.fast_forward(active).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível alterar o estado do fast-forward!",
+                                );
+                            }
+                        }
+                        ProtocolInput::SlowMotion { active } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.slow_motion(active).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível alterar o estado do slow-motion!",
+                                );
+                            }
+                        }
+                        ProtocolInput::EjectDisc => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.eject_disc().is_err() {
+                                logger::warn(LogTarget::Ipc, "Não foi possível ejetar o disco!");
+                            }
+                        }
+                        ProtocolInput::InsertDisc => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.insert_disc().is_err() {
+                                logger::warn(LogTarget::Ipc, "Não foi possível inserir o disco!");
+                            }
+                        }
+                        ProtocolInput::SelectDisc { index } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.select_disc(index).is_err() {
+                                logger::warn(LogTarget::Ipc, "Não foi possível trocar o disco!");
+                            }
+                        }
+                        ProtocolInput::SetControllerType { port, device } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state
+                                .game_dispatchers
+                                .set_controller_type(port, device)
+                                .is_err()
+                            {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível trocar o tipo do controle!",
+                                );
+                            }
+                        }
+                        ProtocolInput::SetCoreOption { key, value } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.set_core_option(key, value).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível alterar a opção do núcleo!",
+                                );
+                            }
+                        }
+                        ProtocolInput::GetCoreOptions => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.report_core_options().is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível listar as opções do núcleo!",
+                                );
+                            }
+                        }
+                        ProtocolInput::SaveCoreOptionOverride { scope } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state
+                                .game_dispatchers
+                                .save_core_option_override(scope)
+                                .is_err()
+                            {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível salvar o override das opções!",
+                                );
+                            }
+                        }
+                        ProtocolInput::ClearCoreOptionOverride { scope } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state
+                                .game_dispatchers
+                                .clear_core_option_override(scope)
+                                .is_err()
+                            {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível apagar o override das opções!",
+                                );
+                            }
+                        }
+                        ProtocolInput::ReadMemory { address, len } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.read_memory(address, len).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível ler a memória do jogo!",
+                                );
+                            }
+                        }
+                        ProtocolInput::WriteMemory { address, data } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.write_memory(address, data).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível escrever na memória do jogo!",
+                                );
+                            }
+                        }
+                        ProtocolInput::AddMemoryWatch { id, address, len } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state
+                                .game_dispatchers
+                                .add_memory_watch(id, address, len)
+                                .is_err()
+                            {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível observar a memória do jogo!",
+                                );
+                            }
+                        }
+                        ProtocolInput::RemoveMemoryWatch { id } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.remove_memory_watch(id).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível remover o watch da memória!",
+                                );
+                            }
+                        }
+                        ProtocolInput::StartCheatSearch { width, big_endian } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state
+                                .game_dispatchers
+                                .start_cheat_search(width, big_endian)
+                                .is_err()
+                            {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível iniciar a busca de cheats!",
+                                );
+                            }
+                        }
+                        ProtocolInput::FilterCheatSearch { filter } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.filter_cheat_search(filter).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível filtrar a busca de cheats!",
+                                );
+                            }
+                        }
+                        ProtocolInput::AddCheatFromSearch {
+                            offset,
+                            value,
+                            desc,
+                        } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state
+                                .game_dispatchers
+                                .add_cheat_from_search(offset, value, desc)
+                                .is_err()
+                            {
+                                logger::warn(LogTarget::Ipc, "Não foi possível criar o cheat!");
+                            }
+                        }
+                        ProtocolInput::SaveState { slot } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.save_state(slot).is_err() {
+                                logger::warn(LogTarget::Ipc, "Não foi possível salvar o state!");
+                            }
+                        }
+                        ProtocolInput::LoadState { slot } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.load_state(slot).is_err() {
+                                logger::warn(LogTarget::Ipc, "Não foi possível carregar o state!");
+                            }
+                        }
+                        ProtocolInput::LoadAutoState => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.load_auto_state().is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível carregar o auto state!",
+                                );
+                            }
+                        }
+                        ProtocolInput::ListSaveStates => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.list_save_states().is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível listar os save states!",
+                                );
+                            }
+                        }
+                        ProtocolInput::DeleteSaveState { slot } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.delete_save_state(slot).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível apagar o save state!",
+                                );
+                            }
+                        }
+                        ProtocolInput::CopySaveState { from, to } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.copy_save_state(from, to).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível copiar o save state!",
+                                );
+                            }
+                        }
+                        ProtocolInput::RenameSaveState { from, to } => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.rename_save_state(from, to).is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível mover o save state!",
+                                );
+                            }
+                        }
+                        ProtocolInput::UndoSaveState => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.undo_save_state().is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível desfazer o último save!",
+                                );
+                            }
+                        }
+                        ProtocolInput::UndoLoadState => {
+                            if !state.game_loaded.load(Ordering::SeqCst) {
+                                continue;
+                            }
+
+                            if state.game_dispatchers.undo_load_state().is_err() {
+                                logger::warn(
+                                    LogTarget::Ipc,
+                                    "Não foi possível desfazer o último load!",
+                                );
+                            }
+                        }
+                        ProtocolInput::SetLogLevel { target, level } => {
+                            logger::set_log_level(target, level);
                         }
                         ProtocolInput::Exit => {
                             state.running.store(false, Ordering::SeqCst);
                             if state.game_dispatchers.exit().is_err() {
-                                println!("Não foi possível o tinic!");
+                                logger::warn(LogTarget::Ipc, "Não foi possível o tinic!");
                             }
                         }
                     }
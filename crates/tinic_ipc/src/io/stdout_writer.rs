@@ -1,7 +1,11 @@
 use std::io;
 use std::io::Write;
-use tinic::{ErrorHandle, SaveStateInfo};
-use tinic_ipc_protocol::out::ProtocolOut;
+use tinic::{
+    CheatCandidateInfo, CheatInfo, ControllerPortInfo, CoreMessageInfo, CoreOptionCategoryInfo,
+    CoreOptionInfo, DiskInfo, ErrorHandle, InputDescriptorInfo, MemoryAddressInfo, MemoryWatchInfo,
+    SaveRamInfo, SaveStateInfo, SaveStateSlotInfo,
+};
+use tinic_ipc_protocol::out::{LogRecord, ProtocolOut};
 
 pub(crate) fn emit_protocol_event(event: &ProtocolOut) -> Result<(), ErrorHandle> {
     let json = serde_json::to_string(event)
@@ -41,6 +45,69 @@ impl StdoutWriter {
         emit_protocol_event(&ProtocolOut::KeyboardState { using })
     }
 
+    pub fn save_ram_result(info: SaveRamInfo) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::SaveRamResult { info })
+    }
+
+    pub fn cheats_changed(cheats: Vec<CheatInfo>) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::CheatsChanged { cheats })
+    }
+
+    pub fn disk_state_changed(info: DiskInfo) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::DiskStateChanged { info })
+    }
+
+    pub fn frame_advanced(frame_count: u64) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::FrameAdvanced { frame_count })
+    }
+
+    pub fn input_descriptors_changed(
+        descriptors: Vec<InputDescriptorInfo>,
+    ) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::InputDescriptorsChanged { descriptors })
+    }
+
+    pub fn controller_types_changed(ports: Vec<ControllerPortInfo>) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::ControllerTypesChanged { ports })
+    }
+
+    pub fn core_message(message: CoreMessageInfo) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::CoreMessage { message })
+    }
+
+    pub fn core_options_changed(
+        options: Vec<CoreOptionInfo>,
+        categories: Vec<CoreOptionCategoryInfo>,
+    ) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::CoreOptionsChanged {
+            options,
+            categories,
+        })
+    }
+
+    pub fn memory_read(address: MemoryAddressInfo, data: Vec<u8>) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::MemoryRead { address, data })
+    }
+
+    pub fn cheat_search_changed(
+        count: usize,
+        candidates: Vec<CheatCandidateInfo>,
+    ) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::CheatSearchChanged { count, candidates })
+    }
+
+    pub fn memory_watch_triggered(watch: MemoryWatchInfo) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::MemoryWatchTriggered { watch })
+    }
+
+    pub fn save_state_slots_changed(slots: Vec<SaveStateSlotInfo>) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::SaveStateSlotsChanged { slots })
+    }
+
+    pub fn log(record: LogRecord) -> Result<(), ErrorHandle> {
+        emit_protocol_event(&ProtocolOut::Log { record })
+    }
+
     pub fn device_connected(id: String, name: String) -> Result<(), ErrorHandle> {
         emit_protocol_event(&ProtocolOut::DeviceConnected { name, id })
     }
@@ -8,7 +8,9 @@ mod window_event_listener;
 use crate::device_listener::DeviceEventHandle;
 use crate::game_loop::game_loop;
 use crate::io::stdin_reader::StdinReader;
+use crate::io::stdout_writer::StdoutWriter;
 use crate::{app_state::AppState, window_event_listener::WindowEvents};
+use tinic::logger::LogRecord;
 use tinic::{ErrorHandle, Tinic};
 
 fn main() -> Result<(), ErrorHandle> {
@@ -25,6 +27,11 @@ fn main() -> Result<(), ErrorHandle> {
     };
     tinic.set_window_listener(Box::new(window_event));
 
+    // o stdout é o canal do protocolo, então os logs também precisam sair como eventos
+    tinic.set_log_sink(|record: &LogRecord| {
+        let _ = StdoutWriter::log(record.clone());
+    });
+
     // App config
     StdinReader::start(app_state.clone());
 
@@ -1,6 +1,10 @@
 use crate::{app_state::AppStateHandle, io::stdout_writer::StdoutWriter};
 use std::sync::atomic::Ordering;
-use tinic::{GameState, SaveStateInfo, WindowListener, WindowState};
+use tinic::{
+    CheatCandidateInfo, CheatInfo, ControllerPortInfo, CoreMessageInfo, CoreOptionCategoryInfo,
+    CoreOptionInfo, DiskInfo, GameState, InputDescriptorInfo, MemoryAddressInfo, MemoryWatchInfo,
+    SaveRamInfo, SaveStateInfo, SaveStateSlotInfo, WindowListener, WindowState,
+};
 
 pub struct WindowEvents {
     pub app_state: AppStateHandle,
@@ -36,4 +40,56 @@ impl WindowListener for WindowEvents {
     fn keyboard_state(&self, has_using: bool) {
         let _ = StdoutWriter::keyboard_state(has_using);
     }
+
+    fn save_ram_result(&self, info: SaveRamInfo) {
+        let _ = StdoutWriter::save_ram_result(info);
+    }
+
+    fn cheats_changed(&self, cheats: Vec<CheatInfo>) {
+        let _ = StdoutWriter::cheats_changed(cheats);
+    }
+
+    fn disk_state_changed(&self, info: DiskInfo) {
+        let _ = StdoutWriter::disk_state_changed(info);
+    }
+
+    fn frame_advanced(&self, frame_count: u64) {
+        let _ = StdoutWriter::frame_advanced(frame_count);
+    }
+
+    fn input_descriptors_changed(&self, descriptors: Vec<InputDescriptorInfo>) {
+        let _ = StdoutWriter::input_descriptors_changed(descriptors);
+    }
+
+    fn controller_types_changed(&self, ports: Vec<ControllerPortInfo>) {
+        let _ = StdoutWriter::controller_types_changed(ports);
+    }
+
+    fn core_message(&self, message: CoreMessageInfo) {
+        let _ = StdoutWriter::core_message(message);
+    }
+
+    fn core_options_changed(
+        &self,
+        options: Vec<CoreOptionInfo>,
+        categories: Vec<CoreOptionCategoryInfo>,
+    ) {
+        let _ = StdoutWriter::core_options_changed(options, categories);
+    }
+
+    fn memory_read(&self, address: MemoryAddressInfo, data: Vec<u8>) {
+        let _ = StdoutWriter::memory_read(address, data);
+    }
+
+    fn cheat_search_changed(&self, count: usize, candidates: Vec<CheatCandidateInfo>) {
+        let _ = StdoutWriter::cheat_search_changed(count, candidates);
+    }
+
+    fn memory_watch_triggered(&self, watch: MemoryWatchInfo) {
+        let _ = StdoutWriter::memory_watch_triggered(watch);
+    }
+
+    fn save_state_slots_changed(&self, slots: Vec<SaveStateSlotInfo>) {
+        let _ = StdoutWriter::save_state_slots_changed(slots);
+    }
 }
@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use tinic::logger::{LogLevel, LogTarget};
+use tinic::{
+    CheatSearchFilter, CheatSearchWidth, CoreOptionOverride, MemoryAddressInfo, SaveStateSlot,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "cmd", rename_all = "snake_case")]
@@ -7,7 +11,113 @@ pub enum ProtocolInput {
         rom_path: String,
         core_path: String,
         base_retro_path: String,
+        #[serde(default)]
+        subsystem: Option<String>,
+        #[serde(default)]
+        extra_rom_paths: Vec<String>,
+        #[serde(default)]
+        language: Option<String>,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        compress_save_states: bool,
+        #[serde(default)]
+        load_auto_state: bool,
     },
     GameClose,
+    FrameAdvance {
+        frames: usize,
+    },
+    Rewind {
+        active: bool,
+    },
+    SetRewind {
+        enabled: bool,
+        #[serde(default)]
+        interval: Option<usize>,
+        #[serde(default)]
+        buffer_size_mb: Option<usize>,
+    },
+    FastForward {
+        active: bool,
+    },
+    SlowMotion {
+        active: bool,
+    },
+    EjectDisc,
+    InsertDisc,
+    SelectDisc {
+        index: usize,
+    },
+    SetControllerType {
+        port: u32,
+        device: u32,
+    },
+    SetCoreOption {
+        key: String,
+        value: String,
+    },
+    GetCoreOptions,
+    SaveCoreOptionOverride {
+        scope: CoreOptionOverride,
+    },
+    ClearCoreOptionOverride {
+        scope: CoreOptionOverride,
+    },
+    ReadMemory {
+        address: MemoryAddressInfo,
+        len: usize,
+    },
+    WriteMemory {
+        address: MemoryAddressInfo,
+        data: Vec<u8>,
+    },
+    AddMemoryWatch {
+        id: u32,
+        address: MemoryAddressInfo,
+        len: usize,
+    },
+    RemoveMemoryWatch {
+        id: u32,
+    },
+    StartCheatSearch {
+        width: CheatSearchWidth,
+        #[serde(default)]
+        big_endian: bool,
+    },
+    FilterCheatSearch {
+        filter: CheatSearchFilter,
+    },
+    AddCheatFromSearch {
+        offset: usize,
+        value: u32,
+        desc: String,
+    },
+    SaveState {
+        slot: usize,
+    },
+    LoadState {
+        slot: usize,
+    },
+    LoadAutoState,
+    ListSaveStates,
+    DeleteSaveState {
+        slot: SaveStateSlot,
+    },
+    CopySaveState {
+        from: SaveStateSlot,
+        to: SaveStateSlot,
+    },
+    RenameSaveState {
+        from: SaveStateSlot,
+        to: SaveStateSlot,
+    },
+    UndoSaveState,
+    UndoLoadState,
+    SetLogLevel {
+        target: LogTarget,
+        #[serde(default)]
+        level: Option<LogLevel>,
+    },
     Exit,
 }
@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
-pub use tinic::{GameState, SaveStateInfo, WindowState};
+pub use tinic::logger::LogRecord;
+pub use tinic::{
+    CheatCandidateInfo, CheatInfo, ControllerPortInfo, CoreMessageInfo, CoreOptionCategoryInfo,
+    CoreOptionInfo, DiskInfo, GameState, InputDescriptorInfo, MemoryAddressInfo, MemoryWatchInfo,
+    SaveRamInfo, SaveStateInfo, SaveStateSlotInfo, WindowState,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "event", rename_all = "snake_case")]
@@ -32,6 +37,48 @@ pub enum ProtocolOut {
     KeyboardState {
         using: bool,
     },
+    SaveRamResult {
+        info: SaveRamInfo,
+    },
+    CheatsChanged {
+        cheats: Vec<CheatInfo>,
+    },
+    DiskStateChanged {
+        info: DiskInfo,
+    },
+    FrameAdvanced {
+        frame_count: u64,
+    },
+    InputDescriptorsChanged {
+        descriptors: Vec<InputDescriptorInfo>,
+    },
+    ControllerTypesChanged {
+        ports: Vec<ControllerPortInfo>,
+    },
+    CoreMessage {
+        message: CoreMessageInfo,
+    },
+    CoreOptionsChanged {
+        options: Vec<CoreOptionInfo>,
+        categories: Vec<CoreOptionCategoryInfo>,
+    },
+    MemoryRead {
+        address: MemoryAddressInfo,
+        data: Vec<u8>,
+    },
+    MemoryWatchTriggered {
+        watch: MemoryWatchInfo,
+    },
+    CheatSearchChanged {
+        count: usize,
+        candidates: Vec<CheatCandidateInfo>,
+    },
+    SaveStateSlotsChanged {
+        slots: Vec<SaveStateSlotInfo>,
+    },
+    Log {
+        record: LogRecord,
+    },
     // *********
     AppExited,
 }
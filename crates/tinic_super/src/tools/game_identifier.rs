@@ -1,7 +1,9 @@
 use crate::rdb_manager::crc32::crc32_file;
 use generics::error_handle::ErrorHandle;
-use std::path::PathBuf;
+use sevenz_rust::{Password, SevenZReader};
+use std::path::{Path, PathBuf};
 use tokio::fs::{File, read_dir};
+use zip::ZipArchive;
 
 #[derive(Debug)]
 pub struct GameIdentifier {
@@ -13,15 +15,24 @@ pub struct GameIdentifier {
 
 const BLACKLIST_EXTENSIONS: &[&str] = &[
     "txt", "nfo", "jpg", "jpeg", "png", "gif", "xml", "json", "ini", "cfg", "md", "db", "sqlite",
-    "log", "zip", "7z",
+    "log",
 ];
 
+const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "7z"];
+
 impl GameIdentifier {
     pub async fn new(path: PathBuf) -> Result<Self, ErrorHandle> {
         if !Self::is_probably_rom(&path) {
             return Err(ErrorHandle::new("arquivo invalido"));
         }
 
+        if Self::is_archive(&path) {
+            let archive_path = path.clone();
+            return tokio::task::spawn_blocking(move || Self::from_archive(archive_path))
+                .await
+                .map_err(|e| ErrorHandle::new(&e.to_string()))?;
+        }
+
         let file = File::open(path.clone()).await?;
         let size = file.metadata().await?.len();
         let crc = crc32_file(file).await?;
@@ -41,6 +52,73 @@ impl GameIdentifier {
         })
     }
 
+    // zip e 7z já guardam o crc de cada arquivo, então não é preciso descompactar a rom
+    fn from_archive(path: PathBuf) -> Result<Self, ErrorHandle> {
+        let is_7z = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("7z"));
+
+        let entries: Vec<(String, u32, u64)> = if is_7z {
+            let reader = SevenZReader::open(&path, Password::empty())
+                .map_err(|e| ErrorHandle::new(&e.to_string()))?;
+
+            reader
+                .archive()
+                .files
+                .iter()
+                .filter(|entry| !entry.is_directory() && entry.has_crc)
+                .map(|entry| (entry.name().to_string(), entry.crc as u32, entry.size()))
+                .collect()
+        } else {
+            let mut archive = ZipArchive::new(std::fs::File::open(&path)?)
+                .map_err(|e| ErrorHandle::new(&e.to_string()))?;
+            let mut entries = Vec::new();
+
+            for i in 0..archive.len() {
+                let file = archive
+                    .by_index(i)
+                    .map_err(|e| ErrorHandle::new(&e.to_string()))?;
+
+                if !file.is_dir() {
+                    entries.push((file.name().to_string(), file.crc32(), file.size()));
+                }
+            }
+
+            entries
+        };
+
+        let mut roms = entries
+            .into_iter()
+            .filter(|(name, _, _)| Self::is_probably_rom(&PathBuf::from(name)));
+
+        let (name, crc, size) = match (roms.next(), roms.next()) {
+            (Some(rom), None) => rom,
+            _ => {
+                return Err(ErrorHandle::new(
+                    "o arquivo compactado precisa ter apenas uma rom",
+                ));
+            }
+        };
+
+        let file_name = match Path::new(&name).file_prefix().and_then(|n| n.to_str()) {
+            Some(file_name) => file_name.to_string(),
+            None => return Err(ErrorHandle::new("Não foi possivel recuperar o nome da rom")),
+        };
+
+        Ok(Self {
+            path,
+            file_name,
+            crc,
+            size,
+        })
+    }
+
+    fn is_archive(path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| ARCHIVE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+    }
+
     fn is_probably_rom(path: &PathBuf) -> bool {
         let ext = match path.extension().and_then(|e| e.to_str()) {
             Some(ext) => ext.to_lowercase(),